@@ -5,30 +5,76 @@ use tauri::State;
 #[tauri::command]
 pub async fn get_system_info(
     scanner_service: State<'_, ScannerService>,
-) -> Result<crate::services::SystemInfo, String> {
+) -> Result<crate::services::SystemInfo, ScannerError> {
     Ok(scanner_service.get_system_info())
 }
 
+#[tauri::command]
+pub async fn get_platform_capabilities(
+    scanner_service: State<'_, ScannerService>,
+) -> Result<crate::services::PlatformCapabilities, ScannerError> {
+    Ok(scanner_service.get_platform_capabilities())
+}
+
 #[tauri::command]
 pub async fn get_scanners(
+    auto_discover: Option<bool>,
     scanner_service: State<'_, ScannerService>,
-) -> Result<Vec<Scanner>, String> {
-    scanner_service.get_scanners()
+) -> Result<Vec<Scanner>, ScannerError> {
+    if auto_discover.unwrap_or(false) {
+        scanner_service.get_scanners_or_discover().await
+    } else {
+        scanner_service.get_scanners()
+    }
 }
 
 #[tauri::command]
 pub async fn get_scanners_by_system(
     system_type: SystemType,
     scanner_service: State<'_, ScannerService>,
-) -> Result<Vec<Scanner>, String> {
+) -> Result<Vec<Scanner>, ScannerError> {
     scanner_service.get_scanners_by_system(system_type)
 }
 
+#[tauri::command]
+pub async fn find_scanners(
+    filter: ScannerFilter,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<Vec<Scanner>, ScannerError> {
+    scanner_service.find_scanners(&filter)
+}
+
+#[tauri::command]
+pub async fn get_scanners_by_tag(
+    tag: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<Vec<Scanner>, ScannerError> {
+    scanner_service.get_scanners_by_tag(&tag)
+}
+
+#[tauri::command]
+pub async fn add_scanner_tag(
+    scanner_id: String,
+    tag: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<Scanner, ScannerError> {
+    scanner_service.add_scanner_tag(&scanner_id, &tag)
+}
+
+#[tauri::command]
+pub async fn remove_scanner_tag(
+    scanner_id: String,
+    tag: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<Scanner, ScannerError> {
+    scanner_service.remove_scanner_tag(&scanner_id, &tag)
+}
+
 #[tauri::command]
 pub async fn get_scanner(
     scanner_id: String,
     scanner_service: State<'_, ScannerService>,
-) -> Result<Scanner, String> {
+) -> Result<Scanner, ScannerError> {
     scanner_service.get_scanner(&scanner_id)
 }
 
@@ -36,7 +82,7 @@ pub async fn get_scanner(
 pub async fn get_scanner_capabilities(
     scanner_id: String,
     scanner_service: State<'_, ScannerService>,
-) -> Result<ScannerCapabilities, String> {
+) -> Result<ScannerCapabilities, ScannerError> {
     scanner_service.get_scanner_capabilities(&scanner_id)
 }
 
@@ -44,56 +90,354 @@ pub async fn get_scanner_capabilities(
 pub async fn test_scanner_connection(
     scanner_id: String,
     scanner_service: State<'_, ScannerService>,
-) -> Result<bool, String> {
+) -> Result<bool, ScannerError> {
     scanner_service.test_scanner_connection(&scanner_id).await
 }
 
+#[tauri::command]
+pub async fn test_scanner_connection_detailed(
+    scanner_id: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<crate::services::ConnectionTestResult, ScannerError> {
+    scanner_service
+        .test_scanner_connection_detailed(&scanner_id)
+        .await
+}
+
 #[tauri::command]
 pub async fn create_scan_job(
     scanner_id: String,
     document_type: DocumentType,
     scan_settings: ScanSettings,
+    then: Option<PostAction>,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<String, ScannerError> {
+    scanner_service
+        .create_scan_job(scanner_id, document_type, scan_settings, then)
+        .await
+}
+
+#[tauri::command]
+pub async fn validate_scan_job(
+    scanner_id: String,
+    document_type: DocumentType,
+    settings: ScanSettings,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.validate_scan_job(&scanner_id, &document_type, &settings)
+}
+
+#[tauri::command]
+pub async fn append_to_job(
+    existing_job_id: String,
+    new_document_type: DocumentType,
+    settings: ScanSettings,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<String, ScannerError> {
+    scanner_service
+        .append_to_job(&existing_job_id, new_document_type, settings)
+        .await
+}
+
+#[tauri::command]
+pub async fn create_batch_jobs(
+    scanner_id: String,
+    requests: Vec<(DocumentType, ScanSettings)>,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<Vec<String>, ScannerError> {
+    scanner_service
+        .create_batch_jobs(scanner_id, requests)
+        .await
+}
+
+#[tauri::command]
+pub async fn save_profile(
+    profile: ScanProfile,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.save_profile(profile)
+}
+
+#[tauri::command]
+pub async fn list_profiles(
+    scanner_service: State<'_, ScannerService>,
+) -> Result<Vec<ScanProfile>, ScannerError> {
+    scanner_service.list_profiles()
+}
+
+#[tauri::command]
+pub async fn delete_profile(
+    profile_name: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.delete_profile(&profile_name)
+}
+
+#[tauri::command]
+pub async fn create_job_from_profile(
+    scanner_id: String,
+    profile_name: String,
     scanner_service: State<'_, ScannerService>,
-) -> Result<String, String> {
+) -> Result<String, ScannerError> {
     scanner_service
-        .create_scan_job(scanner_id, document_type, scan_settings)
+        .create_job_from_profile(scanner_id, &profile_name)
         .await
 }
 
+#[tauri::command]
+pub async fn set_job_document_type(
+    job_id: String,
+    document_type: DocumentType,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.set_job_document_type(&job_id, document_type)
+}
+
+#[tauri::command]
+pub async fn set_job_priority(
+    job_id: String,
+    priority: JobPriority,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.set_job_priority(&job_id, priority)
+}
+
+#[tauri::command]
+pub async fn update_job_settings(
+    job_id: String,
+    scan_settings: ScanSettings,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.update_job_settings(&job_id, scan_settings)
+}
+
+#[tauri::command]
+pub async fn describe_scan_pipeline(
+    scanner_id: String,
+    document_type: DocumentType,
+    settings: ScanSettings,
+    then: Option<PostAction>,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<Vec<String>, ScannerError> {
+    scanner_service.describe_scan_pipeline(&scanner_id, document_type, settings, then)
+}
+
+#[tauri::command]
+pub async fn retry_scan_job(
+    job_id: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<String, ScannerError> {
+    scanner_service.retry_scan_job(&job_id).await
+}
+
 #[tauri::command]
 pub async fn start_scan_job(
     job_id: String,
     scanner_service: State<'_, ScannerService>,
-) -> Result<(), String> {
+) -> Result<(), ScannerError> {
     scanner_service.start_scan_job(&job_id).await
 }
 
+#[tauri::command]
+pub async fn schedule_scan_job(
+    job_id: String,
+    start_at: chrono::DateTime<chrono::Utc>,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.schedule_scan_job(&job_id, start_at)
+}
+
+#[tauri::command]
+pub async fn cancel_schedule(
+    job_id: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.cancel_schedule(&job_id)
+}
+
+#[tauri::command]
+pub async fn start_batch(
+    job_ids: Vec<String>,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.start_batch(&job_ids).await
+}
+
+#[tauri::command]
+pub async fn run_test_scan(
+    scanner_id: String,
+    scan_settings: Option<ScanSettings>,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<String, ScannerError> {
+    scanner_service
+        .run_test_scan(scanner_id, scan_settings)
+        .await
+}
+
+#[tauri::command]
+pub async fn run_preview_scan(
+    scanner_id: String,
+    document_type: DocumentType,
+    scan_settings: ScanSettings,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<String, ScannerError> {
+    scanner_service
+        .run_preview_scan(scanner_id, document_type, scan_settings)
+        .await
+}
+
+#[tauri::command]
+pub async fn scan_from_preview(
+    job_id: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<String, ScannerError> {
+    scanner_service.scan_from_preview(&job_id).await
+}
+
+#[tauri::command]
+pub async fn set_max_concurrent(
+    scanner_id: String,
+    max_concurrent: usize,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.set_max_concurrent(&scanner_id, max_concurrent)
+}
+
+#[tauri::command]
+pub async fn set_generation_concurrency(
+    concurrency: usize,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.set_generation_concurrency(concurrency)
+}
+
+#[tauri::command]
+pub async fn set_max_jobs(
+    max_jobs: usize,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.set_max_jobs(max_jobs)
+}
+
 #[tauri::command]
 pub async fn get_scan_job(
     job_id: String,
     scanner_service: State<'_, ScannerService>,
-) -> Result<ScanJob, String> {
+) -> Result<ScanJob, ScannerError> {
     scanner_service.get_scan_job(&job_id)
 }
 
+#[tauri::command]
+pub async fn get_scanner_for_job(
+    job_id: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<Scanner, ScannerError> {
+    scanner_service.get_scanner_for_job(&job_id)
+}
+
+#[tauri::command]
+pub async fn get_job_history(
+    job_id: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<Vec<StatusTransition>, ScannerError> {
+    scanner_service.get_job_history(&job_id)
+}
+
 #[tauri::command]
 pub async fn get_all_jobs(
     scanner_service: State<'_, ScannerService>,
-) -> Result<Vec<ScanJob>, String> {
+) -> Result<Vec<ScanJob>, ScannerError> {
     scanner_service.get_all_jobs()
 }
 
+#[tauri::command]
+pub async fn export_history(
+    path: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.export_history(std::path::Path::new(&path))
+}
+
+#[tauri::command]
+pub async fn import_history(
+    path: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<usize, ScannerError> {
+    scanner_service.import_history(std::path::Path::new(&path))
+}
+
 #[tauri::command]
 pub async fn cancel_scan_job(
     job_id: String,
     scanner_service: State<'_, ScannerService>,
-) -> Result<(), String> {
+) -> Result<(), ScannerError> {
     scanner_service.cancel_scan_job(&job_id)
 }
 
 #[tauri::command]
-pub async fn get_document_types() -> Result<Vec<DocumentType>, String> {
+pub async fn cancel_all_jobs(
+    scanner_service: State<'_, ScannerService>,
+) -> Result<Vec<String>, ScannerError> {
+    scanner_service.cancel_all_jobs()
+}
+
+#[tauri::command]
+pub async fn pause_scan_job(
+    job_id: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.pause_scan_job(&job_id)
+}
+
+#[tauri::command]
+pub async fn resume_scan_job(
+    job_id: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.resume_scan_job(&job_id)
+}
+
+#[tauri::command]
+pub async fn delete_job(
+    job_id: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.delete_job(&job_id)
+}
+
+#[tauri::command]
+pub async fn clear_completed_jobs(
+    scanner_service: State<'_, ScannerService>,
+) -> Result<Vec<String>, ScannerError> {
+    scanner_service.clear_completed_jobs()
+}
+
+#[tauri::command]
+pub async fn get_queue_status(
+    scanner_service: State<'_, ScannerService>,
+) -> Result<Vec<crate::services::QueueStatus>, ScannerError> {
+    scanner_service.get_queue_status()
+}
+
+#[tauri::command]
+pub async fn get_scanner_metrics(
+    scanner_id: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<crate::services::ScannerMetrics, ScannerError> {
+    scanner_service.get_scanner_metrics(&scanner_id)
+}
+
+#[tauri::command]
+pub async fn get_job_statistics(
+    scanner_service: State<'_, ScannerService>,
+) -> Result<crate::services::JobStatistics, ScannerError> {
+    scanner_service.get_job_statistics()
+}
+
+#[tauri::command]
+pub async fn get_document_types() -> Result<Vec<DocumentType>, ScannerError> {
     Ok(vec![
+        DocumentType::Auto,
         DocumentType::Text,
         DocumentType::Image,
         DocumentType::Mixed,
@@ -102,11 +446,12 @@ pub async fn get_document_types() -> Result<Vec<DocumentType>, String> {
         DocumentType::Receipt,
         DocumentType::Contract,
         DocumentType::Invoice,
+        DocumentType::Barcode,
     ])
 }
 
 #[tauri::command]
-pub async fn get_color_modes() -> Result<Vec<ColorMode>, String> {
+pub async fn get_color_modes() -> Result<Vec<ColorMode>, ScannerError> {
     Ok(vec![
         ColorMode::BlackAndWhite,
         ColorMode::Grayscale,
@@ -115,17 +460,21 @@ pub async fn get_color_modes() -> Result<Vec<ColorMode>, String> {
 }
 
 #[tauri::command]
-pub async fn get_paper_sizes() -> Result<Vec<PaperSize>, String> {
+pub async fn get_paper_sizes() -> Result<Vec<PaperSize>, ScannerError> {
     Ok(vec![
         PaperSize::A4,
         PaperSize::A3,
+        PaperSize::A5,
+        PaperSize::B4,
+        PaperSize::B5,
         PaperSize::Letter,
         PaperSize::Legal,
+        PaperSize::Auto,
     ])
 }
 
 #[tauri::command]
-pub async fn get_output_formats() -> Result<Vec<OutputFormat>, String> {
+pub async fn get_output_formats() -> Result<Vec<OutputFormat>, ScannerError> {
     Ok(vec![
         OutputFormat::Pdf,
         OutputFormat::Jpeg,
@@ -135,7 +484,12 @@ pub async fn get_output_formats() -> Result<Vec<OutputFormat>, String> {
 }
 
 #[tauri::command]
-pub async fn get_scanner_types() -> Result<Vec<ScannerType>, String> {
+pub async fn get_pdf_a_levels() -> Result<Vec<PdfALevel>, ScannerError> {
+    Ok(vec![PdfALevel::PdfA1b, PdfALevel::PdfA2b])
+}
+
+#[tauri::command]
+pub async fn get_scanner_types() -> Result<Vec<ScannerType>, ScannerError> {
     Ok(vec![
         ScannerType::Flatbed,
         ScannerType::DocumentFeeder,
@@ -147,22 +501,41 @@ pub async fn get_scanner_types() -> Result<Vec<ScannerType>, String> {
 }
 
 #[tauri::command]
-pub async fn get_default_scan_settings() -> Result<ScanSettings, String> {
+pub async fn get_supported_locales() -> Result<Vec<LocaleInfo>, ScannerError> {
+    use crate::generators::ScanGenerator;
+
+    Ok(ScanGenerator::get_supported_locales())
+}
+
+#[tauri::command]
+pub async fn estimate_file_size(
+    document_type: DocumentType,
+    settings: ScanSettings,
+) -> Result<u64, ScannerError> {
+    use crate::generators::ScanGenerator;
+
+    // An Auto paper size is estimated on the paper the document type scans on
+    let settings = settings.with_paper_for(document_type);
+    ScanGenerator::estimate_file_size(&settings).map_err(ScannerError::InvalidSettings)
+}
+
+#[tauri::command]
+pub async fn get_default_scan_settings() -> Result<ScanSettings, ScannerError> {
     Ok(ScanSettings::default())
 }
 
 #[tauri::command]
-pub async fn open_output_directory() -> Result<String, String> {
+pub async fn open_output_directory() -> Result<String, ScannerError> {
     use crate::generators::ScanGenerator;
 
-    let output_dir = ScanGenerator::get_output_directory()?;
+    let output_dir = ScanGenerator::get_output_directory().map_err(ScannerError::IoError)?;
 
     #[cfg(target_os = "macos")]
     {
         std::process::Command::new("open")
             .arg(&output_dir)
             .spawn()
-            .map_err(|e| format!("Failed to open directory: {}", e))?;
+            .map_err(|e| ScannerError::IoError(format!("Failed to open directory: {}", e)))?;
     }
 
     #[cfg(target_os = "windows")]
@@ -170,7 +543,7 @@ pub async fn open_output_directory() -> Result<String, String> {
         std::process::Command::new("explorer")
             .arg(&output_dir)
             .spawn()
-            .map_err(|e| format!("Failed to open directory: {}", e))?;
+            .map_err(|e| ScannerError::IoError(format!("Failed to open directory: {}", e)))?;
     }
 
     #[cfg(target_os = "linux")]
@@ -178,67 +551,161 @@ pub async fn open_output_directory() -> Result<String, String> {
         std::process::Command::new("xdg-open")
             .arg(&output_dir)
             .spawn()
-            .map_err(|e| format!("Failed to open directory: {}", e))?;
+            .map_err(|e| ScannerError::IoError(format!("Failed to open directory: {}", e)))?;
     }
 
     Ok(format!("Opened directory: {}", output_dir.display()))
 }
 
+#[tauri::command]
+pub async fn set_output_directory(path: String) -> Result<String, ScannerError> {
+    use crate::generators::ScanGenerator;
+
+    let output_dir = ScanGenerator::set_output_directory(std::path::Path::new(&path))
+        .map_err(ScannerError::InvalidSettings)?;
+    Ok(output_dir.display().to_string())
+}
+
+#[tauri::command]
+pub async fn list_output_files() -> Result<Vec<OutputFileInfo>, ScannerError> {
+    use crate::generators::ScanGenerator;
+
+    ScanGenerator::list_output_files().map_err(ScannerError::IoError)
+}
+
+#[tauri::command]
+pub async fn delete_output_file(path: String) -> Result<(), ScannerError> {
+    use crate::generators::ScanGenerator;
+
+    ScanGenerator::delete_output_file(std::path::Path::new(&path)).map_err(ScannerError::IoError)
+}
+
 #[tauri::command]
 pub async fn get_scan_result(
     job_id: String,
     scanner_service: State<'_, ScannerService>,
-) -> Result<Option<ScanResult>, String> {
+) -> Result<Option<ScanResult>, ScannerError> {
+    scanner_service.get_scan_result(&job_id)
+}
+
+#[tauri::command]
+pub async fn invalidate_result_cache(
+    job_id: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.invalidate_result_cache(&job_id)
+}
+
+#[tauri::command]
+pub async fn merge_jobs(
+    job_ids: Vec<String>,
+    output_name: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<ScanResult, ScannerError> {
+    scanner_service.merge_jobs(&job_ids, &output_name)
+}
+
+#[tauri::command]
+pub async fn get_scan_output(
+    job_id: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<ScanOutput, ScannerError> {
     let job = scanner_service.get_scan_job(&job_id)?;
-    Ok(job.scan_result)
+    let preview_available = job
+        .scan_result
+        .as_ref()
+        .and_then(|result| result.preview_path.as_ref())
+        .is_some_and(|path| path.exists());
+
+    Ok(ScanOutput {
+        job_id: job.id,
+        output_path: job
+            .scan_result
+            .as_ref()
+            .map(|result| result.file_path.clone()),
+        result: job.scan_result,
+        preview_available,
+    })
 }
 
 #[tauri::command]
-pub async fn preview_scan_file(file_path: String) -> Result<(), String> {
-    let path = std::path::Path::new(&file_path);
+pub async fn reorder_pages(
+    job_id: String,
+    new_order: Vec<u32>,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<ScanResult, ScannerError> {
+    scanner_service.reorder_pages(&job_id, &new_order)
+}
 
-    if !path.exists() {
-        return Err("File does not exist".to_string());
-    }
+#[tauri::command]
+pub async fn read_scan_file(
+    job_id: String,
+    offset: Option<u64>,
+    length: Option<u64>,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<ScanFileChunk, ScannerError> {
+    scanner_service.read_scan_file(&job_id, offset.unwrap_or(0), length)
+}
 
-    #[cfg(target_os = "macos")]
-    {
-        std::process::Command::new("open")
-            .arg(path)
-            .spawn()
-            .map_err(|e| format!("Failed to open file: {}", e))?;
-    }
+#[tauri::command]
+pub async fn generate_preview(
+    job_id: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<std::path::PathBuf, ScannerError> {
+    scanner_service.generate_preview(&job_id)
+}
 
-    #[cfg(target_os = "windows")]
-    {
-        std::process::Command::new("cmd")
-            .args(&["/c", "start", "", &file_path])
-            .spawn()
-            .map_err(|e| format!("Failed to open file: {}", e))?;
-    }
+#[tauri::command]
+pub async fn verify_scan_result(
+    job_id: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<bool, ScannerError> {
+    scanner_service.verify_scan_result(&job_id)
+}
 
-    #[cfg(target_os = "linux")]
-    {
-        std::process::Command::new("xdg-open")
-            .arg(path)
-            .spawn()
-            .map_err(|e| format!("Failed to open file: {}", e))?;
-    }
+#[tauri::command]
+pub async fn ocr_scan(
+    job_id: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<String, ScannerError> {
+    scanner_service.ocr_scan(&job_id).await
+}
 
-    Ok(())
+#[tauri::command]
+pub async fn preview_scan_file(
+    file_path: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.preview_scan_file(std::path::Path::new(&file_path))
 }
 
 #[tauri::command]
 pub async fn discover_scanners(
     scanner_service: State<'_, ScannerService>,
-) -> Result<Vec<Scanner>, String> {
+) -> Result<Vec<Scanner>, ScannerError> {
     scanner_service.discover_scanners().await
 }
 
+#[tauri::command]
+pub async fn discover_network_scanners(
+    subnet: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<Vec<Scanner>, ScannerError> {
+    scanner_service.discover_network_scanners(&subnet).await
+}
+
+#[tauri::command]
+pub async fn start_scanner_discovery(
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.start_scanner_discovery();
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn get_all_scanners(
     scanner_service: State<'_, ScannerService>,
-) -> Result<Vec<Scanner>, String> {
+) -> Result<Vec<Scanner>, ScannerError> {
     scanner_service.get_all_scanners()
 }
 
@@ -246,29 +713,89 @@ pub async fn get_all_scanners(
 pub async fn add_scanner(
     scanner: Scanner,
     scanner_service: State<'_, ScannerService>,
-) -> Result<String, String> {
+) -> Result<String, ScannerError> {
     scanner_service.add_scanner(scanner).await
 }
 
+#[tauri::command]
+pub async fn add_network_scanner(
+    name: String,
+    address: String,
+    scanner_type: ScannerType,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<String, ScannerError> {
+    scanner_service
+        .add_network_scanner(name, &address, scanner_type)
+        .await
+}
+
+#[tauri::command]
+pub async fn clone_scanner(
+    scanner_id: String,
+    new_name: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<String, ScannerError> {
+    scanner_service.clone_scanner(&scanner_id, &new_name).await
+}
+
 #[tauri::command]
 pub async fn remove_scanner(
     scanner_id: String,
     scanner_service: State<'_, ScannerService>,
-) -> Result<(), String> {
+) -> Result<(), ScannerError> {
     scanner_service.remove_scanner(&scanner_id)
 }
 
 #[tauri::command]
 pub async fn simulate_scanner_events(
     scanner_service: State<'_, ScannerService>,
-) -> Result<(), String> {
+) -> Result<(), ScannerError> {
     scanner_service.simulate_scanner_events().await
 }
 
+#[tauri::command]
+pub async fn set_simulation_params(
+    params: crate::services::SimulationParams,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.set_simulation_params(params)
+}
+
+#[tauri::command]
+pub async fn start_event_loop(
+    interval_secs: u64,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.start_event_loop(std::time::Duration::from_secs(interval_secs))
+}
+
+#[tauri::command]
+pub async fn stop_event_loop(
+    scanner_service: State<'_, ScannerService>,
+) -> Result<(), ScannerError> {
+    scanner_service.stop_event_loop()
+}
+
+#[tauri::command]
+pub async fn reconnect_scanner(
+    scanner_id: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<ScannerStatus, ScannerError> {
+    scanner_service.reconnect_scanner(&scanner_id).await
+}
+
 #[tauri::command]
 pub async fn reset_scanner_status(
     scanner_id: String,
     scanner_service: State<'_, ScannerService>,
-) -> Result<(), String> {
+) -> Result<(), ScannerError> {
     scanner_service.reset_scanner_status(&scanner_id)
 }
+
+#[tauri::command]
+pub async fn clear_jam(
+    scanner_id: String,
+    scanner_service: State<'_, ScannerService>,
+) -> Result<Vec<String>, ScannerError> {
+    scanner_service.clear_jam(&scanner_id)
+}
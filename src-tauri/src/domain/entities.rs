@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Scanner {
@@ -9,6 +12,22 @@ pub struct Scanner {
     pub status: ScannerStatus,
     pub capabilities: ScannerCapabilities,
     pub system_type: SystemType,
+    #[serde(default)]
+    pub source: ScannerSource,
+    #[serde(default)]
+    pub serial_number: Option<String>, // Reported by discovered devices
+    #[serde(default)]
+    pub network_address: Option<String>, // IP address or host name of network scanners
+    #[serde(default)]
+    pub tags: Vec<String>, // Groupings such as a location, kept across discovery runs
+}
+
+/// How a scanner got into the list; discovery only ever replaces the devices it found
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum ScannerSource {
+    #[default]
+    Discovered,
+    Manual, // Registered through add_scanner
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -21,10 +40,11 @@ pub enum ScannerType {
     PhotoScanner,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ScannerStatus {
     Available,
     Busy,
+    WarmingUp, // Just powered on or reconnected, takes jobs once warm
     Offline,
     Error(String),
 }
@@ -36,6 +56,74 @@ pub struct ScannerCapabilities {
     pub paper_sizes: Vec<PaperSize>,
     pub has_duplex: bool,
     pub has_adf: bool, // Automatic Document Feeder
+    #[serde(default)]
+    pub adf_min_size: Option<PageDimensions>,
+    #[serde(default)]
+    pub adf_max_size: Option<PageDimensions>,
+    // Discrete DPI steps and file formats offered; empty lists mean unrestricted
+    #[serde(default)]
+    pub supported_resolutions: Vec<u32>,
+    #[serde(default)]
+    pub supported_formats: Vec<OutputFormat>,
+}
+
+impl ScannerCapabilities {
+    /// Common scanner DPI steps, from draft quality to film scanning
+    pub const STANDARD_RESOLUTIONS: &'static [u32] =
+        &[75, 100, 150, 200, 300, 600, 1200, 2400, 4800, 6400];
+
+    /// The standard DPI steps up to and including `max_resolution`
+    pub fn standard_resolutions(max_resolution: u32) -> Vec<u32> {
+        Self::STANDARD_RESOLUTIONS
+            .iter()
+            .copied()
+            .filter(|&resolution| resolution <= max_resolution)
+            .collect()
+    }
+}
+
+/// Criteria for finding scanners; unset fields match every scanner
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScannerFilter {
+    #[serde(default)]
+    pub scanner_type: Option<ScannerType>,
+    #[serde(default)]
+    pub status: Option<ScannerStatus>,
+    #[serde(default)]
+    pub min_resolution: Option<u32>,
+    #[serde(default)]
+    pub requires_duplex: Option<bool>,
+    #[serde(default)]
+    pub requires_adf: Option<bool>,
+}
+
+impl ScannerFilter {
+    /// True when the scanner meets every criterion. An `Error` status matches
+    /// scanners in any error state, whatever the message.
+    pub fn matches(&self, scanner: &Scanner) -> bool {
+        let type_matches = self
+            .scanner_type
+            .is_none_or(|scanner_type| scanner.scanner_type == scanner_type);
+        let status_matches = self.status.as_ref().is_none_or(|status| {
+            std::mem::discriminant(status) == std::mem::discriminant(&scanner.status)
+        });
+        let resolution_matches = self
+            .min_resolution
+            .is_none_or(|min| scanner.capabilities.max_resolution >= min);
+
+        type_matches
+            && status_matches
+            && resolution_matches
+            && (self.requires_duplex != Some(true) || scanner.capabilities.has_duplex)
+            && (self.requires_adf != Some(true) || scanner.capabilities.has_adf)
+    }
+}
+
+/// Physical page dimensions in millimetres
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PageDimensions {
+    pub width: u32,
+    pub height: u32,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -45,13 +133,81 @@ pub enum ColorMode {
     Color,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum PaperSize {
     A4,
     A3,
+    A5,
+    B4, // ISO B-series, not the slightly larger JIS B sizes
+    B5,
     Letter,
     Legal,
     Custom { width: u32, height: u32 },
+    Auto, // Picked from the document type when the job starts scanning, see for_document_type
+}
+
+impl PaperSize {
+    /// Largest width or height accepted for a custom paper size, in millimetres
+    pub const MAX_CUSTOM_MM: u32 = 1200;
+
+    /// Check that a custom size has usable dimensions; standard sizes always pass
+    pub fn validate(&self) -> Result<(), String> {
+        if let PaperSize::Custom { width, height } = *self {
+            if width == 0 || height == 0 {
+                return Err("Custom paper size must have a non-zero width and height".to_string());
+            }
+            if width > Self::MAX_CUSTOM_MM || height > Self::MAX_CUSTOM_MM {
+                return Err(format!(
+                    "Custom paper size {}x{} mm exceeds the {} mm maximum",
+                    width,
+                    height,
+                    Self::MAX_CUSTOM_MM
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The paper a document of this type is usually on
+    pub fn for_document_type(document_type: DocumentType) -> Self {
+        match document_type {
+            DocumentType::BusinessCard => PaperSize::Custom {
+                width: 85,
+                height: 55,
+            },
+            DocumentType::Photo => PaperSize::Custom {
+                width: 102,
+                height: 152,
+            },
+            // An 80 mm till roll
+            DocumentType::Receipt => PaperSize::Custom {
+                width: 80,
+                height: 200,
+            },
+            _ => PaperSize::A4,
+        }
+    }
+
+    /// Width and height in millimetres; Auto measures as A4 until it is resolved
+    pub fn dimensions_mm(&self) -> (f32, f32) {
+        match self {
+            PaperSize::A4 | PaperSize::Auto => (210.0, 297.0),
+            PaperSize::A3 => (297.0, 420.0),
+            PaperSize::A5 => (148.0, 210.0),
+            PaperSize::B4 => (250.0, 353.0),
+            PaperSize::B5 => (176.0, 250.0),
+            PaperSize::Letter => (215.9, 279.4),
+            PaperSize::Legal => (215.9, 355.6),
+            PaperSize::Custom { width, height } => (*width as f32, *height as f32),
+        }
+    }
+
+    /// Width and height in pixels when scanned at the given resolution
+    pub fn pixel_dimensions(&self, dpi: u32) -> (u32, u32) {
+        let (width_mm, height_mm) = self.dimensions_mm();
+        let to_pixels = |mm: f32| ((mm / 25.4 * dpi as f32).round() as u32).max(1);
+        (to_pixels(width_mm), to_pixels(height_mm))
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -80,6 +236,8 @@ pub enum DocumentType {
     Receipt,
     Contract,
     Invoice,
+    Barcode, // Label with a QR code at the top of the page
+    Auto,    // Detected from the scanned text once the file is generated
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -89,10 +247,69 @@ pub struct ScanJob {
     pub document_type: DocumentType,
     pub scan_settings: ScanSettings,
     pub status: JobStatus,
+    // Every status the job has entered, oldest first, starting with Pending
+    #[serde(default)]
+    pub status_history: Vec<StatusTransition>,
     pub progress: f32, // 0.0 to 1.0
+    // Passes over the document so far, automatic retries after hardware errors included
+    #[serde(default)]
+    pub attempts: u32,
+    // Projected from scanning speed so far; None unless the job is running
+    #[serde(default)]
+    pub estimated_completion: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub eta_secs: Option<u64>,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
     pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
     pub scan_result: Option<ScanResult>,
+    // Resolved type of an Auto job once its scan completes
+    #[serde(default)]
+    pub detected_type: Option<DocumentType>,
+    // Pages salvaged from a failed multi-page generation
+    #[serde(default)]
+    pub partial_result: Option<ScanResult>,
+    pub is_test_scan: bool,
+    // Low-resolution proof written to the temp directory, left out of statistics and history
+    #[serde(default)]
+    pub is_preview: bool,
+    pub then: Option<PostAction>,
+    pub post_action_outcome: Option<PostActionOutcome>,
+    // Why the scanner paused the job, e.g. a paper jam; None for a pause the user asked for
+    #[serde(default)]
+    pub pause_reason: Option<String>,
+    // When schedule_scan_job will start the pending job; None starts it on request
+    #[serde(default)]
+    pub scheduled_at: Option<chrono::DateTime<chrono::Utc>>,
+    // Shared with every clone of the job so the running scan task sees cancellation
+    #[serde(skip)]
+    cancel_requested: Arc<AtomicBool>,
+    #[serde(skip)]
+    pause_requested: Arc<AtomicBool>,
+}
+
+/// Follow-up work run automatically after a scan completes successfully
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PostAction {
+    GenerateThumbnail,
+    Export(std::path::PathBuf),
+    RunComposite(Vec<String>), // Other job IDs to combine with this one
+    AppendTo(String),          // Completed PDF job whose file gets this scan's pages
+}
+
+/// A status a job entered and when it entered it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusTransition {
+    pub status: JobStatus,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostActionOutcome {
+    pub action: PostAction,
+    pub output_path: Option<std::path::PathBuf>,
+    pub error: Option<String>,
+    pub completed_at: chrono::DateTime<chrono::Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -103,6 +320,182 @@ pub struct ScanSettings {
     pub duplex: bool,
     pub output_format: OutputFormat,
     pub quality: u8, // 1-100
+    #[serde(default)]
+    pub input_source: InputSource,
+    #[serde(default)]
+    pub locale: Option<String>, // Language and formats of sample content, None for en-US
+    #[serde(default)]
+    pub include_exif: bool, // JPEG output only
+    #[serde(default)]
+    pub background_color: Option<[u8; 3]>, // RGB paper tint, None means white
+    #[serde(default)]
+    pub pdf_a_level: Option<PdfALevel>, // PDF output only
+    #[serde(default)]
+    pub upload: Option<UploadTarget>, // Sent after the file is generated
+    #[serde(default = "default_page_count")]
+    pub page_count: u32, // PDF and TIFF output only
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64, // Scanning, pauses included, or generating the file for longer than this fails the job
+    #[serde(default)]
+    pub output_dir: Option<std::path::PathBuf>, // None uses the Scanner Tool Outputs folder
+    #[serde(default)]
+    pub watermark: Option<String>, // PDF output only, stamped with a job ID footer
+    #[serde(default)]
+    pub priority: JobPriority, // Order among the pending jobs queued on a scanner
+    #[serde(default)]
+    pub compression: CompressionLevel, // Lossless encoder effort, independent of quality
+    #[serde(default)]
+    pub post_processing: PostProcessing, // Applied while the job is Processing
+    #[serde(default)]
+    pub scan_area: Option<ScanArea>, // Part of the page to scan, None scans all of it
+    #[serde(default)]
+    pub brightness: i8, // -100 to 100, JPEG, PNG and TIFF output only
+    #[serde(default)]
+    pub contrast: i8, // -100 to 100, JPEG, PNG and TIFF output only
+    #[serde(default)]
+    pub barcode_payload: Option<String>, // Barcode documents only, None encodes the job ID
+    // Never serialized, so it stays out of saved state, profiles, exported history
+    // and what the frontend is sent back
+    #[serde(default, skip_serializing)]
+    pub pdf_password: Option<String>, // PDF output only, encrypts the file to need it to open
+    // Kept in place of the password, so settings that lost it on the way through
+    // saved state or a profile are refused rather than scanned unprotected
+    #[serde(default)]
+    pub encryption_requested: bool,
+    #[serde(default)]
+    pub progress_steps: Option<u32>, // Progress updates while scanning, None for 20 per page
+    #[serde(default)]
+    pub max_retries: u8, // Rescans after a hardware error before the job fails
+    #[serde(default = "default_dither")]
+    pub dither: bool, // Black and white PNG and TIFF only, false applies a hard threshold
+    #[serde(default)]
+    pub filename_template: Option<String>, // Output file name pattern, see FILENAME_PLACEHOLDERS
+    #[serde(default)]
+    pub adf_sheet_count: Option<u32>, // Sheets stacked in the document feeder, sets page_count
+}
+
+/// Region of the page to scan, in millimetres from the top-left corner
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScanArea {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ScanArea {
+    /// Check that the area is not empty and lies within the paper
+    pub fn validate(&self, paper_size: &PaperSize) -> Result<(), String> {
+        if self.width == 0 || self.height == 0 {
+            return Err("Scan area must have a non-zero width and height".to_string());
+        }
+
+        let (paper_width, paper_height) = paper_size.dimensions_mm();
+        let right = self.x.saturating_add(self.width) as f32;
+        let bottom = self.y.saturating_add(self.height) as f32;
+        if right > paper_width || bottom > paper_height {
+            return Err(format!(
+                "Scan area {}x{} mm at ({}, {}) mm does not fit on {:?} paper",
+                self.width, self.height, self.x, self.y, paper_size
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Image adjustments applied between scanning and file generation (simulated)
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct PostProcessing {
+    #[serde(default)]
+    pub deskew: bool,
+    #[serde(default)]
+    pub auto_crop: bool,
+    #[serde(default)]
+    pub contrast: bool,
+}
+
+impl PostProcessing {
+    /// Names of the enabled adjustments, in the order they are applied
+    pub fn steps(&self) -> Vec<&'static str> {
+        [
+            (self.deskew, "Deskew"),
+            (self.auto_crop, "Auto-crop"),
+            (self.contrast, "Contrast adjustment"),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, step)| enabled.then_some(step))
+        .collect()
+    }
+}
+
+/// How hard the PDF, PNG and TIFF encoders compress. JPEG size follows quality alone,
+/// so there the level only changes the simulated scan data.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum CompressionLevel {
+    None,
+    Fast,
+    #[default]
+    Balanced,
+    Maximum,
+}
+
+/// Queued jobs start highest priority first, oldest first within a priority
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+pub enum JobPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+fn default_page_count() -> u32 {
+    1
+}
+
+fn default_timeout_secs() -> u64 {
+    120
+}
+
+fn default_dither() -> bool {
+    true
+}
+
+/// Named document type and settings combination for recurring scans
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanProfile {
+    pub name: String,
+    pub document_type: DocumentType,
+    pub settings: ScanSettings,
+}
+
+/// Remote HTTP destination that receives the generated file as the request body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadTarget {
+    pub url: String,
+    #[serde(default)]
+    pub method: UploadMethod,
+    #[serde(default)]
+    pub headers: std::collections::HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum UploadMethod {
+    #[default]
+    Post,
+    Put,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PdfALevel {
+    PdfA1b,
+    PdfA2b,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum InputSource {
+    #[default]
+    Flatbed,
+    Adf,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -113,16 +506,70 @@ pub enum OutputFormat {
     Tiff,
 }
 
+impl OutputFormat {
+    /// Format a file was written in, judging by its extension
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "pdf" => Some(Self::Pdf),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "png" => Some(Self::Png),
+            "tif" | "tiff" => Some(Self::Tiff),
+            _ => None,
+        }
+    }
+
+    /// Extension given to files written in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Pdf => "pdf",
+            Self::Jpeg => "jpg",
+            Self::Png => "png",
+            Self::Tiff => "tif",
+        }
+    }
+
+    /// Format a file was written in, judging by its leading bytes
+    pub fn from_magic_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(b"%PDF-") {
+            Some(Self::Pdf)
+        } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(Self::Jpeg)
+        } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(Self::Png)
+        } else if bytes.starts_with(b"II*\0") || bytes.starts_with(b"MM\0*") {
+            Some(Self::Tiff)
+        } else {
+            None
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Pdf => "application/pdf",
+            Self::Jpeg => "image/jpeg",
+            Self::Png => "image/png",
+            Self::Tiff => "image/tiff",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum JobStatus {
     Pending,
     Scanning,
+    Paused,
     Processing,
     Completed,
     Failed(String),
     Cancelled,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocaleInfo {
+    pub code: String,
+    pub display_name: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanAction {
     pub action_type: ActionType,
@@ -139,6 +586,53 @@ pub enum ActionType {
     TestConnection,
 }
 
+impl ScanSettings {
+    /// Most progress updates a scan reports, however many pages it has
+    pub const MAX_PROGRESS_STEPS: u32 = 200;
+    /// Most automatic rescans a job can ask for
+    pub const MAX_RETRIES: u8 = 5;
+    const PROGRESS_STEPS_PER_PAGE: u32 = 20;
+
+    /// Progress updates to report while scanning: the requested count, or 20 for
+    /// each page so long scans move the progress bar in smaller increments
+    pub fn scan_progress_steps(&self) -> u32 {
+        self.progress_steps
+            .unwrap_or(Self::PROGRESS_STEPS_PER_PAGE.saturating_mul(self.page_count))
+            .clamp(1, Self::MAX_PROGRESS_STEPS)
+    }
+
+    /// The settings with one page per sheet in the document feeder, when its
+    /// stack is given
+    pub fn with_adf_sheets(mut self) -> Self {
+        if let Some(sheets) = self.adf_sheet_count {
+            self.page_count = sheets;
+        }
+        self
+    }
+
+    /// The settings noting that a PDF password was given, which outlasts the
+    /// password itself in saved state and profiles
+    pub fn with_encryption_recorded(mut self) -> Self {
+        self.encryption_requested |= self.pdf_password.is_some();
+        self
+    }
+
+    /// Whether the settings ask for a password protected PDF but no longer hold
+    /// the password
+    pub fn password_lost(&self) -> bool {
+        self.encryption_requested && self.pdf_password.is_none()
+    }
+
+    /// The settings with an Auto paper size replaced by the usual paper of the
+    /// document type
+    pub fn with_paper_for(mut self, document_type: DocumentType) -> Self {
+        if self.paper_size == PaperSize::Auto {
+            self.paper_size = PaperSize::for_document_type(document_type);
+        }
+        self
+    }
+}
+
 impl Default for ScanSettings {
     fn default() -> Self {
         Self {
@@ -148,6 +642,30 @@ impl Default for ScanSettings {
             duplex: false,
             output_format: OutputFormat::Pdf,
             quality: 85,
+            input_source: InputSource::Flatbed,
+            locale: None,
+            include_exif: false,
+            background_color: None,
+            pdf_a_level: None,
+            upload: None,
+            page_count: 1,
+            timeout_secs: 120,
+            output_dir: None,
+            watermark: None,
+            priority: JobPriority::Normal,
+            compression: CompressionLevel::Balanced,
+            post_processing: PostProcessing::default(),
+            scan_area: None,
+            brightness: 0,
+            contrast: 0,
+            barcode_payload: None,
+            pdf_password: None,
+            encryption_requested: false,
+            progress_steps: None,
+            max_retries: 0,
+            dither: true,
+            filename_template: None,
+            adf_sheet_count: None,
         }
     }
 }
@@ -161,12 +679,63 @@ impl Scanner {
             status: ScannerStatus::Available,
             capabilities: ScannerCapabilities::default(),
             system_type,
+            source: ScannerSource::Discovered,
+            serial_number: None,
+            network_address: None,
+            tags: Vec::new(),
         }
     }
 
+    /// A discovered device, with an ID derived from its name, platform and serial
+    /// number so the same device keeps its ID across discovery runs
+    pub fn discovered(
+        name: String,
+        scanner_type: ScannerType,
+        system_type: SystemType,
+        serial_number: &str,
+    ) -> Self {
+        let mut scanner = Self::new(name, scanner_type, system_type);
+        scanner.id = Self::stable_id(&scanner.name, system_type, serial_number);
+        scanner.serial_number = Some(serial_number.to_string());
+        scanner
+    }
+
+    // UUIDv8 built from a SHA-256 of the device key, so IDs keep the usual format
+    fn stable_id(name: &str, system_type: SystemType, serial_number: &str) -> String {
+        let digest = Sha256::digest(format!("{:?}/{}/{}", system_type, name, serial_number));
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&digest[..16]);
+        uuid::Builder::from_custom_bytes(bytes)
+            .into_uuid()
+            .to_string()
+    }
+
+    /// Whether both entries describe the same physical device, regardless of ID.
+    /// Units of the same model are told apart by serial number when both have one.
+    pub fn is_same_device(&self, other: &Scanner) -> bool {
+        let same_serial = match (&self.serial_number, &other.serial_number) {
+            (Some(serial), Some(other_serial)) => serial == other_serial,
+            _ => true,
+        };
+        self.system_type == other.system_type && self.name == other.name && same_serial
+    }
+
+    /// Whether the scanner carries the tag, ignoring case and surrounding spaces
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = tag.trim();
+        self.tags
+            .iter()
+            .any(|existing| existing.eq_ignore_ascii_case(tag))
+    }
+
     pub fn is_available(&self) -> bool {
         matches!(self.status, ScannerStatus::Available)
     }
+
+    /// Whether the scanner can take new jobs, possibly after the ones it is running
+    pub fn accepts_jobs(&self) -> bool {
+        matches!(self.status, ScannerStatus::Available | ScannerStatus::Busy)
+    }
 }
 
 impl Default for ScannerCapabilities {
@@ -181,11 +750,23 @@ impl Default for ScannerCapabilities {
             paper_sizes: vec![
                 PaperSize::A4,
                 PaperSize::A3,
+                PaperSize::A5,
+                PaperSize::B4,
+                PaperSize::B5,
                 PaperSize::Letter,
                 PaperSize::Legal,
             ],
             has_duplex: true,
             has_adf: false,
+            adf_min_size: None,
+            adf_max_size: None,
+            supported_resolutions: Self::standard_resolutions(600),
+            supported_formats: vec![
+                OutputFormat::Pdf,
+                OutputFormat::Jpeg,
+                OutputFormat::Png,
+                OutputFormat::Tiff,
+            ],
         }
     }
 }
@@ -196,36 +777,161 @@ impl ScanJob {
         document_type: DocumentType,
         scan_settings: ScanSettings,
     ) -> Self {
+        let now = chrono::Utc::now();
         Self {
             id: uuid::Uuid::new_v4().to_string(),
             scanner_id,
             document_type,
-            scan_settings,
+            // An Auto paper size stays until the job starts, as its type can change
+            scan_settings: scan_settings.with_adf_sheets().with_encryption_recorded(),
             status: JobStatus::Pending,
+            status_history: vec![StatusTransition {
+                status: JobStatus::Pending,
+                timestamp: now,
+            }],
             progress: 0.0,
-            created_at: chrono::Utc::now(),
+            attempts: 0,
+            estimated_completion: None,
+            eta_secs: None,
+            created_at: now,
+            updated_at: now,
             completed_at: None,
             scan_result: None,
+            detected_type: None,
+            partial_result: None,
+            is_test_scan: false,
+            is_preview: false,
+            then: None,
+            post_action_outcome: None,
+            pause_reason: None,
+            scheduled_at: None,
+            cancel_requested: Arc::new(AtomicBool::new(false)),
+            pause_requested: Arc::new(AtomicBool::new(false)),
         }
     }
 
     pub fn start_scanning(&mut self) {
-        self.status = JobStatus::Scanning;
+        self.scan_settings = self
+            .scan_settings
+            .clone()
+            .with_paper_for(self.document_type);
+        self.set_status(JobStatus::Scanning);
+        self.attempts = 1;
+        self.touch();
+    }
+
+    /// Scan the document again from the start after a transient failure
+    pub fn retry_scanning(&mut self) {
+        self.set_status(JobStatus::Scanning);
+        self.attempts += 1;
+        self.progress = 0.0;
+        self.estimated_completion = None;
+        self.eta_secs = None;
+        self.touch();
+    }
+
+    /// Scanning is done; adjustments and file generation follow
+    pub fn start_processing(&mut self) {
+        // A pause that arrived after the last scan step has nothing left to hold
+        self.pause_requested.store(false, Ordering::SeqCst);
+        self.set_status(JobStatus::Processing);
+        self.estimated_completion = None;
+        self.eta_secs = None;
+        self.touch();
     }
 
     pub fn complete(&mut self) {
-        self.status = JobStatus::Completed;
+        self.set_status(JobStatus::Completed);
         self.progress = 1.0;
-        self.completed_at = Some(chrono::Utc::now());
+        self.finish();
     }
 
     pub fn fail(&mut self, error: String) {
-        self.status = JobStatus::Failed(error);
+        self.set_status(JobStatus::Failed(error));
+        self.finish();
+    }
+
+    pub fn cancel(&mut self) {
+        self.cancel_requested.store(true, Ordering::SeqCst);
+        self.set_status(JobStatus::Cancelled);
+        self.finish();
+    }
+
+    /// Hold the scan at its current progress; the scanner stays reserved
+    pub fn pause(&mut self) {
+        self.pause_requested.store(true, Ordering::SeqCst);
+        self.set_status(JobStatus::Paused);
+        self.pause_reason = None;
+        self.estimated_completion = None;
+        self.eta_secs = None;
+        self.touch();
+    }
+
+    /// Pause on behalf of the scanner, which has to recover before the job resumes
+    pub fn pause_with_reason(&mut self, reason: String) {
+        self.pause();
+        self.pause_reason = Some(reason);
+    }
+
+    pub fn resume(&mut self) {
+        self.pause_requested.store(false, Ordering::SeqCst);
+        self.set_status(JobStatus::Scanning);
+        self.pause_reason = None;
+        self.touch();
+    }
+
+    // Enter a status, recording the transition; touch() is left to the caller
+    fn set_status(&mut self, status: JobStatus) {
+        self.status_history.push(StatusTransition {
+            status: status.clone(),
+            timestamp: chrono::Utc::now(),
+        });
+        self.status = status;
+    }
+
+    fn finish(&mut self) {
         self.completed_at = Some(chrono::Utc::now());
+        self.estimated_completion = None;
+        self.eta_secs = None;
+        self.touch();
     }
 
     pub fn update_progress(&mut self, progress: f32) {
         self.progress = progress.clamp(0.0, 1.0);
+        self.touch();
+    }
+
+    /// Record how much longer the job is expected to run
+    pub fn update_eta(&mut self, remaining: std::time::Duration) {
+        self.estimated_completion = chrono::Duration::from_std(remaining)
+            .ok()
+            .map(|remaining| chrono::Utc::now() + remaining);
+        // Round up so a job with time left never reports zero seconds
+        self.eta_secs = Some(remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0));
+        self.touch();
+    }
+
+    /// Whether the job has been cancelled, visible from any clone of it
+    pub fn is_cancel_requested(&self) -> bool {
+        self.cancel_requested.load(Ordering::SeqCst)
+    }
+
+    /// Whether the job has been paused, visible from any clone of it
+    pub fn is_pause_requested(&self) -> bool {
+        self.pause_requested.load(Ordering::SeqCst)
+    }
+
+    /// Record that the job was modified
+    pub fn touch(&mut self) {
+        self.updated_at = chrono::Utc::now();
+    }
+
+    /// Whether the job has reached a final state and will not change again
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.status,
+            JobStatus::Completed | JobStatus::Failed(_) | JobStatus::Cancelled
+        )
     }
 }
 
@@ -238,6 +944,38 @@ pub struct ScanResult {
     pub color_mode: ColorMode,
     pub format: OutputFormat,
     pub scan_time: chrono::DateTime<chrono::Utc>,
+    pub is_test_scan: bool,
+    pub exif_embedded: bool,
+    pub pdf_a_level: Option<PdfALevel>,
+    pub upload: Option<UploadResult>,
+    #[serde(default)]
+    pub preview_path: Option<std::path::PathBuf>, // PNG thumbnail of the first page
+    #[serde(default)]
+    pub ocr_text: Option<String>, // Set once ocr_scan has run
+    #[serde(default)]
+    pub checksum: String, // Hex SHA-256 of the finished file, empty for older scans
+    #[serde(default)]
+    pub encrypted: bool, // Password protected PDF
+    #[serde(default)]
+    pub page_info: Vec<PageInfo>, // Empty for older scans
+    #[serde(default)]
+    pub is_preview: bool, // Proof from a preview scan, not the archival file
+}
+
+/// Size of one page of a scan file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageInfo {
+    pub index: u32, // Page number, starting at 1
+    pub width_mm: f32,
+    pub height_mm: f32,
+    pub bytes: u64, // The page's own data plus its share of what all pages use
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadResult {
+    pub url: String,
+    pub status: u16,
+    pub location: Option<String>, // Location header returned by the server, if any
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -247,3 +985,25 @@ pub struct ScanOutput {
     pub preview_available: bool,
     pub output_path: Option<std::path::PathBuf>,
 }
+
+/// Bytes of a scan file starting at `offset`, for clients without file system
+/// access. The file is complete once `offset` plus the data length reaches
+/// `total_size`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanFileChunk {
+    pub job_id: String,
+    pub offset: u64,
+    pub data: Vec<u8>,
+    pub total_size: u64,
+    pub mime_type: String,
+}
+
+/// A file found in the output directory, whether or not its job is still known
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputFileInfo {
+    pub name: String,
+    pub path: std::path::PathBuf,
+    pub size: u64,
+    pub modified: chrono::DateTime<chrono::Utc>,
+    pub format: Option<OutputFormat>, // None for files such as OCR text sidecars
+}
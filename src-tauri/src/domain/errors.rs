@@ -0,0 +1,70 @@
+use serde::Serialize;
+use std::fmt;
+
+/// Error returned by the scanner service and commands.
+///
+/// Serializes as `{"code": "SCANNER_NOT_FOUND", "message": "..."}` so callers can
+/// branch on the code and show the message as is.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "code", content = "message", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ScannerError {
+    ScannerNotFound(String),
+    ScannerUnavailable(String), // Busy, offline or in an error state
+    InvalidSettings(String),
+    JobNotFound(String),
+    InvalidJobState(String), // The job's status does not allow the operation
+    ProfileNotFound(String),
+    IoError(String),
+    Internal(String),
+}
+
+impl ScannerError {
+    pub fn scanner_not_found(scanner_id: &str) -> Self {
+        Self::ScannerNotFound(format!("Scanner with ID {} not found", scanner_id))
+    }
+
+    pub fn job_not_found(job_id: &str) -> Self {
+        Self::JobNotFound(format!("Job with ID {} not found", job_id))
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::ScannerNotFound(message)
+            | Self::ScannerUnavailable(message)
+            | Self::InvalidSettings(message)
+            | Self::JobNotFound(message)
+            | Self::InvalidJobState(message)
+            | Self::ProfileNotFound(message)
+            | Self::IoError(message)
+            | Self::Internal(message) => message,
+        }
+    }
+}
+
+impl fmt::Display for ScannerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for ScannerError {}
+
+// Lock failures, reported as plain strings, surface as internal errors. Helper
+// errors with a meaning of their own are mapped to their variant at the call site.
+impl From<String> for ScannerError {
+    fn from(message: String) -> Self {
+        Self::Internal(message)
+    }
+}
+
+impl From<&str> for ScannerError {
+    fn from(message: &str) -> Self {
+        Self::Internal(message.to_string())
+    }
+}
+
+impl From<std::io::Error> for ScannerError {
+    fn from(error: std::io::Error) -> Self {
+        Self::IoError(error.to_string())
+    }
+}
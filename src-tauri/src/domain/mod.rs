@@ -1,3 +1,5 @@
 pub mod entities;
+pub mod errors;
 
 pub use entities::*;
+pub use errors::*;
@@ -0,0 +1,15 @@
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::Path;
+
+/// Hex-encoded SHA-256 of a file's contents, read in chunks so large scans are
+/// never held in memory
+pub fn file_checksum(path: &Path) -> Result<String, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open scan file: {}", e))?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut BufReader::new(file), &mut hasher)
+        .map_err(|e| format!("Failed to read scan file: {}", e))?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
@@ -0,0 +1,74 @@
+use crate::domain::ScanSettings;
+use chrono::NaiveDateTime;
+
+/// Language and number/date conventions of generated sample documents
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContentLocale {
+    EnUs,
+    DeDe,
+    FrFr,
+}
+
+impl ContentLocale {
+    /// Locale codes with content templates, paired with their display names
+    pub const SUPPORTED: &'static [(&'static str, &'static str)] = &[
+        ("en-US", "English (United States)"),
+        ("de-DE", "German (Germany)"),
+        ("fr-FR", "French (France)"),
+    ];
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.to_ascii_lowercase().as_str() {
+            "en-us" => Some(Self::EnUs),
+            "de-de" => Some(Self::DeDe),
+            "fr-fr" => Some(Self::FrFr),
+            _ => None,
+        }
+    }
+
+    /// The settings' locale, with unset or unknown codes falling back to en-US
+    pub fn from_settings(settings: &ScanSettings) -> Self {
+        settings
+            .locale
+            .as_deref()
+            .and_then(Self::from_code)
+            .unwrap_or(Self::EnUs)
+    }
+
+    /// An amount in cents with the locale's separators and currency; en-US keeps
+    /// the dollar amounts the templates always had
+    pub fn amount(self, cents: u64) -> String {
+        let (thousands, decimal) = match self {
+            Self::EnUs => (',', '.'),
+            Self::DeDe => ('.', ','),
+            Self::FrFr => (' ', ','),
+        };
+
+        let units = (cents / 100).to_string();
+        let mut grouped = String::new();
+        for (position, digit) in units.chars().enumerate() {
+            if position > 0 && (units.len() - position).is_multiple_of(3) {
+                grouped.push(thousands);
+            }
+            grouped.push(digit);
+        }
+
+        match self {
+            Self::EnUs => format!("${}{}{:02}", grouped, decimal, cents % 100),
+            Self::DeDe | Self::FrFr => format!("{}{}{:02} €", grouped, decimal, cents % 100),
+        }
+    }
+
+    pub fn date(self, date: &NaiveDateTime) -> String {
+        date.format(match self {
+            Self::EnUs => "%Y-%m-%d",
+            Self::DeDe => "%d.%m.%Y",
+            Self::FrFr => "%d/%m/%Y",
+        })
+        .to_string()
+    }
+
+    pub fn date_time(self, date: &NaiveDateTime) -> String {
+        format!("{} {}", self.date(date), date.format("%H:%M"))
+    }
+}
@@ -0,0 +1,55 @@
+use std::path::Path;
+
+/// Fail when the file system holding `dir` has less than `needed` bytes free for
+/// this user. Platforms where free space cannot be read are not checked.
+pub fn check_disk_space(dir: &Path, needed: u64) -> Result<(), String> {
+    match available_space(dir) {
+        Some(available) if available < needed => Err(format!(
+            "Insufficient disk space: {} bytes needed, {} bytes available",
+            needed, available
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Bytes this user can still write to the file system holding `dir`
+#[cfg(unix)]
+pub fn available_space(dir: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = std::ffi::CString::new(dir.as_os_str().as_bytes()).ok()?;
+    let mut stats = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: path is NUL-terminated and stats is only read after statvfs fills it
+    let stats = unsafe {
+        if libc::statvfs(path.as_ptr(), stats.as_mut_ptr()) != 0 {
+            return None;
+        }
+        stats.assume_init()
+    };
+    Some((stats.f_bavail as u64).saturating_mul(stats.f_frsize as u64))
+}
+
+/// Bytes this user can still write to the file system holding `dir`
+#[cfg(windows)]
+pub fn available_space(dir: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let path: Vec<u16> = dir.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut available = 0u64;
+    // SAFETY: path is NUL-terminated and the totals we do not need may be null
+    let succeeded = unsafe {
+        GetDiskFreeSpaceExW(
+            path.as_ptr(),
+            &mut available,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    (succeeded != 0).then_some(available)
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn available_space(_dir: &Path) -> Option<u64> {
+    None
+}
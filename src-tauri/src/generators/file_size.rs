@@ -0,0 +1,189 @@
+use crate::domain::*;
+use img_parts::jpeg::{markers, Jpeg, JpegSegment};
+use img_parts::png::{Png, PngChunk};
+use img_parts::Bytes;
+use printpdf::lopdf::{Dictionary, Document, Object, Stream};
+use rand::RngCore;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// Bytes a single page occupies at the reference resolution and full quality
+pub const BYTES_PER_REFERENCE_PAGE: u64 = 500_000;
+
+/// Resolution the per-page size is quoted at
+pub const REFERENCE_RESOLUTION: u64 = 300;
+
+// Saves spent converging on the target size, since the stream length is itself written out
+const PDF_PADDING_ATTEMPTS: usize = 3;
+
+// Largest payload a single JPEG marker segment can hold
+const MAX_JPEG_SEGMENT_CONTENTS: usize = 65_533;
+
+// Marker plus 2-byte length
+const JPEG_SEGMENT_OVERHEAD: usize = 4;
+
+// Length, type and CRC
+const PNG_CHUNK_OVERHEAD: usize = 12;
+
+// Ancillary, private, safe-to-copy chunk holding the simulated scan data
+const PNG_PADDING_CHUNK: [u8; 4] = *b"scAn";
+
+/// Size a scan of `pages` pages should have with these settings:
+///
+/// `pages × BYTES_PER_REFERENCE_PAGE × (resolution / 300)² × quality / 100 × compression`
+///
+/// where the compression factor is 3 for None, 1.25 for Fast, 1 for Balanced and
+/// 0.75 for Maximum. So a Balanced scan at 75 DPI and quality 50 gives about
+/// 15.6 KB per page and one at 1200 DPI and quality 100 gives 8 MB per page.
+/// Quality is clamped to 1-100 and the result is rounded down to whole bytes.
+pub fn estimated_file_size(settings: &ScanSettings, pages: u32) -> u64 {
+    let resolution = settings.resolution as u64;
+    let quality = settings.quality.clamp(1, 100) as u64;
+
+    let balanced = pages as u64 * BYTES_PER_REFERENCE_PAGE * resolution * resolution * quality
+        / (REFERENCE_RESOLUTION * REFERENCE_RESOLUTION * 100);
+    balanced * compression_size_percent(settings.compression) / 100
+}
+
+// Scan data size relative to Balanced compression
+fn compression_size_percent(compression: CompressionLevel) -> u64 {
+    match compression {
+        CompressionLevel::None => 300,
+        CompressionLevel::Fast => 125,
+        CompressionLevel::Balanced => 100,
+        CompressionLevel::Maximum => 75,
+    }
+}
+
+/// Grow a generated scan file to `target` bytes with filler the format's readers
+/// skip, returning the new size. Files already at or above the target are left
+/// as they are, and the result can fall a few bytes short where the format's
+/// framing does not divide evenly.
+pub fn pad_file_to_size(path: &Path, format: &OutputFormat, target: u64) -> Result<u64, String> {
+    let current = file_size(path)?;
+    if current >= target {
+        return Ok(current);
+    }
+    let deficit = (target - current) as usize;
+
+    match format {
+        OutputFormat::Pdf => pad_pdf(path, deficit)?,
+        OutputFormat::Jpeg => pad_jpeg(path, deficit)?,
+        OutputFormat::Png => pad_png(path, deficit)?,
+        OutputFormat::Tiff => pad_tiff(path, deficit)?,
+    }
+
+    file_size(path)
+}
+
+// PDF: an unfiltered stream referenced from the catalog, resized after each save
+// until the object and cross-reference overhead is absorbed
+fn pad_pdf(path: &Path, deficit: usize) -> Result<(), String> {
+    let mut doc = Document::load(path).map_err(|e| format!("Failed to reload PDF: {}", e))?;
+    let target = file_size(path)? + deficit as u64;
+
+    let mut padding = deficit;
+    let stream_id = doc.add_object(padding_stream(padding));
+    doc.catalog_mut()
+        .map_err(|e| format!("PDF has no catalog: {}", e))?
+        .set("ScanData", Object::Reference(stream_id));
+
+    for _ in 0..PDF_PADDING_ATTEMPTS {
+        doc.save(path)
+            .map_err(|e| format!("Failed to save padded PDF: {}", e))?;
+
+        let size = file_size(path)?;
+        if size == target {
+            break;
+        }
+        padding = (padding as i64 + target as i64 - size as i64).max(0) as usize;
+        doc.objects
+            .insert(stream_id, padding_stream(padding).into());
+    }
+
+    Ok(())
+}
+
+// JPEG: comment segments ahead of the scan data
+fn pad_jpeg(path: &Path, deficit: usize) -> Result<(), String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read JPEG file: {}", e))?;
+    let mut jpeg = Jpeg::from_bytes(Bytes::from(bytes))
+        .map_err(|e| format!("Failed to parse JPEG file: {}", e))?;
+
+    let segments = jpeg.segments_mut();
+    let scan_start = segments
+        .iter()
+        .position(|segment| segment.marker() == markers::SOS)
+        .unwrap_or(segments.len());
+
+    let mut remaining = deficit;
+    while remaining > JPEG_SEGMENT_OVERHEAD {
+        let contents = (remaining - JPEG_SEGMENT_OVERHEAD).min(MAX_JPEG_SEGMENT_CONTENTS);
+        segments.insert(
+            scan_start,
+            JpegSegment::new_with_contents(markers::COM, random_bytes(contents)),
+        );
+        remaining -= contents + JPEG_SEGMENT_OVERHEAD;
+    }
+
+    let mut output = Vec::new();
+    jpeg.encoder()
+        .write_to(&mut output)
+        .map_err(|e| format!("Failed to encode padded JPEG: {}", e))?;
+    fs::write(path, output).map_err(|e| format!("Failed to write JPEG file: {}", e))
+}
+
+// PNG: a private ancillary chunk just before IEND
+fn pad_png(path: &Path, deficit: usize) -> Result<(), String> {
+    if deficit <= PNG_CHUNK_OVERHEAD {
+        return Ok(());
+    }
+
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read PNG file: {}", e))?;
+    let mut png = Png::from_bytes(Bytes::from(bytes))
+        .map_err(|e| format!("Failed to parse PNG file: {}", e))?;
+
+    let chunks = png.chunks_mut();
+    let end = chunks.len().saturating_sub(1);
+    chunks.insert(
+        end,
+        PngChunk::new(
+            PNG_PADDING_CHUNK,
+            random_bytes(deficit - PNG_CHUNK_OVERHEAD),
+        ),
+    );
+
+    let mut output = Vec::new();
+    png.encoder()
+        .write_to(&mut output)
+        .map_err(|e| format!("Failed to encode padded PNG: {}", e))?;
+    fs::write(path, output).map_err(|e| format!("Failed to write PNG file: {}", e))
+}
+
+// TIFF: trailing bytes, which no image directory points at
+fn pad_tiff(path: &Path, deficit: usize) -> Result<(), String> {
+    let mut file = fs::OpenOptions::new()
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open TIFF file: {}", e))?;
+    file.write_all(&random_bytes(deficit))
+        .map_err(|e| format!("Failed to write TIFF file: {}", e))
+}
+
+// Random rather than zeroed so the filler stays the same size if the file is recompressed
+fn random_bytes(len: usize) -> Bytes {
+    let mut bytes = vec![0u8; len];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    Bytes::from(bytes)
+}
+
+fn padding_stream(len: usize) -> Stream {
+    Stream::new(Dictionary::new(), random_bytes(len).to_vec()).with_compression(false)
+}
+
+fn file_size(path: &Path) -> Result<u64, String> {
+    fs::metadata(path)
+        .map(|metadata| metadata.len())
+        .map_err(|e| format!("Failed to get file size: {}", e))
+}
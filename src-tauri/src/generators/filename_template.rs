@@ -0,0 +1,181 @@
+use std::path::Path;
+
+/// Template reproducing the built-in `{type}_{timestamp}.{ext}` file names
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "{type}_{date}_{time}.{ext}";
+
+/// Placeholders a filename template can use:
+///
+/// - `{type}`: document type, e.g. `invoice`
+/// - `{date}`: scan date as `YYYYMMDD`
+/// - `{time}`: scan time as `HHMMSS`, in UTC like the date
+/// - `{scanner}`: name of the scanner
+/// - `{counter}`: 4-digit number, the lowest that gives a file not yet in the
+///   output directory
+/// - `{ext}`: file extension without the dot, added at the end when left out
+///
+/// `/` in the template separates subdirectories of the output directory.
+/// Characters that are not valid in file names, path separators included, are
+/// replaced with `_` in placeholder values.
+pub const FILENAME_PLACEHOLDERS: [&str; 6] = ["type", "date", "time", "scanner", "counter", "ext"];
+
+const MAX_TEMPLATE_LEN: usize = 255;
+
+// Highest {counter} value, after which the name is reused
+const MAX_COUNTER: u32 = 9999;
+
+/// Values filled into a filename template
+pub struct FilenameFields<'a> {
+    pub document_type: &'a str,
+    pub extension: &'a str,
+    pub scanner: &'a str,
+    pub timestamp: &'a chrono::DateTime<chrono::Utc>,
+}
+
+/// Check that a template only uses known placeholders and stays inside the
+/// output directory
+pub fn validate_filename_template(template: &str) -> Result<(), String> {
+    if template.trim().is_empty() {
+        return Err("Filename template cannot be empty".to_string());
+    }
+    if template.len() > MAX_TEMPLATE_LEN {
+        return Err(format!(
+            "Filename template must be at most {} characters",
+            MAX_TEMPLATE_LEN
+        ));
+    }
+
+    let mut literal = String::new();
+    for segment in parse(template)? {
+        match segment {
+            Segment::Literal(text) => literal.push_str(text),
+            Segment::Placeholder(_) => literal.push('x'),
+        }
+    }
+    if literal.contains('\\') || !literal.chars().all(is_file_name_char_or_slash) {
+        return Err(
+            "Filename template contains characters that are not valid in file names".to_string(),
+        );
+    }
+    if literal.starts_with('/') {
+        return Err("Filename template must be relative to the output directory".to_string());
+    }
+    if literal
+        .split('/')
+        .any(|component| component.is_empty() || component == "." || component == "..")
+    {
+        return Err("Filename template cannot have empty, '.' or '..' path components".to_string());
+    }
+    Ok(())
+}
+
+/// Fill in a validated template, returning a path relative to `output_dir`
+pub fn render_filename_template(
+    template: &str,
+    fields: &FilenameFields,
+    output_dir: &Path,
+) -> Result<String, String> {
+    let segments = parse(template)?;
+    let uses_counter = segments
+        .iter()
+        .any(|segment| matches!(segment, Segment::Placeholder("counter")));
+
+    let render = |counter: u32| {
+        let mut filename = String::new();
+        for segment in &segments {
+            match segment {
+                Segment::Literal(text) => filename.push_str(text),
+                Segment::Placeholder(name) => {
+                    let value = match *name {
+                        "type" => fields.document_type.to_string(),
+                        "date" => fields.timestamp.format("%Y%m%d").to_string(),
+                        "time" => fields.timestamp.format("%H%M%S").to_string(),
+                        "scanner" => fields.scanner.to_string(),
+                        "counter" => format!("{:04}", counter),
+                        _ => fields.extension.to_string(),
+                    };
+                    filename.push_str(&sanitize(&value));
+                }
+            }
+        }
+        if !segments
+            .iter()
+            .any(|segment| matches!(segment, Segment::Placeholder("ext")))
+        {
+            filename.push('.');
+            filename.push_str(fields.extension);
+        }
+        filename
+    };
+
+    if !uses_counter {
+        return Ok(render(1));
+    }
+    Ok((1..=MAX_COUNTER)
+        .map(render)
+        .find(|filename| !output_dir.join(filename).exists())
+        .unwrap_or_else(|| render(MAX_COUNTER)))
+}
+
+enum Segment<'a> {
+    Literal(&'a str),
+    Placeholder(&'a str),
+}
+
+fn parse(template: &str) -> Result<Vec<Segment<'_>>, String> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find(['{', '}']) {
+        if rest[start..].starts_with('}') {
+            return Err("Unmatched '}' in filename template".to_string());
+        }
+        let end = rest[start..]
+            .find('}')
+            .map(|end| start + end)
+            .ok_or("Unmatched '{' in filename template")?;
+        let name = &rest[start + 1..end];
+        if !FILENAME_PLACEHOLDERS.contains(&name) {
+            return Err(format!(
+                "Unknown filename placeholder {{{}}}, expected one of {}",
+                name,
+                FILENAME_PLACEHOLDERS
+                    .iter()
+                    .map(|placeholder| format!("{{{}}}", placeholder))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        if start > 0 {
+            segments.push(Segment::Literal(&rest[..start]));
+        }
+        segments.push(Segment::Placeholder(name));
+        rest = &rest[end + 1..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Literal(rest));
+    }
+    Ok(segments)
+}
+
+// Characters no supported platform allows in a file name, plus control characters
+fn is_file_name_char_or_slash(c: char) -> bool {
+    !c.is_control() && !matches!(c, '<' | '>' | ':' | '"' | '|' | '?' | '*' | '\\')
+}
+
+// Placeholder values never add path components, and never start a hidden file
+fn sanitize(value: &str) -> String {
+    let sanitized: String = value
+        .trim()
+        .chars()
+        .map(|c| {
+            if c == '/' || !is_file_name_char_or_slash(c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    match sanitized.trim_start_matches('.') {
+        "" => "_".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
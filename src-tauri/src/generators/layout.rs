@@ -0,0 +1,92 @@
+use printpdf::*;
+
+const MM_PER_POINT: f32 = 25.4 / 72.0;
+
+/// Page margins in millimetres
+#[derive(Debug, Clone, Copy)]
+pub struct Margins {
+    pub top: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+impl Default for Margins {
+    fn default() -> Self {
+        Self {
+            top: 27.0,
+            bottom: 20.0,
+            left: 20.0,
+        }
+    }
+}
+
+/// Text cursor that flows content down a page from the top margin, so the
+/// same content helpers work on any paper size.
+pub struct PageLayout<'a> {
+    layer: &'a PdfLayerReference,
+    font: &'a IndirectFontRef,
+    margins: Margins,
+    cursor_y: f32,
+}
+
+impl<'a> PageLayout<'a> {
+    /// Minimum distance the cursor advances after each line
+    pub const LINE_HEIGHT: f32 = 10.0;
+
+    pub fn new(
+        layer: &'a PdfLayerReference,
+        font: &'a IndirectFontRef,
+        page_height: f32,
+        margins: Margins,
+    ) -> Self {
+        Self {
+            layer,
+            font,
+            margins,
+            cursor_y: page_height - margins.top,
+        }
+    }
+
+    /// Write a line at the left margin and advance the cursor
+    pub fn line(&mut self, text: &str, font_size: f32) {
+        self.indented_line(text, font_size, 0.0);
+    }
+
+    /// Write a line offset from the left margin and advance the cursor
+    pub fn indented_line(&mut self, text: &str, font_size: f32, indent: f32) {
+        // Content that would run into the bottom margin is dropped rather than overprinted
+        if self.cursor_y >= self.margins.bottom {
+            self.layer.use_text(
+                text,
+                font_size,
+                Mm(self.margins.left + indent),
+                Mm(self.cursor_y),
+                self.font,
+            );
+        }
+        self.cursor_y -= Self::line_advance(font_size);
+    }
+
+    /// Leave vertical space before the next line
+    pub fn skip(&mut self, space: f32) {
+        self.cursor_y -= space;
+    }
+
+    /// Reserve a block of the given height at the left margin for drawing and
+    /// advance the cursor past it. Returns the block's bottom-left corner, or
+    /// `None` when the block would run into the bottom margin.
+    pub fn block(&mut self, height: f32) -> Option<(f32, f32)> {
+        let bottom = self.cursor_y - height;
+        self.cursor_y = bottom - Self::LINE_HEIGHT;
+        (bottom >= self.margins.bottom).then_some((self.margins.left, bottom))
+    }
+
+    /// The layer content is drawn on
+    pub fn layer(&self) -> &'a PdfLayerReference {
+        self.layer
+    }
+
+    fn line_advance(font_size: f32) -> f32 {
+        (font_size * MM_PER_POINT * 1.15).max(Self::LINE_HEIGHT)
+    }
+}
@@ -0,0 +1,139 @@
+use crate::domain::*;
+use exif::experimental::Writer;
+use exif::{Field, In, Rational, Tag, Value};
+use img_parts::jpeg::Jpeg;
+use img_parts::{Bytes, ImageEXIF};
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+const SOFTWARE_NAME: &str = "Scanner Tool v1.0";
+
+// EXIF ResolutionUnit value for inches
+const RESOLUTION_UNIT_INCH: u16 = 2;
+
+// EXIF ColorSpace values
+const COLOR_SPACE_SRGB: u16 = 1;
+const COLOR_SPACE_UNCALIBRATED: u16 = 0xFFFF;
+
+/// Embed EXIF tags (scan date/time, DPI, software, color space) into an existing JPEG file
+pub fn embed_jpeg_exif(
+    path: &Path,
+    settings: &ScanSettings,
+    scan_time: &chrono::DateTime<chrono::Utc>,
+) -> Result<(), String> {
+    let exif_data = build_exif(settings, scan_time)?;
+
+    let jpeg_bytes = fs::read(path).map_err(|e| format!("Failed to read JPEG file: {}", e))?;
+    let mut jpeg = Jpeg::from_bytes(Bytes::from(jpeg_bytes))
+        .map_err(|e| format!("Failed to parse JPEG file: {}", e))?;
+    jpeg.set_exif(Some(Bytes::from(exif_data)));
+
+    let mut output = Vec::new();
+    jpeg.encoder()
+        .write_to(&mut output)
+        .map_err(|e| format!("Failed to encode JPEG with EXIF: {}", e))?;
+    fs::write(path, output).map_err(|e| format!("Failed to write JPEG file: {}", e))?;
+
+    Ok(())
+}
+
+fn build_exif(
+    settings: &ScanSettings,
+    scan_time: &chrono::DateTime<chrono::Utc>,
+) -> Result<Vec<u8>, String> {
+    let timestamp = scan_time.format("%Y:%m:%d %H:%M:%S").to_string();
+    let dpi = Rational {
+        num: settings.resolution,
+        denom: 1,
+    };
+    let color_space = match settings.color_mode {
+        ColorMode::Color => COLOR_SPACE_SRGB,
+        ColorMode::Grayscale | ColorMode::BlackAndWhite => COLOR_SPACE_UNCALIBRATED,
+    };
+
+    let fields = [
+        ascii_field(Tag::DateTime, &timestamp),
+        ascii_field(Tag::DateTimeOriginal, &timestamp),
+        ascii_field(Tag::Software, SOFTWARE_NAME),
+        Field {
+            tag: Tag::XResolution,
+            ifd_num: In::PRIMARY,
+            value: Value::Rational(vec![dpi]),
+        },
+        Field {
+            tag: Tag::YResolution,
+            ifd_num: In::PRIMARY,
+            value: Value::Rational(vec![dpi]),
+        },
+        Field {
+            tag: Tag::ResolutionUnit,
+            ifd_num: In::PRIMARY,
+            value: Value::Short(vec![RESOLUTION_UNIT_INCH]),
+        },
+        Field {
+            tag: Tag::ColorSpace,
+            ifd_num: In::PRIMARY,
+            value: Value::Short(vec![color_space]),
+        },
+    ];
+
+    let mut writer = Writer::new();
+    for field in &fields {
+        writer.push_field(field);
+    }
+
+    let mut buffer = Cursor::new(Vec::new());
+    writer
+        .write(&mut buffer, false)
+        .map_err(|e| format!("Failed to build EXIF data: {}", e))?;
+
+    Ok(buffer.into_inner())
+}
+
+fn ascii_field(tag: Tag, text: &str) -> Field {
+    Field {
+        tag,
+        ifd_num: In::PRIMARY,
+        value: Value::Ascii(vec![text.as_bytes().to_vec()]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::io::BufReader;
+
+    #[test]
+    fn embedded_exif_reads_back_dpi_and_date() {
+        let path = std::env::temp_dir().join(format!("exif_test_{}.jpg", uuid::Uuid::new_v4()));
+        ::image::RgbImage::from_pixel(8, 8, ::image::Rgb([255, 255, 255]))
+            .save(&path)
+            .unwrap();
+        let settings = ScanSettings {
+            resolution: 600,
+            output_format: OutputFormat::Jpeg,
+            ..ScanSettings::default()
+        };
+        let scan_time = chrono::Utc.with_ymd_and_hms(2024, 3, 9, 14, 5, 30).unwrap();
+
+        let embedded = embed_jpeg_exif(&path, &settings, &scan_time);
+        let exif = embedded.and_then(|_| {
+            let file = fs::File::open(&path).map_err(|e| e.to_string())?;
+            exif::Reader::new()
+                .read_from_container(&mut BufReader::new(file))
+                .map_err(|e| e.to_string())
+        });
+        let _ = fs::remove_file(&path);
+        let exif = exif.unwrap();
+
+        let x_resolution = exif.get_field(Tag::XResolution, In::PRIMARY).unwrap();
+        match &x_resolution.value {
+            Value::Rational(values) => assert_eq!(values[0].to_f64(), 600.0),
+            value => panic!("XResolution is not rational: {:?}", value),
+        }
+        let date = exif.get_field(Tag::DateTimeOriginal, In::PRIMARY).unwrap();
+        assert_eq!(date.display_value().to_string(), "2024-03-09 14:05:30");
+    }
+}
@@ -1,3 +1,27 @@
+pub mod checksum;
+pub mod content_locale;
+pub mod disk_space;
+pub mod file_size;
+pub mod filename_template;
+pub mod layout;
+pub mod metadata;
+pub mod page_info;
+pub mod pdf_a;
+pub mod pdf_encryption;
+pub mod pdf_merge;
+pub mod preview;
 pub mod scan_generator;
 
+pub use checksum::*;
+pub use content_locale::*;
+pub use disk_space::*;
+pub use file_size::*;
+pub use filename_template::*;
+pub use layout::*;
+pub use metadata::*;
+pub use page_info::*;
+pub use pdf_a::*;
+pub use pdf_encryption::*;
+pub use pdf_merge::*;
+pub use preview::*;
 pub use scan_generator::*;
@@ -0,0 +1,160 @@
+use crate::domain::*;
+use printpdf::lopdf::{Dictionary, Document, Object, ObjectId};
+use std::fs;
+use std::path::Path;
+use tiff::decoder::Decoder;
+use tiff::tags::Tag;
+
+const MM_PER_INCH: f32 = 25.4;
+const POINTS_PER_INCH: f32 = 72.0;
+
+/// Break a finished scan file down page by page. Each page is credited with the
+/// bytes of its own content, plus an even share of what the pages have in common
+/// (fonts, metadata, simulated scan data), so the sizes add up to the file size.
+pub fn page_breakdown(result: &ScanResult) -> Result<Vec<PageInfo>, String> {
+    let pages = match result.format {
+        OutputFormat::Pdf => pdf_pages(&result.file_path)?,
+        OutputFormat::Tiff => tiff_pages(&result.file_path, result.resolution)?,
+        OutputFormat::Jpeg | OutputFormat::Png => {
+            let (width, height) = ::image::image_dimensions(&result.file_path)
+                .map_err(|e| format!("Failed to read image dimensions: {}", e))?;
+            vec![(
+                pixels_to_mm(width, result.resolution),
+                pixels_to_mm(height, result.resolution),
+                0,
+            )]
+        }
+    };
+
+    let file_size = fs::metadata(&result.file_path)
+        .map_err(|e| format!("Failed to get file size: {}", e))?
+        .len();
+    let own_total: u64 = pages.iter().map(|(_, _, bytes)| bytes).sum();
+    let shared = file_size.saturating_sub(own_total);
+    let count = pages.len().max(1) as u64;
+
+    Ok(pages
+        .into_iter()
+        .enumerate()
+        .map(|(position, (width_mm, height_mm, bytes))| {
+            // The first page takes the remainder of the split, as it holds the headers
+            let remainder = if position == 0 { shared % count } else { 0 };
+            PageInfo {
+                index: position as u32 + 1,
+                width_mm: round_to_tenth(width_mm),
+                height_mm: round_to_tenth(height_mm),
+                bytes: bytes + shared / count + remainder,
+            }
+        })
+        .collect())
+}
+
+// Pixel and point conversions leave float noise well below what a scan can resolve
+fn round_to_tenth(mm: f32) -> f32 {
+    (mm * 10.0).round() / 10.0
+}
+
+fn pixels_to_mm(pixels: u32, dpi: u32) -> f32 {
+    pixels as f32 * MM_PER_INCH / dpi.max(1) as f32
+}
+
+// Media box and content bytes of each page in page tree order. Stream lengths
+// are read as stored, so encrypted files need no password.
+fn pdf_pages(path: &Path) -> Result<Vec<(f32, f32, u64)>, String> {
+    let doc = Document::load(path).map_err(|e| format!("Failed to load PDF: {}", e))?;
+
+    doc.get_pages()
+        .into_values()
+        .map(|page_id| {
+            let (width, height) = media_box_size(&doc, page_id)?;
+            let contents: u64 = doc
+                .get_page_contents(page_id)
+                .into_iter()
+                .chain(xobject_ids(&doc, page_id))
+                .filter_map(|id| doc.get_object(id).and_then(Object::as_stream).ok())
+                .map(|stream| stream.content.len() as u64)
+                .sum();
+            Ok((
+                width * MM_PER_INCH / POINTS_PER_INCH,
+                height * MM_PER_INCH / POINTS_PER_INCH,
+                contents,
+            ))
+        })
+        .collect()
+}
+
+// The page's media box, which may be inherited from its page tree ancestors
+fn media_box_size(doc: &Document, page_id: ObjectId) -> Result<(f32, f32), String> {
+    let mut node = Some(page_id);
+    while let Some(node_id) = node {
+        let dict = doc
+            .get_dictionary(node_id)
+            .map_err(|e| format!("PDF page is missing: {}", e))?;
+        if let Ok(media_box) = dict.get(b"MediaBox").and_then(Object::as_array) {
+            let corners: Vec<f32> = media_box
+                .iter()
+                .filter_map(|value| value.as_float().ok())
+                .collect();
+            if let [left, bottom, right, top] = corners[..] {
+                return Ok(((right - left).abs(), (top - bottom).abs()));
+            }
+        }
+        node = dict.get(b"Parent").and_then(Object::as_reference).ok();
+    }
+    Err("PDF page has no media box".to_string())
+}
+
+// Images and forms drawn on the page
+fn xobject_ids(doc: &Document, page_id: ObjectId) -> Vec<ObjectId> {
+    let resources = doc
+        .get_dictionary(page_id)
+        .and_then(|page| page.get(b"Resources"))
+        .and_then(|resources| resolve_dictionary(doc, resources));
+    resources
+        .and_then(|resources| resources.get(b"XObject"))
+        .and_then(|xobjects| resolve_dictionary(doc, xobjects))
+        .map(|xobjects| {
+            xobjects
+                .iter()
+                .filter_map(|(_, value)| value.as_reference().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn resolve_dictionary<'a>(
+    doc: &'a Document,
+    object: &'a Object,
+) -> printpdf::lopdf::Result<&'a Dictionary> {
+    match object {
+        Object::Reference(id) => doc.get_dictionary(*id),
+        object => object.as_dict(),
+    }
+}
+
+// Frame dimensions and strip bytes of each image directory
+fn tiff_pages(path: &Path, dpi: u32) -> Result<Vec<(f32, f32, u64)>, String> {
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open TIFF file: {}", e))?;
+    let mut decoder = Decoder::new(file).map_err(|e| format!("Failed to read TIFF file: {}", e))?;
+
+    let mut pages = Vec::new();
+    loop {
+        let (width, height) = decoder
+            .dimensions()
+            .map_err(|e| format!("Failed to read TIFF frame: {}", e))?;
+        let strips: u64 = decoder
+            .get_tag_u64_vec(Tag::StripByteCounts)
+            .map_err(|e| format!("Failed to read TIFF strips: {}", e))?
+            .iter()
+            .sum();
+        pages.push((pixels_to_mm(width, dpi), pixels_to_mm(height, dpi), strips));
+
+        if !decoder.more_images() {
+            break;
+        }
+        decoder
+            .next_image()
+            .map_err(|e| format!("Failed to read TIFF frame: {}", e))?;
+    }
+    Ok(pages)
+}
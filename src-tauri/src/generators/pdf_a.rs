@@ -0,0 +1,122 @@
+use crate::domain::*;
+use printpdf::lopdf::xref::XrefType;
+use printpdf::lopdf::{Dictionary, Document, Object, Stream, StringFormat};
+use printpdf::PdfConformance;
+use std::path::Path;
+
+const PRODUCER: &str = "Scanner Tool v1.0";
+
+pub fn pdf_a_conformance(level: &PdfALevel) -> PdfConformance {
+    match level {
+        PdfALevel::PdfA1b => PdfConformance::A1B_2005_PDF_1_4,
+        PdfALevel::PdfA2b => PdfConformance::A2B_2011_PDF_1_7,
+    }
+}
+
+/// Rewrite a saved PDF so it declares PDF/A conformance.
+///
+/// printpdf embeds the output intent ICC profile but leaves out the XMP
+/// identification PDF/A readers look for, so this replaces the document info
+/// with a matching Info dictionary and XMP packet, retags the output intent,
+/// and downgrades features PDF/A-1 does not allow.
+pub fn apply_pdf_a_conformance(path: &Path, level: &PdfALevel, title: &str) -> Result<(), String> {
+    let mut doc =
+        Document::load(path).map_err(|e| format!("Failed to reload PDF for PDF/A: {}", e))?;
+
+    let (version, part) = match level {
+        PdfALevel::PdfA1b => ("1.4", "1"),
+        PdfALevel::PdfA2b => ("1.7", "2"),
+    };
+    doc.version = version.to_string();
+    // Cross-reference streams are a PDF 1.5 feature
+    doc.reference_table.cross_reference_type = XrefType::CrossReferenceTable;
+    doc.trailer.remove(b"Type");
+
+    let now = chrono::Utc::now();
+    let info_date = now.format("D:%Y%m%d%H%M%S+00'00'").to_string();
+    let xmp_date = now.format("%Y-%m-%dT%H:%M:%S+00:00").to_string();
+
+    let info_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Title", pdf_string(title)),
+        ("Producer", pdf_string(PRODUCER)),
+        ("CreationDate", pdf_string(&info_date)),
+        ("ModDate", pdf_string(&info_date)),
+    ]));
+    doc.trailer.set("Info", Object::Reference(info_id));
+
+    // Drop the replaced info dictionary and old cross-reference stream, then compress
+    // content before the metadata stream is added so it stays unfiltered
+    doc.prune_objects();
+    doc.compress();
+
+    // PDF/A requires the metadata stream to be stored unfiltered
+    let xmp = build_xmp(title, &xmp_date, part);
+    let metadata = Stream::new(
+        Dictionary::from_iter(vec![
+            ("Type", Object::Name(b"Metadata".to_vec())),
+            ("Subtype", Object::Name(b"XML".to_vec())),
+        ]),
+        xmp.into_bytes(),
+    );
+    let metadata_id = doc.add_object(metadata);
+
+    let catalog = doc
+        .catalog_mut()
+        .map_err(|e| format!("PDF has no catalog: {}", e))?;
+    catalog.set("Metadata", Object::Reference(metadata_id));
+    if let Ok(Object::Array(intents)) = catalog.get_mut(b"OutputIntents") {
+        for intent in intents.iter_mut() {
+            if let Ok(intent) = intent.as_dict_mut() {
+                intent.set("S", Object::Name(b"GTS_PDFA1".to_vec()));
+            }
+        }
+    }
+    // Optional content (layers) arrived in PDF 1.5
+    if matches!(level, PdfALevel::PdfA1b) {
+        catalog.remove(b"OCProperties");
+    }
+
+    doc.save(path)
+        .map_err(|e| format!("Failed to save PDF/A file: {}", e))?;
+
+    Ok(())
+}
+
+fn pdf_string(text: &str) -> Object {
+    Object::String(text.as_bytes().to_vec(), StringFormat::Literal)
+}
+
+fn build_xmp(title: &str, date: &str, part: &str) -> String {
+    format!(
+        r#"<?xpacket begin="{bom}" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+   <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+      <rdf:Description rdf:about="" xmlns:pdfaid="http://www.aiim.org/pdfa/ns/id/">
+         <pdfaid:part>{part}</pdfaid:part>
+         <pdfaid:conformance>B</pdfaid:conformance>
+      </rdf:Description>
+      <rdf:Description rdf:about="" xmlns:dc="http://purl.org/dc/elements/1.1/">
+         <dc:format>application/pdf</dc:format>
+         <dc:title>
+            <rdf:Alt>
+               <rdf:li xml:lang="x-default">{title}</rdf:li>
+            </rdf:Alt>
+         </dc:title>
+      </rdf:Description>
+      <rdf:Description rdf:about="" xmlns:xmp="http://ns.adobe.com/xap/1.0/">
+         <xmp:CreateDate>{date}</xmp:CreateDate>
+         <xmp:ModifyDate>{date}</xmp:ModifyDate>
+      </rdf:Description>
+      <rdf:Description rdf:about="" xmlns:pdf="http://ns.adobe.com/pdf/1.3/">
+         <pdf:Producer>{producer}</pdf:Producer>
+      </rdf:Description>
+   </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#,
+        bom = '\u{feff}',
+        part = part,
+        title = title,
+        date = date,
+        producer = PRODUCER,
+    )
+}
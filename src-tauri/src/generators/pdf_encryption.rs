@@ -0,0 +1,206 @@
+use printpdf::lopdf::{Dictionary, Document, Object, ObjectId, StringFormat};
+use rand::RngCore;
+use std::path::Path;
+
+/// Longest password the security handler reads; anything past it would be ignored
+pub const MAX_PDF_PASSWORD_LEN: usize = 32;
+
+// Filler the standard security handler pads passwords with to 32 bytes
+const PASSWORD_PADDING: [u8; 32] = [
+    0x28, 0xBF, 0x4E, 0x5E, 0x4E, 0x75, 0x8A, 0x41, 0x64, 0x00, 0x4E, 0x56, 0xFF, 0xFA, 0x01, 0x08,
+    0x2E, 0x2E, 0x00, 0xB6, 0xD0, 0x68, 0x3E, 0x80, 0x2F, 0x0C, 0xA9, 0xFE, 0x64, 0x53, 0x69, 0x7A,
+];
+
+// 128-bit RC4, the strongest key revision 3 of the standard security handler takes
+const KEY_LENGTH: usize = 16;
+
+// Every permission granted: the password guards opening the file, not what a
+// reader may do with it afterwards
+const PERMISSIONS: i32 = -4;
+
+/// Check that a password can be used for PDF encryption
+pub fn validate_pdf_password(password: &str) -> Result<(), String> {
+    if password.is_empty() {
+        return Err("PDF password cannot be empty".to_string());
+    }
+    // Non-ASCII passwords are encoded differently from one reader to the next
+    if !password.is_ascii() || password.len() > MAX_PDF_PASSWORD_LEN {
+        return Err(format!(
+            "PDF password must be at most {} ASCII characters",
+            MAX_PDF_PASSWORD_LEN
+        ));
+    }
+    Ok(())
+}
+
+/// Encrypt the PDF at `path` in place with the standard security handler
+/// (revision 3, 128-bit RC4), so that it needs `password` to open.
+///
+/// The same password is used as the owner password, since a scan has no
+/// separate owner to hand restrictions to.
+pub fn encrypt_pdf(path: &Path, password: &str) -> Result<(), String> {
+    let mut doc =
+        Document::load(path).map_err(|e| format!("Failed to reload PDF for encryption: {}", e))?;
+    if doc.is_encrypted() {
+        return Err("PDF is already encrypted".to_string());
+    }
+
+    let file_id = file_id(&mut doc);
+    let owner_entry = owner_password_entry(password.as_bytes());
+    let key = file_key(password.as_bytes(), &owner_entry, &file_id);
+    let user_entry = user_password_entry(&key, &file_id);
+
+    for (&id, object) in doc.objects.iter_mut() {
+        encrypt_object(object, &object_key(&key, id));
+    }
+
+    // Added after the loop above, since the encryption dictionary itself stays readable
+    let encrypt_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Filter", Object::Name(b"Standard".to_vec())),
+        ("V", Object::Integer(2)),
+        ("R", Object::Integer(3)),
+        ("Length", Object::Integer(KEY_LENGTH as i64 * 8)),
+        ("O", Object::String(owner_entry, StringFormat::Hexadecimal)),
+        ("U", Object::String(user_entry, StringFormat::Hexadecimal)),
+        ("P", Object::Integer(PERMISSIONS as i64)),
+    ]));
+    doc.trailer.set("Encrypt", Object::Reference(encrypt_id));
+
+    doc.save(path)
+        .map_err(|e| format!("Failed to save encrypted PDF: {}", e))?;
+    Ok(())
+}
+
+// First half of the trailer's file identifier, which salts the key. Files
+// without one are given a random identifier.
+fn file_id(doc: &mut Document) -> Vec<u8> {
+    let existing = doc
+        .trailer
+        .get(b"ID")
+        .and_then(Object::as_array)
+        .ok()
+        .and_then(|ids| ids.first())
+        .and_then(|id| id.as_str().ok())
+        .map(<[u8]>::to_vec);
+    if let Some(id) = existing {
+        return id;
+    }
+
+    let mut id = vec![0u8; 16];
+    rand::thread_rng().fill_bytes(&mut id);
+    doc.trailer.set(
+        "ID",
+        Object::Array(vec![
+            Object::String(id.clone(), StringFormat::Hexadecimal),
+            Object::String(id.clone(), StringFormat::Hexadecimal),
+        ]),
+    );
+    id
+}
+
+fn padded_password(password: &[u8]) -> [u8; 32] {
+    let mut padded = PASSWORD_PADDING;
+    let len = password.len().min(32);
+    padded[..len].copy_from_slice(&password[..len]);
+    padded[len..].copy_from_slice(&PASSWORD_PADDING[..32 - len]);
+    padded
+}
+
+// The /O entry, the padded user password encrypted under a key derived from
+// the owner password (algorithm 3 of the PDF standard)
+fn owner_password_entry(password: &[u8]) -> Vec<u8> {
+    let mut hash = md5::compute(padded_password(password)).0;
+    for _ in 0..50 {
+        hash = md5::compute(&hash[..KEY_LENGTH]).0;
+    }
+    rc4_rounds(&hash[..KEY_LENGTH], &padded_password(password))
+}
+
+// The document's encryption key (algorithm 2)
+fn file_key(password: &[u8], owner_entry: &[u8], file_id: &[u8]) -> Vec<u8> {
+    let mut context = md5::Context::new();
+    context.consume(padded_password(password));
+    context.consume(owner_entry);
+    context.consume((PERMISSIONS as u32).to_le_bytes());
+    context.consume(file_id);
+
+    let mut hash = context.compute().0;
+    for _ in 0..50 {
+        hash = md5::compute(&hash[..KEY_LENGTH]).0;
+    }
+    hash[..KEY_LENGTH].to_vec()
+}
+
+// The /U entry readers check a password against (algorithm 5). Only the first
+// 16 bytes are compared; the rest is arbitrary padding.
+fn user_password_entry(key: &[u8], file_id: &[u8]) -> Vec<u8> {
+    let mut context = md5::Context::new();
+    context.consume(PASSWORD_PADDING);
+    context.consume(file_id);
+
+    let mut entry = rc4_rounds(key, &context.compute().0);
+    entry.extend_from_slice(&PASSWORD_PADDING[..16]);
+    entry
+}
+
+// RC4 under the key, then 19 more passes under the key XORed with the pass number
+fn rc4_rounds(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut output = rc4(key, data);
+    for round in 1..=19u8 {
+        let round_key: Vec<u8> = key.iter().map(|byte| byte ^ round).collect();
+        output = rc4(&round_key, &output);
+    }
+    output
+}
+
+// Each object is encrypted under the document key salted with its own ID (algorithm 1)
+fn object_key(key: &[u8], (number, generation): ObjectId) -> Vec<u8> {
+    let mut salted = key.to_vec();
+    salted.extend_from_slice(&number.to_le_bytes()[..3]);
+    salted.extend_from_slice(&generation.to_le_bytes()[..2]);
+    md5::compute(&salted).0[..(key.len() + 5).min(16)].to_vec()
+}
+
+// Strings and stream contents are encrypted wherever they appear; names,
+// numbers and references are left as they are
+fn encrypt_object(object: &mut Object, key: &[u8]) {
+    match object {
+        Object::String(bytes, _) => *bytes = rc4(key, bytes),
+        Object::Array(items) => {
+            for item in items {
+                encrypt_object(item, key);
+            }
+        }
+        Object::Dictionary(dict) => encrypt_dictionary(dict, key),
+        Object::Stream(stream) => {
+            encrypt_dictionary(&mut stream.dict, key);
+            stream.content = rc4(key, &stream.content);
+        }
+        _ => {}
+    }
+}
+
+fn encrypt_dictionary(dict: &mut Dictionary, key: &[u8]) {
+    for (_, value) in dict.iter_mut() {
+        encrypt_object(value, key);
+    }
+}
+
+fn rc4(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut state: [u8; 256] = std::array::from_fn(|i| i as u8);
+    let mut j = 0u8;
+    for i in 0..256 {
+        j = j.wrapping_add(state[i]).wrapping_add(key[i % key.len()]);
+        state.swap(i, j as usize);
+    }
+
+    let (mut i, mut j) = (0u8, 0u8);
+    data.iter()
+        .map(|byte| {
+            i = i.wrapping_add(1);
+            j = j.wrapping_add(state[i as usize]);
+            state.swap(i as usize, j as usize);
+            byte ^ state[state[i as usize].wrapping_add(state[j as usize]) as usize]
+        })
+        .collect()
+}
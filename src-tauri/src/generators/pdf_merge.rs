@@ -0,0 +1,142 @@
+use printpdf::lopdf::{Document, Object, ObjectId};
+use std::path::Path;
+
+// Page attributes a page takes from its page tree ancestors when it lacks them
+const INHERITABLE_PAGE_KEYS: [&[u8]; 4] = [b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+
+/// Append every page of the PDF at `source` to the end of the PDF at `target`,
+/// rewriting `target` in place. Returns the page count of the merged document.
+pub fn append_pdf_pages(target: &Path, source: &Path) -> Result<u32, String> {
+    let mut merged =
+        Document::load(target).map_err(|e| format!("Failed to load PDF to append to: {}", e))?;
+    let mut appended =
+        Document::load(source).map_err(|e| format!("Failed to load appended PDF: {}", e))?;
+
+    // Move the appended objects past the IDs the target already uses
+    appended.renumber_objects_with(merged.max_id + 1);
+    let appended_pages: Vec<ObjectId> = appended.get_pages().into_values().collect();
+    for &page_id in &appended_pages {
+        inline_inherited_attributes(&mut appended, page_id)?;
+    }
+
+    // The appended pages join the target's page tree, so the source's own catalog
+    // and page tree root are left behind
+    let appended_catalog = catalog_id(&appended)?;
+    let appended_tree = page_tree_id(&appended)?;
+    let tree_id = page_tree_id(&merged)?;
+    let page_count = (merged.get_pages().len() + appended_pages.len()) as u32;
+    for (id, object) in appended.objects {
+        if id != appended_catalog && id != appended_tree {
+            merged.objects.insert(id, object);
+        }
+    }
+    merged.max_id = merged.max_id.max(appended.max_id);
+
+    for &page_id in &appended_pages {
+        merged
+            .get_dictionary_mut(page_id)
+            .map_err(|e| format!("Appended page is missing: {}", e))?
+            .set("Parent", Object::Reference(tree_id));
+    }
+    let tree = merged
+        .get_dictionary_mut(tree_id)
+        .map_err(|e| format!("PDF has no page tree: {}", e))?;
+    tree.get_mut(b"Kids")
+        .and_then(Object::as_array_mut)
+        .map_err(|e| format!("PDF page tree has no kids: {}", e))?
+        .extend(appended_pages.iter().map(|&id| Object::Reference(id)));
+    tree.set("Count", page_count as i64);
+
+    merged
+        .save(target)
+        .map_err(|e| format!("Failed to save merged PDF: {}", e))?;
+    Ok(page_count)
+}
+
+/// Rewrite the PDF at `path` with its pages in `order`, which lists every page
+/// number of the document, starting at 1, exactly once
+pub fn reorder_pdf_pages(path: &Path, order: &[u32]) -> Result<(), String> {
+    let mut doc = Document::load(path).map_err(|e| format!("Failed to load PDF: {}", e))?;
+    let pages = doc.get_pages();
+
+    let mut sorted = order.to_vec();
+    sorted.sort_unstable();
+    if !sorted.iter().copied().eq(1..=pages.len() as u32) {
+        return Err(format!(
+            "Page order must list each of the {} page numbers exactly once",
+            pages.len()
+        ));
+    }
+
+    // Every page moves directly under the root of the page tree, so it can no
+    // longer inherit from intermediate nodes
+    let tree_id = page_tree_id(&doc)?;
+    let reordered: Vec<ObjectId> = order.iter().map(|number| pages[number]).collect();
+    for &page_id in &reordered {
+        inline_inherited_attributes(&mut doc, page_id)?;
+        doc.get_dictionary_mut(page_id)
+            .map_err(|e| format!("PDF page is missing: {}", e))?
+            .set("Parent", Object::Reference(tree_id));
+    }
+    let tree = doc
+        .get_dictionary_mut(tree_id)
+        .map_err(|e| format!("PDF has no page tree: {}", e))?;
+    tree.set(
+        "Kids",
+        reordered
+            .iter()
+            .map(|&id| Object::Reference(id))
+            .collect::<Vec<_>>(),
+    );
+    tree.set("Count", reordered.len() as i64);
+    doc.prune_objects();
+
+    doc.save(path)
+        .map_err(|e| format!("Failed to save reordered PDF: {}", e))?;
+    Ok(())
+}
+
+fn catalog_id(doc: &Document) -> Result<ObjectId, String> {
+    doc.trailer
+        .get(b"Root")
+        .and_then(Object::as_reference)
+        .map_err(|e| format!("PDF has no catalog: {}", e))
+}
+
+fn page_tree_id(doc: &Document) -> Result<ObjectId, String> {
+    doc.catalog()
+        .and_then(|catalog| catalog.get(b"Pages"))
+        .and_then(Object::as_reference)
+        .map_err(|e| format!("PDF has no page tree: {}", e))
+}
+
+// Copy attributes a page inherits onto the page itself, since it is about to get
+// a parent that does not define them
+fn inline_inherited_attributes(doc: &mut Document, page_id: ObjectId) -> Result<(), String> {
+    let page = doc
+        .get_dictionary(page_id)
+        .map_err(|e| format!("PDF page is missing: {}", e))?;
+
+    let mut inherited: Vec<(&[u8], Object)> = Vec::new();
+    let mut parent = page.get(b"Parent").and_then(Object::as_reference).ok();
+    while let Some(parent_id) = parent {
+        let Ok(node) = doc.get_dictionary(parent_id) else {
+            break;
+        };
+        for key in INHERITABLE_PAGE_KEYS {
+            let known = page.has(key) || inherited.iter().any(|(found, _)| *found == key);
+            if let (false, Ok(value)) = (known, node.get(key)) {
+                inherited.push((key, value.clone()));
+            }
+        }
+        parent = node.get(b"Parent").and_then(Object::as_reference).ok();
+    }
+
+    let page = doc
+        .get_dictionary_mut(page_id)
+        .map_err(|e| format!("PDF page is missing: {}", e))?;
+    for (key, value) in inherited {
+        page.set(key, value);
+    }
+    Ok(())
+}
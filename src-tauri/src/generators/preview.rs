@@ -0,0 +1,400 @@
+use crate::domain::*;
+use crate::generators::{ScanGenerator, EMBEDDED_SERIF_FONT};
+use fontdue::{Font, FontSettings};
+use image::{Rgb, RgbImage};
+use printpdf::lopdf;
+use std::path::{Path, PathBuf};
+
+const PREVIEW_WIDTH: u32 = 200;
+const PREVIEW_MARGIN: u32 = 12;
+const PREVIEW_FONT_PX: f32 = 7.0;
+const PREVIEW_LINE_SPACING: u32 = 9;
+const CONTACT_SHEET_COLUMNS: u32 = 4;
+const CONTACT_SHEET_GAP: u32 = 10;
+
+/// Render a small page mock-up for a scan: a paper-proportioned sheet with
+/// bars standing in for the document's text lines.
+pub fn render_page_preview(document_type: &DocumentType, settings: &ScanSettings) -> RgbImage {
+    let (width, height) = preview_size(settings);
+    render_page(document_type, settings, width, height)
+}
+
+/// Render the page mock-up at an arbitrary pixel size
+pub fn render_page(
+    document_type: &DocumentType,
+    settings: &ScanSettings,
+    width: u32,
+    height: u32,
+) -> RgbImage {
+    let paper = settings.background_color.unwrap_or([255, 255, 255]);
+    let mut page = RgbImage::from_pixel(width, height, Rgb(paper));
+    let ink = preview_ink(&settings.color_mode);
+
+    // Header bar followed by body lines, spaced like the PDF content
+    let margin = width / 10;
+    let line_height = (height / 28).max(2);
+    fill_rect(
+        &mut page,
+        margin,
+        height / 10,
+        width / 2,
+        line_height * 2,
+        ink.0,
+    );
+
+    let body_lines = match document_type {
+        DocumentType::BusinessCard | DocumentType::Receipt => 6,
+        DocumentType::Photo | DocumentType::Image => 0,
+        _ => 14,
+    };
+    // Keep a bottom margin however short the page is
+    let max_lines = (height - height / 5 - height / 10) / (line_height * 2);
+    let body_lines = body_lines.min(max_lines);
+    for line in 0..body_lines {
+        let y = height / 5 + line * line_height * 2;
+        let line_width = if line % 4 == 3 {
+            (width - margin * 2) / 2
+        } else {
+            width - margin * 2
+        };
+        fill_rect(&mut page, margin, y, line_width, line_height, ink.1);
+    }
+
+    // Photos and images get a solid block where the picture would be
+    if body_lines == 0 {
+        fill_rect(
+            &mut page,
+            margin,
+            height / 5,
+            width - margin * 2,
+            height / 2,
+            ink.0,
+        );
+    }
+
+    page
+}
+
+/// Render the raster version of the PDF test pattern at an arbitrary pixel size:
+/// heading, 10 mm grid, grayscale ramp, color bars in color and resolution
+/// targets, placed on the page the way ScanGenerator::test_pattern_placement puts
+/// them. Coordinates below are millimetres of that layout from its bottom left,
+/// as in the PDF.
+pub fn render_test_pattern(settings: &ScanSettings, width: u32, height: u32) -> RgbImage {
+    const INK: [u8; 3] = [0, 0, 0];
+
+    let paper = settings.background_color.unwrap_or([255, 255, 255]);
+    let mut page = RgbImage::from_pixel(width, height, Rgb(paper));
+
+    let (page_width, page_height) = settings.paper_size.dimensions_mm();
+    let (scale, offset_x, offset_y) =
+        ScanGenerator::test_pattern_placement(page_width, page_height);
+    let px_per_mm = width as f32 / page_width;
+    let to_px = |x: f32, y: f32| {
+        (
+            (offset_x + x * scale) * px_per_mm,
+            (page_height - offset_y - y * scale) * px_per_mm,
+        )
+    };
+    // Filled area between two layout corners, at least a pixel wide and high
+    let fill = |page: &mut RgbImage, (x1, y1): (f32, f32), (x2, y2): (f32, f32), color| {
+        let (left, top) = to_px(x1, y2);
+        let (right, bottom) = to_px(x2, y1);
+        fill_rect(
+            page,
+            left.max(0.0).round() as u32,
+            top.max(0.0).round() as u32,
+            ((right - left).round() as u32).max(1),
+            ((bottom - top).round() as u32).max(1),
+            color,
+        );
+    };
+
+    // 10mm grid covering the upper part of the page
+    let (left, right, bottom, top) = (20.0, 190.0, 150.0, 260.0);
+    for step in 0..=17 {
+        let x = left + step as f32 * 10.0;
+        fill(&mut page, (x - 0.15, bottom), (x + 0.15, top), INK);
+    }
+    for step in 0..=11 {
+        let y = bottom + step as f32 * 10.0;
+        fill(&mut page, (left, y - 0.15), (right, y + 0.15), INK);
+    }
+
+    // Grayscale ramp from black to white
+    let steps = 17;
+    let step_width = 170.0 / steps as f32;
+    for step in 0..steps {
+        let level = (step as f32 / (steps - 1) as f32 * 255.0).round() as u8;
+        let x = 20.0 + step as f32 * step_width;
+        fill(&mut page, (x, 120.0), (x + step_width, 138.0), [level; 3]);
+    }
+
+    // Primary color bars only make sense when scanning in color
+    if settings.color_mode == ColorMode::Color {
+        let bars = ScanGenerator::TEST_PATTERN_COLOR_BARS;
+        let bar_width = 170.0 / bars.len() as f32;
+        for (index, bar) in bars.into_iter().enumerate() {
+            let x = 20.0 + index as f32 * bar_width;
+            fill(&mut page, (x, 98.0), (x + bar_width, 116.0), bar);
+        }
+    }
+
+    // Each block packs progressively finer line pairs into the same width, every
+    // line half a pitch wide. Pairs finer than two pixels blur into the average of
+    // ink and paper, as they would on a real scan.
+    let blurred =
+        [0, 1, 2].map(|channel| ((INK[channel] as u16 + paper[channel] as u16) / 2) as u8);
+    for (index, lp_per_mm) in ScanGenerator::TEST_PATTERN_LINE_PAIRS.iter().enumerate() {
+        let block_left = 20.0 + index as f32 * 34.0;
+        let pitch = 1.0 / lp_per_mm;
+        if pitch * scale * px_per_mm < 2.0 {
+            fill(
+                &mut page,
+                (block_left, 50.0),
+                (block_left + 28.0, 80.0),
+                blurred,
+            );
+            continue;
+        }
+        let mut x = block_left;
+        while x < block_left + 28.0 {
+            fill(
+                &mut page,
+                (x - pitch / 4.0, 50.0),
+                (x + pitch / 4.0, 80.0),
+                INK,
+            );
+            x += pitch;
+        }
+    }
+
+    // Labels are left off if the font cannot be loaded; the pattern still shows
+    if let Ok(font) = Font::from_bytes(EMBEDDED_SERIF_FONT, FontSettings::default()) {
+        let mut label = |text: &str, x: f32, y: f32, size_pt: f32| {
+            let (x, baseline) = to_px(x, y);
+            let size_px = size_pt * 25.4 / 72.0 * scale * px_per_mm;
+            draw_text(
+                &mut page,
+                &font,
+                text,
+                (x.max(0.0) as u32, baseline.max(0.0) as u32),
+                size_px,
+                INK,
+            );
+        };
+        label("SCANNER TEST PATTERN", 20.0, 275.0, 18.0);
+        label(
+            &ScanGenerator::test_pattern_caption(settings),
+            20.0,
+            267.0,
+            10.0,
+        );
+        label("Grayscale ramp", 20.0, 142.0, 9.0);
+        label("Resolution targets (line pairs per mm)", 20.0, 88.0, 9.0);
+        for (index, lp_per_mm) in ScanGenerator::TEST_PATTERN_LINE_PAIRS.iter().enumerate() {
+            let block_left = 20.0 + index as f32 * 34.0;
+            label(
+                &ScanGenerator::line_pair_label(*lp_per_mm, scale),
+                block_left,
+                44.0,
+                8.0,
+            );
+        }
+    }
+
+    page
+}
+
+/// Render a preview of the first page of a job's scan file.
+///
+/// Raster output is downscaled; PDF and text output have the text of their first
+/// page drawn onto a paper-sized canvas. Anything that cannot be read falls back
+/// to the page mock-up.
+pub fn render_scan_preview(job: &ScanJob) -> Result<RgbImage, String> {
+    let result = job.scan_result.as_ref().ok_or("Job has no scan result")?;
+    let extension = result
+        .file_path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let first_page_text = match extension.as_str() {
+        "jpg" | "jpeg" | "png" | "tif" | "tiff" => {
+            let image = image::open(&result.file_path)
+                .map_err(|e| format!("Failed to open scan image: {}", e))?;
+            return Ok(image.thumbnail(PREVIEW_WIDTH, u32::MAX).to_rgb8());
+        }
+        "pdf" => pdf_first_page_text(&result.file_path),
+        "txt" => std::fs::read_to_string(&result.file_path).ok(),
+        _ => None,
+    };
+
+    match first_page_text.filter(|text| !text.trim().is_empty()) {
+        Some(text) => render_text_preview(&text, &job.scan_settings),
+        None => Ok(render_page_preview(&job.document_type, &job.scan_settings)),
+    }
+}
+
+/// Write a PNG thumbnail next to the scan output and return its path
+pub fn save_thumbnail(job: &ScanJob, output_path: &Path) -> Result<PathBuf, String> {
+    let preview = render_scan_preview(job)?;
+    let thumbnail_path = sibling_path(output_path, "thumb");
+
+    preview
+        .save(&thumbnail_path)
+        .map_err(|e| format!("Failed to save thumbnail: {}", e))?;
+
+    Ok(thumbnail_path)
+}
+
+/// Tile page previews of several jobs into a single contact sheet PNG
+pub fn save_contact_sheet(jobs: &[ScanJob], output_path: &Path) -> Result<PathBuf, String> {
+    if jobs.is_empty() {
+        return Err("No jobs to include in the contact sheet".to_string());
+    }
+
+    let previews: Vec<RgbImage> = jobs
+        .iter()
+        .map(render_scan_preview)
+        .collect::<Result<_, _>>()?;
+
+    let cell_width = previews.iter().map(|p| p.width()).max().unwrap_or(1);
+    let cell_height = previews.iter().map(|p| p.height()).max().unwrap_or(1);
+    let columns = CONTACT_SHEET_COLUMNS.min(previews.len() as u32);
+    let rows = (previews.len() as u32).div_ceil(columns);
+
+    let sheet_width = columns * cell_width + (columns + 1) * CONTACT_SHEET_GAP;
+    let sheet_height = rows * cell_height + (rows + 1) * CONTACT_SHEET_GAP;
+    let mut sheet = RgbImage::from_pixel(sheet_width, sheet_height, Rgb([64, 64, 64]));
+
+    for (index, preview) in previews.iter().enumerate() {
+        let column = index as u32 % columns;
+        let row = index as u32 / columns;
+        let x = CONTACT_SHEET_GAP + column * (cell_width + CONTACT_SHEET_GAP);
+        let y = CONTACT_SHEET_GAP + row * (cell_height + CONTACT_SHEET_GAP);
+        image::imageops::replace(&mut sheet, preview, x as i64, y as i64);
+    }
+
+    let sheet_path = sibling_path(output_path, "composite");
+    sheet
+        .save(&sheet_path)
+        .map_err(|e| format!("Failed to save contact sheet: {}", e))?;
+
+    Ok(sheet_path)
+}
+
+// Encrypted files, and pages whose fonts lopdf cannot decode, yield no text and use
+// the mock-up instead
+fn pdf_first_page_text(path: &Path) -> Option<String> {
+    let document = lopdf::Document::load(path).ok()?;
+    if document.is_encrypted() {
+        return None;
+    }
+    document.extract_text(&[1]).ok()
+}
+
+fn render_text_preview(text: &str, settings: &ScanSettings) -> Result<RgbImage, String> {
+    let font = Font::from_bytes(EMBEDDED_SERIF_FONT, FontSettings::default())
+        .map_err(|e| format!("Failed to load preview font: {}", e))?;
+
+    let (width, height) = preview_size(settings);
+    let paper = settings.background_color.unwrap_or([255, 255, 255]);
+    let mut page = RgbImage::from_pixel(width, height, Rgb(paper));
+    let ink = preview_ink(&settings.color_mode).0;
+
+    let lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    let mut baseline = PREVIEW_MARGIN + PREVIEW_LINE_SPACING;
+    for line in lines {
+        if baseline + PREVIEW_MARGIN > height {
+            break;
+        }
+        draw_text(
+            &mut page,
+            &font,
+            line,
+            (PREVIEW_MARGIN, baseline),
+            PREVIEW_FONT_PX,
+            ink,
+        );
+        baseline += PREVIEW_LINE_SPACING;
+    }
+
+    Ok(page)
+}
+
+// Draw a single line of text with its baseline at `baseline`, clipped to the image
+fn draw_text(
+    image: &mut RgbImage,
+    font: &Font,
+    text: &str,
+    (x, baseline): (u32, u32),
+    size_px: f32,
+    ink: [u8; 3],
+) {
+    let mut pen_x = x as f32;
+    for character in text.chars() {
+        let (metrics, coverage) = font.rasterize(character, size_px);
+        let left = pen_x as i32 + metrics.xmin;
+        let top = baseline as i32 - metrics.height as i32 - metrics.ymin;
+
+        for row in 0..metrics.height {
+            for column in 0..metrics.width {
+                let px = left + column as i32;
+                let py = top + row as i32;
+                if px < 0 || py < 0 || px >= image.width() as i32 || py >= image.height() as i32 {
+                    continue;
+                }
+
+                let alpha = coverage[row * metrics.width + column] as f32 / 255.0;
+                let pixel = image.get_pixel_mut(px as u32, py as u32);
+                for (channel, ink_channel) in pixel.0.iter_mut().zip(ink) {
+                    let blended = *channel as f32 * (1.0 - alpha) + ink_channel as f32 * alpha;
+                    *channel = blended.round() as u8;
+                }
+            }
+        }
+
+        pen_x += metrics.advance_width;
+        if pen_x as u32 + PREVIEW_MARGIN > image.width() {
+            break;
+        }
+    }
+}
+
+// Preview dimensions: fixed width, height following the aspect ratio of the paper
+// or of its scan area
+fn preview_size(settings: &ScanSettings) -> (u32, u32) {
+    let (width_mm, height_mm) = ScanGenerator::with_scan_area(settings)
+        .paper_size
+        .dimensions_mm();
+    let height = ((PREVIEW_WIDTH as f32 * height_mm / width_mm).round() as u32).max(1);
+    (PREVIEW_WIDTH, height)
+}
+
+fn sibling_path(output_path: &Path, suffix: &str) -> PathBuf {
+    let stem = output_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "scan".to_string());
+    output_path.with_file_name(format!("{}_{}.png", stem, suffix))
+}
+
+// Heading and body colors for each color mode
+fn preview_ink(color_mode: &ColorMode) -> ([u8; 3], [u8; 3]) {
+    match color_mode {
+        ColorMode::Color => ([30, 70, 160], [90, 90, 90]),
+        ColorMode::Grayscale => ([60, 60, 60], [140, 140, 140]),
+        ColorMode::BlackAndWhite => ([0, 0, 0], [0, 0, 0]),
+    }
+}
+
+fn fill_rect(image: &mut RgbImage, x: u32, y: u32, width: u32, height: u32, color: [u8; 3]) {
+    let x_end = (x + width).min(image.width());
+    let y_end = (y + height).min(image.height());
+    for py in y..y_end {
+        for px in x..x_end {
+            image.put_pixel(px, py, Rgb(color));
+        }
+    }
+}
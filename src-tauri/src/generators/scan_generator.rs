@@ -1,62 +1,548 @@
 use crate::domain::*;
+use crate::generators::{
+    apply_pdf_a_conformance, check_disk_space, embed_jpeg_exif, encrypt_pdf, estimated_file_size,
+    file_checksum, pad_file_to_size, page_breakdown, pdf_a_conformance, render_filename_template,
+    render_page, render_test_pattern, ContentLocale, FilenameFields, Margins, PageLayout,
+    DEFAULT_FILENAME_TEMPLATE,
+};
+use ::image::codecs::jpeg::{JpegEncoder, PixelDensity};
+use ::image::codecs::png::{CompressionType, FilterType, PngEncoder};
+use ::image::{DynamicImage, GrayImage, Luma, Pixel, RgbImage};
 use printpdf::*;
-use rand::Rng;
+use qrcode::QrCode;
 use std::fs;
-use std::io::BufWriter;
-use std::path::PathBuf;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tiff::encoder::compression::DeflateLevel;
+use tiff::encoder::{colortype, Compression, Rational, TiffEncoder};
+use tiff::tags::ResolutionUnit;
+
+// PDF/A forbids the non-embedded standard fonts, so compliant output embeds this instead
+pub const EMBEDDED_SERIF_FONT: &[u8] = include_bytes!("../../assets/fonts/DejaVuSerif.ttf");
 
 pub struct ScanGenerator;
 
+/// Failed generation, with the pages completed before the failure when any could
+/// be salvaged into a file of their own
+#[derive(Debug)]
+pub struct GenerationError {
+    pub message: String,
+    pub partial_result: Option<ScanResult>,
+}
+
+impl From<String> for GenerationError {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            partial_result: None,
+        }
+    }
+}
+
+impl std::fmt::Display for GenerationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+// Error from a multi-page generator, recording how many output pages were done
+struct PageFailure {
+    message: String,
+    pages_completed: u32,
+}
+
+// On-disk format of the output directory chosen with set_output_directory
+#[derive(serde::Serialize, serde::Deserialize)]
+struct OutputDirectorySetting {
+    output_directory: PathBuf,
+}
+
+impl From<String> for PageFailure {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            pages_completed: 0,
+        }
+    }
+}
+
 impl ScanGenerator {
-    /// Generate a realistic scan file based on document type and settings
-    pub async fn generate_scan_file(
+    /// Largest raster page, in pixels, generated before the resolution is lowered
+    pub const MAX_RASTER_PIXELS: u64 = 150_000_000;
+
+    /// Longest raster side in pixels, kept under the 65,535 pixel JPEG limit
+    pub const MAX_RASTER_SIDE: u32 = 65_000;
+
+    /// Width and height in millimetres of the page the test pattern is laid out on
+    pub const TEST_PATTERN_PAGE: (f32, f32) = (210.0, 297.0);
+
+    /// Line pairs per millimetre of the test pattern's resolution targets
+    pub const TEST_PATTERN_LINE_PAIRS: [f32; 5] = [0.5, 1.0, 2.0, 4.0, 8.0];
+
+    /// Primary and secondary color bars of test patterns scanned in color
+    pub const TEST_PATTERN_COLOR_BARS: [[u8; 3]; 6] = [
+        [255, 0, 0],
+        [0, 255, 0],
+        [0, 0, 255],
+        [0, 255, 255],
+        [255, 0, 255],
+        [255, 255, 0],
+    ];
+
+    /// Locale codes with content templates, paired with their display names
+    pub const SUPPORTED_LOCALES: &'static [(&'static str, &'static str)] = ContentLocale::SUPPORTED;
+
+    pub fn get_supported_locales() -> Vec<LocaleInfo> {
+        Self::SUPPORTED_LOCALES
+            .iter()
+            .map(|(code, display_name)| LocaleInfo {
+                code: code.to_string(),
+                display_name: display_name.to_string(),
+            })
+            .collect()
+    }
+
+    pub fn is_locale_supported(locale: &str) -> bool {
+        Self::SUPPORTED_LOCALES
+            .iter()
+            .any(|(code, _)| code.eq_ignore_ascii_case(locale))
+    }
+
+    /// Describe what generate_scan_file will do for these settings, one step per entry
+    pub fn describe_generation(
         document_type: &DocumentType,
         settings: &ScanSettings,
-        output_path: &PathBuf,
-    ) -> Result<ScanResult, String> {
+    ) -> Vec<String> {
+        let mut steps = Vec::new();
+
+        if let Some(area) = &settings.scan_area {
+            steps.push(format!(
+                "Crop to the {}x{} mm area at ({}, {}) mm of the {:?} page",
+                area.width, area.height, area.x, area.y, settings.paper_size
+            ));
+        }
+
+        let requested_resolution = settings.resolution;
+        let settings = &Self::with_raster_resolution(&Self::with_scan_area(settings));
+        if settings.resolution < requested_resolution {
+            steps.push(format!(
+                "Lower the resolution from {} to {} DPI to keep the image within {} megapixels",
+                requested_resolution,
+                settings.resolution,
+                Self::MAX_RASTER_PIXELS / 1_000_000
+            ));
+        }
+
+        match settings.output_format {
+            OutputFormat::Pdf => {
+                steps.push(format!(
+                    "Render {:?} content on {} {:?} PDF page(s) in {:?}, each with a header bar and a page number footer",
+                    document_type,
+                    settings.page_count.max(1),
+                    settings.paper_size,
+                    settings.color_mode
+                ));
+                if *document_type == DocumentType::Barcode {
+                    let payload = match &settings.barcode_payload {
+                        Some(payload) => format!("\"{}\"", payload),
+                        None => "the job ID".to_string(),
+                    };
+                    steps.push(format!(
+                        "Encode {} as a QR code at the top of each front page",
+                        payload
+                    ));
+                }
+                if settings.duplex {
+                    steps.push("Add a reverse side page after each front page".to_string());
+                }
+                if let Some([r, g, b]) = settings.background_color {
+                    steps.push(format!("Tint the page background RGB({}, {}, {})", r, g, b));
+                }
+                if let Some(watermark) = &settings.watermark {
+                    steps.push(format!(
+                        "Stamp a \"{}\" watermark and a job ID and timestamp footer on every page",
+                        watermark
+                    ));
+                }
+                if let Some(level) = &settings.pdf_a_level {
+                    steps.push(format!(
+                        "Embed the DejaVu Serif font and convert to {:?}",
+                        level
+                    ));
+                }
+                if settings.pdf_password.is_some() {
+                    steps.push(
+                        "Encrypt the PDF with 128-bit RC4 so it needs the password to open"
+                            .to_string(),
+                    );
+                }
+            }
+            OutputFormat::Jpeg => {
+                let (width, height) = settings.paper_size.pixel_dimensions(settings.resolution);
+                steps.push(format!(
+                    "Render {:?} content as a {}x{} JPEG at quality {}",
+                    document_type,
+                    width,
+                    height,
+                    settings.quality.clamp(1, 100)
+                ));
+                if settings.include_exif {
+                    steps.push("Embed EXIF scan metadata".to_string());
+                }
+            }
+            OutputFormat::Png => {
+                let (width, height) = settings.paper_size.pixel_dimensions(settings.resolution);
+                let channels = match settings.color_mode {
+                    ColorMode::Color => "RGB",
+                    ColorMode::Grayscale => "grayscale",
+                    ColorMode::BlackAndWhite => "black and white",
+                };
+                steps.push(format!(
+                    "Render {:?} content as a {}x{} {} PNG",
+                    document_type, width, height, channels
+                ));
+            }
+            OutputFormat::Tiff => {
+                let (width, height) = settings.paper_size.pixel_dimensions(settings.resolution);
+                steps.push(format!(
+                    "Render {:?} content as {} {}x{} {:?} TIFF frame(s), {:?} compression",
+                    document_type,
+                    settings.page_count.max(1),
+                    width,
+                    height,
+                    settings.color_mode,
+                    settings.compression
+                ));
+                if settings.duplex {
+                    steps.push("Add a blank reverse side frame after each front frame".to_string());
+                }
+            }
+        }
+
+        if settings.brightness != 0 || settings.contrast != 0 {
+            if settings.output_format == OutputFormat::Pdf {
+                steps.push(
+                    "Skip the brightness and contrast adjustment, which needs raster output"
+                        .to_string(),
+                );
+            } else {
+                steps.push(format!(
+                    "Adjust brightness by {:+} and contrast by {:+}",
+                    settings.brightness, settings.contrast
+                ));
+            }
+        }
+        if settings.color_mode == ColorMode::BlackAndWhite
+            && matches!(
+                settings.output_format,
+                OutputFormat::Png | OutputFormat::Tiff
+            )
+        {
+            steps.push(if settings.dither {
+                "Dither the gray levels to black and white dots".to_string()
+            } else {
+                "Threshold the gray levels to black and white".to_string()
+            });
+        }
+        if settings.include_exif && settings.output_format != OutputFormat::Jpeg {
+            steps.push("Skip EXIF metadata, which needs a JPEG file".to_string());
+        }
+        steps.push(format!(
+            "Pad the file to {} bytes to match {} DPI at quality {} with {:?} compression",
+            estimated_file_size(settings, Self::output_page_count(settings)),
+            settings.resolution,
+            settings.quality.clamp(1, 100),
+            settings.compression
+        ));
+
+        steps
+    }
+
+    /// Size in bytes generate_scan_file will give a scan with these settings,
+    /// computed from the same size model without generating anything
+    pub fn estimate_file_size(settings: &ScanSettings) -> Result<u64, String> {
+        settings.paper_size.validate()?;
+        if let Some(area) = &settings.scan_area {
+            area.validate(&settings.paper_size)?;
+        }
+        let settings = Self::with_raster_resolution(&Self::with_scan_area(
+            &settings.clone().with_adf_sheets(),
+        ));
+        Ok(estimated_file_size(
+            &settings,
+            Self::output_page_count(&settings),
+        ))
+    }
+
+    /// Pages written to the output file: PDF and TIFF hold every scanned side,
+    /// JPEG and PNG a single page
+    pub fn output_page_count(settings: &ScanSettings) -> u32 {
         match settings.output_format {
-            OutputFormat::Pdf => Self::generate_pdf(document_type, settings, output_path).await,
-            _ => Self::generate_text_file(document_type, settings, output_path).await,
+            OutputFormat::Pdf | OutputFormat::Tiff => {
+                let sides = if settings.duplex { 2 } else { 1 };
+                settings.page_count.max(1) * sides
+            }
+            OutputFormat::Jpeg | OutputFormat::Png => 1,
         }
     }
 
-    async fn generate_pdf(
+    /// Settings with the paper size narrowed to the scan area, if one is set, so every
+    /// output format is generated at the cropped size
+    pub fn with_scan_area(settings: &ScanSettings) -> ScanSettings {
+        let mut settings = settings.clone();
+        if let Some(area) = settings.scan_area.take() {
+            settings.paper_size = PaperSize::Custom {
+                width: area.width,
+                height: area.height,
+            };
+        }
+        settings
+    }
+
+    /// Settings with the resolution lowered, for raster output, to the highest DPI
+    /// whose page fits MAX_RASTER_PIXELS and MAX_RASTER_SIDE. PDF output is
+    /// vector and keeps the requested resolution.
+    pub fn with_raster_resolution(settings: &ScanSettings) -> ScanSettings {
+        let mut settings = settings.clone();
+        if settings.output_format == OutputFormat::Pdf {
+            return settings;
+        }
+
+        let (width_mm, height_mm) = settings.paper_size.dimensions_mm();
+        let width_in = width_mm as f64 / 25.4;
+        let height_in = height_mm as f64 / 25.4;
+        if width_in <= 0.0 || height_in <= 0.0 {
+            return settings;
+        }
+
+        let area_limit = (Self::MAX_RASTER_PIXELS as f64 / (width_in * height_in)).sqrt();
+        let side_limit = Self::MAX_RASTER_SIDE as f64 / width_in.max(height_in);
+        let max_resolution = area_limit.min(side_limit).floor().max(1.0) as u32;
+
+        settings.resolution = settings.resolution.min(max_resolution);
+        settings
+    }
+
+    /// Generate a realistic scan file based on document type and settings. When a
+    /// multi-page file fails partway, the pages completed so far are salvaged into
+    /// the output file and returned with the error.
+    pub async fn generate_scan_file(
+        job_id: &str,
         document_type: &DocumentType,
         settings: &ScanSettings,
         output_path: &PathBuf,
-    ) -> Result<ScanResult, String> {
-        let (doc, page1, layer1) = PdfDocument::new(
-            "Scanned Document",
-            Mm(210.0), // A4 width
-            Mm(297.0), // A4 height
-            "Layer 1",
-        );
-
-        let font = doc
-            .add_builtin_font(BuiltinFont::TimesRoman)
-            .map_err(|e| format!("Failed to add font: {}", e))?;
+    ) -> Result<ScanResult, GenerationError> {
+        settings.paper_size.validate()?;
+        if let Some(area) = &settings.scan_area {
+            area.validate(&settings.paper_size)?;
+        }
+        let settings = &Self::with_raster_resolution(&Self::with_scan_area(settings));
+        // Filename templates can place the file in a subdirectory
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create output directory: {}", e))?;
+            // A full disk would otherwise fail partway through writing
+            check_disk_space(
+                parent,
+                estimated_file_size(settings, Self::output_page_count(settings)),
+            )?;
+        }
 
-        let current_layer = doc.get_page(page1).get_layer(layer1);
+        match Self::generate_pages(job_id, document_type, settings, output_path).await {
+            Ok(result) => Self::finish_scan_file(settings, result).map_err(|e| {
+                // Padding, PDF/A conversion or encryption stopped partway
+                let _ = fs::remove_file(output_path);
+                GenerationError::from(e)
+            }),
+            Err(failure) => Err(GenerationError {
+                partial_result: Self::salvage_pages(
+                    job_id,
+                    document_type,
+                    settings,
+                    output_path,
+                    failure.pages_completed,
+                )
+                .await,
+                message: failure.message,
+            }),
+        }
+    }
 
-        // Generate content based on document type
-        match document_type {
-            DocumentType::Text => {
-                Self::add_text_content(&current_layer, &font)?;
-            }
-            DocumentType::Invoice => {
-                Self::add_invoice_content(&current_layer, &font)?;
+    async fn generate_pages(
+        job_id: &str,
+        document_type: &DocumentType,
+        settings: &ScanSettings,
+        output_path: &PathBuf,
+    ) -> Result<ScanResult, PageFailure> {
+        let document = |settings: &ScanSettings, width, height| {
+            render_page(document_type, settings, width, height)
+        };
+        Ok(match settings.output_format {
+            OutputFormat::Pdf => {
+                Self::generate_pdf(job_id, document_type, settings, output_path).await?
             }
-            DocumentType::Contract => {
-                Self::add_contract_content(&current_layer, &font)?;
+            OutputFormat::Jpeg => Self::generate_jpeg(&document, settings, output_path).await?,
+            OutputFormat::Png => Self::generate_png(&document, settings, output_path).await?,
+            OutputFormat::Tiff => Self::generate_tiff(&document, settings, output_path).await?,
+        })
+    }
+
+    // Regenerate only the whole sheets completed before a failure into the same
+    // file. Returns None, leaving no file behind, when there is nothing to salvage.
+    async fn salvage_pages(
+        job_id: &str,
+        document_type: &DocumentType,
+        settings: &ScanSettings,
+        output_path: &PathBuf,
+        pages_completed: u32,
+    ) -> Option<ScanResult> {
+        let sheets = if settings.duplex {
+            pages_completed / 2
+        } else {
+            pages_completed
+        };
+        let partial = if sheets == 0 {
+            None
+        } else {
+            let partial_settings = ScanSettings {
+                page_count: sheets,
+                ..settings.clone()
+            };
+            match Self::generate_pages(job_id, document_type, &partial_settings, output_path).await
+            {
+                Ok(result) => Self::finish_scan_file(&partial_settings, result).ok(),
+                Err(_) => None,
             }
-            DocumentType::Receipt => {
-                Self::add_receipt_content(&current_layer, &font)?;
+        };
+
+        match &partial {
+            Some(result) => println!(
+                "Salvaged {} of {} pages into {:?}",
+                result.pages,
+                Self::output_page_count(settings),
+                output_path
+            ),
+            None => {
+                let _ = fs::remove_file(output_path);
             }
-            DocumentType::BusinessCard => {
-                Self::add_business_card_content(&current_layer, &font)?;
+        }
+        partial
+    }
+
+    fn finish_scan_file(
+        settings: &ScanSettings,
+        mut result: ScanResult,
+    ) -> Result<ScanResult, String> {
+        if settings.include_exif && result.format == OutputFormat::Jpeg {
+            embed_jpeg_exif(&result.file_path, settings, &result.scan_time)?;
+            result.exif_embedded = true;
+            result.file_size = std::fs::metadata(&result.file_path)
+                .map_err(|e| format!("Failed to get file size: {}", e))?
+                .len();
+        }
+
+        // Padding goes last so EXIF and PDF/A rewrites are part of the measured size
+        let target = estimated_file_size(settings, result.pages);
+        result.file_size = pad_file_to_size(&result.file_path, &result.format, target)?;
+        result.checksum = file_checksum(&result.file_path)?;
+        result.page_info = page_breakdown(&result)?;
+
+        Ok(result)
+    }
+
+    async fn generate_pdf(
+        job_id: &str,
+        document_type: &DocumentType,
+        settings: &ScanSettings,
+        output_path: &PathBuf,
+    ) -> Result<ScanResult, PageFailure> {
+        let scan_time = chrono::Utc::now();
+        let (page_width, page_height) = Self::page_size(&settings.paper_size);
+        let (doc, page1, layer1) =
+            PdfDocument::new("Scanned Document", page_width, page_height, "Layer 1");
+        let doc = Self::apply_conformance(doc, settings);
+
+        let font = Self::add_document_font(&doc, settings, BuiltinFont::TimesRoman)?;
+        let locale = ContentLocale::from_settings(settings);
+
+        // Duplex scans follow every front page with its reverse side
+        let page_count = Self::output_page_count(settings);
+        for page_number in 1..=page_count {
+            let current_layer = if page_number == 1 {
+                doc.get_page(page1).get_layer(layer1)
+            } else {
+                let (page, layer) = doc.add_page(page_width, page_height, "Layer 1");
+                doc.get_page(page).get_layer(layer)
+            };
+            Self::fill_page_background(
+                &current_layer,
+                page_width.0,
+                page_height.0,
+                settings.background_color,
+                &settings.color_mode,
+            );
+            Self::add_header_bar(
+                &current_layer,
+                page_width.0,
+                page_height.0,
+                &settings.color_mode,
+            );
+            // Drawn before the content so the content stays legible on top of it
+            if let Some(watermark) = &settings.watermark {
+                Self::add_watermark(
+                    &current_layer,
+                    &font,
+                    watermark,
+                    page_width.0,
+                    page_height.0,
+                    &settings.color_mode,
+                );
             }
-            _ => {
-                Self::add_generic_content(&current_layer, &font, document_type)?;
+            let mut layout =
+                PageLayout::new(&current_layer, &font, page_height.0, Margins::default());
+
+            let content = if settings.duplex && page_number % 2 == 0 {
+                Self::add_reverse_side_content(&mut layout)
+            } else {
+                // Generate content based on document type
+                match document_type {
+                    DocumentType::Text => Self::add_text_content(&mut layout),
+                    DocumentType::Invoice => Self::add_invoice_content(&mut layout, locale),
+                    DocumentType::Contract => Self::add_contract_content(&mut layout, locale),
+                    DocumentType::Receipt => Self::add_receipt_content(&mut layout, locale),
+                    DocumentType::BusinessCard => Self::add_business_card_content(&mut layout),
+                    DocumentType::Barcode => Self::add_barcode_content(
+                        &mut layout,
+                        settings.barcode_payload.as_deref().unwrap_or(job_id),
+                    ),
+                    _ => Self::add_generic_content(&mut layout, document_type),
+                }
+            };
+            content.map_err(|message| PageFailure {
+                message,
+                pages_completed: page_number - 1,
+            })?;
+
+            // Footer sits inside the bottom margin, below any flowed content
+            current_layer.use_text(
+                format!("Page {} of {}", page_number, page_count),
+                9.0,
+                Mm(page_width.0 / 2.0 - 10.0),
+                Mm(10.0),
+                &font,
+            );
+            if settings.watermark.is_some() {
+                current_layer.use_text(
+                    format!(
+                        "Job {} - {}",
+                        job_id,
+                        scan_time.format("%Y-%m-%d %H:%M:%S UTC")
+                    ),
+                    7.0,
+                    Mm(Margins::default().left),
+                    Mm(5.0),
+                    &font,
+                );
             }
         }
 
@@ -67,6 +553,15 @@ impl ScanGenerator {
         ))
         .map_err(|e| format!("Failed to save PDF: {}", e))?;
 
+        if let Some(level) = &settings.pdf_a_level {
+            apply_pdf_a_conformance(output_path, level, "Scanned Document")?;
+        }
+        Self::compress_pdf_streams(output_path, settings.compression)?;
+        // Last, since the other rewrites need the content readable
+        if let Some(password) = &settings.pdf_password {
+            encrypt_pdf(output_path, password)?;
+        }
+
         let file_size = std::fs::metadata(output_path)
             .map_err(|e| format!("Failed to get file size: {}", e))?
             .len();
@@ -74,479 +569,1248 @@ impl ScanGenerator {
         Ok(ScanResult {
             file_path: output_path.clone(),
             file_size,
-            pages: 1,
+            pages: page_count,
             resolution: settings.resolution,
             color_mode: settings.color_mode,
             format: settings.output_format,
-            scan_time: chrono::Utc::now(),
+            scan_time,
+            is_test_scan: false,
+            exif_embedded: false,
+            pdf_a_level: settings.pdf_a_level,
+            upload: None,
+            preview_path: None,
+            ocr_text: None,
+            checksum: String::new(),
+            encrypted: settings.pdf_password.is_some(),
+            page_info: Vec::new(),
+            is_preview: false,
         })
     }
 
-    async fn generate_text_file(
-        document_type: &DocumentType,
+    /// Re-encode every stream printpdf and the PDF/A conversion left Flate compressed,
+    /// or uncompressed, at the requested level. Streams in other encodings, such
+    /// as JPEG images, are left alone, as is the XMP metadata PDF/A keeps unfiltered.
+    fn compress_pdf_streams(path: &Path, compression: CompressionLevel) -> Result<(), String> {
+        let level = match compression {
+            CompressionLevel::None => None,
+            CompressionLevel::Fast => Some(flate2::Compression::fast()),
+            CompressionLevel::Balanced => Some(flate2::Compression::default()),
+            CompressionLevel::Maximum => Some(flate2::Compression::best()),
+        };
+
+        let mut doc =
+            lopdf::Document::load(path).map_err(|e| format!("Failed to reload PDF: {}", e))?;
+        for object in doc.objects.values_mut() {
+            let lopdf::Object::Stream(stream) = object else {
+                continue;
+            };
+            if stream.dict.type_is(b"Metadata") {
+                continue;
+            }
+            stream.decompress();
+            let Some(level) = level else {
+                continue;
+            };
+            if !stream.allows_compression || stream.dict.has(b"Filter") {
+                continue;
+            }
+
+            let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), level);
+            encoder
+                .write_all(&stream.content)
+                .and_then(|_| encoder.finish())
+                .map(|compressed| {
+                    stream.dict.set("Filter", "FlateDecode");
+                    stream.set_content(compressed);
+                })
+                .map_err(|e| format!("Failed to compress PDF stream: {}", e))?;
+        }
+        doc.save(path)
+            .map_err(|e| format!("Failed to save PDF: {}", e))?;
+
+        Ok(())
+    }
+
+    async fn generate_jpeg(
+        render: &impl Fn(&ScanSettings, u32, u32) -> RgbImage,
         settings: &ScanSettings,
         output_path: &PathBuf,
     ) -> Result<ScanResult, String> {
-        let content = Self::generate_text_content(document_type, settings);
-
-        // Change extension to .txt for non-PDF formats
-        let mut text_path = output_path.clone();
-        text_path.set_extension("txt");
+        let (width, height) = settings.paper_size.pixel_dimensions(settings.resolution);
+        let mut page = render(settings, width, height);
+        Self::adjust_tone(&mut page, settings);
 
-        fs::write(&text_path, content).map_err(|e| format!("Failed to write text file: {}", e))?;
+        let file = std::fs::File::create(output_path)
+            .map_err(|e| format!("Failed to create JPEG file: {}", e))?;
+        let mut encoder =
+            JpegEncoder::new_with_quality(BufWriter::new(file), settings.quality.clamp(1, 100));
+        // JFIF density is 16-bit, so resolutions beyond that are left to EXIF
+        if let Ok(dpi) = u16::try_from(settings.resolution) {
+            encoder.set_pixel_density(PixelDensity::dpi(dpi));
+        }
+        encoder
+            .encode_image(&page)
+            .map_err(|e| format!("Failed to encode JPEG: {}", e))?;
 
-        let file_size = std::fs::metadata(&text_path)
+        let file_size = std::fs::metadata(output_path)
             .map_err(|e| format!("Failed to get file size: {}", e))?
             .len();
 
         Ok(ScanResult {
-            file_path: text_path,
+            file_path: output_path.clone(),
             file_size,
             pages: 1,
             resolution: settings.resolution,
             color_mode: settings.color_mode,
-            format: settings.output_format,
+            format: OutputFormat::Jpeg,
             scan_time: chrono::Utc::now(),
+            is_test_scan: false,
+            exif_embedded: false,
+            pdf_a_level: None,
+            upload: None,
+            preview_path: None,
+            ocr_text: None,
+            checksum: String::new(),
+            encrypted: false,
+            page_info: Vec::new(),
+            is_preview: false,
         })
     }
 
-    fn generate_text_content(document_type: &DocumentType, settings: &ScanSettings) -> String {
-        let quality_note = format!(
-            "\n[Scanned at {} DPI, {} quality, {} mode]\n\n",
-            settings.resolution,
-            settings.quality,
-            match settings.color_mode {
-                ColorMode::BlackAndWhite => "B&W",
-                ColorMode::Grayscale => "Grayscale",
-                ColorMode::Color => "Color",
-            }
-        );
+    async fn generate_png(
+        render: &impl Fn(&ScanSettings, u32, u32) -> RgbImage,
+        settings: &ScanSettings,
+        output_path: &PathBuf,
+    ) -> Result<ScanResult, String> {
+        let (width, height) = settings.paper_size.pixel_dimensions(settings.resolution);
+        let mut page = Self::render_original(render, settings, width, height);
+        Self::adjust_tone(&mut page, settings);
 
-        match document_type {
-            DocumentType::Text => format!(
-                "MEMORANDUM{}\
-                TO: Development Team\n\
-                FROM: Scanner Tool Project Manager\n\
-                DATE: {}\n\
-                RE: Scanner Tool Implementation Test\n\n\
-                This document serves as a test of the scanner simulation functionality.\n\n\
-                The implementation includes:\n\
-                • Document type recognition\n\
-                • Multiple output formats (PDF, JPEG, PNG, TIFF)\n\
-                • Realistic scan quality simulation\n\
-                • File generation capabilities\n\
-                • Cross-platform scanner detection\n\n\
-                Scanner Settings Used:\n\
-                - Resolution: {} DPI\n\
-                - Color Mode: {:?}\n\
-                - Paper Size: {:?}\n\
-                - Output Format: {:?}\n\
-                - Quality: {}%\n\
-                - Duplex: {}\n\n\
-                Please review and provide feedback on the scanning functionality.\n\n\
-                Best regards,\n\
-                Scanner Tool Team",
-                quality_note,
-                chrono::Utc::now().format("%Y-%m-%d"),
-                settings.resolution,
-                settings.color_mode,
-                settings.paper_size,
-                settings.output_format,
-                settings.quality,
-                if settings.duplex { "Yes" } else { "No" }
-            ),
+        let compression = match settings.compression {
+            CompressionLevel::None => CompressionType::Uncompressed,
+            CompressionLevel::Fast => CompressionType::Fast,
+            CompressionLevel::Balanced => CompressionType::Default,
+            CompressionLevel::Maximum => CompressionType::Best,
+        };
+        let file = std::fs::File::create(output_path)
+            .map_err(|e| format!("Failed to create PNG file: {}", e))?;
+        Self::convert_to_color_mode(page, settings)
+            .write_with_encoder(PngEncoder::new_with_quality(
+                BufWriter::new(file),
+                compression,
+                FilterType::Adaptive,
+            ))
+            .map_err(|e| format!("Failed to save PNG: {}", e))?;
 
-            DocumentType::Invoice => format!(
-                "INVOICE{}\
-                Invoice #: INV-{}\n\
-                Date: {}\n\
-                Due Date: {}\n\n\
-                BILL TO:\n\
-                Scanner Tool Test Customer\n\
-                123 Business Street\n\
-                Technology City, TC 12345\n\
-                United States\n\n\
-                DESCRIPTION                    QTY    RATE      AMOUNT\n\
-                ================================================\n\
-                Scanner Tool License            1    $299.00   $299.00\n\
-                Technical Support (5 hrs)      5     $50.00   $250.00\n\
-                Implementation Services         1    $150.00   $150.00\n\
-                ================================================\n\
-                                          SUBTOTAL:   $699.00\n\
-                                          TAX (8.5%):  $59.42\n\
-                                          TOTAL:      $758.42\n\n\
-                Payment Terms: Net 30 days\n\
-                Thank you for your business!",
-                quality_note,
-                rand::thread_rng().gen_range(10000..99999),
-                chrono::Utc::now().format("%Y-%m-%d"),
-                (chrono::Utc::now() + chrono::Duration::days(30)).format("%Y-%m-%d")
-            ),
+        let file_size = std::fs::metadata(output_path)
+            .map_err(|e| format!("Failed to get file size: {}", e))?
+            .len();
 
-            DocumentType::Contract => format!(
-                "SOFTWARE LICENSE AGREEMENT{}\
-                This Software License Agreement ('Agreement') is entered into\n\
-                between Scanner Tool Corporation ('Licensor') and the end user\n\
-                ('Licensee') effective as of {}.\n\n\
-                1. GRANT OF LICENSE\n\
-                Licensor hereby grants to Licensee a non-exclusive, non-transferable\n\
-                license to use the Scanner Tool software in accordance with the terms\n\
-                and conditions set forth herein.\n\n\
-                2. PERMITTED USES\n\
-                Licensee may:\n\
-                (a) Install and use the software on authorized devices\n\
-                (b) Create backup copies for archival purposes\n\
-                (c) Use the software for legitimate business purposes\n\n\
-                3. RESTRICTIONS\n\
-                Licensee may not:\n\
-                (a) Modify, adapt, or create derivative works\n\
-                (b) Reverse engineer, decompile, or disassemble\n\
-                (c) Distribute, sublicense, or transfer rights\n\n\
-                4. TERMINATION\n\
-                This Agreement terminates automatically if Licensee breaches\n\
-                any term hereof. Upon termination, Licensee must destroy all\n\
-                copies of the software.\n\n\
-                5. WARRANTY DISCLAIMER\n\
-                THE SOFTWARE IS PROVIDED 'AS IS' WITHOUT WARRANTY OF ANY KIND.\n\n\
-                Signature: ________________________________\n\
-                Date: ____________________________________\n\
-                Print Name: _______________________________",
-                quality_note,
-                chrono::Utc::now().format("%B %d, %Y")
-            ),
+        Ok(ScanResult {
+            file_path: output_path.clone(),
+            file_size,
+            pages: 1,
+            resolution: settings.resolution,
+            color_mode: settings.color_mode,
+            format: OutputFormat::Png,
+            scan_time: chrono::Utc::now(),
+            is_test_scan: false,
+            exif_embedded: false,
+            pdf_a_level: None,
+            upload: None,
+            preview_path: None,
+            ocr_text: None,
+            checksum: String::new(),
+            encrypted: false,
+            page_info: Vec::new(),
+            is_preview: false,
+        })
+    }
 
-            DocumentType::Receipt => format!(
-                "        TECH STORE RECEIPT{}\
-                    123 Technology Avenue\n\
-                       Silicon Valley, CA\n\
-                      Phone: (555) 123-4567\n\
-                =============================\n\n\
-                Date: {}\n\
-                Time: {}\n\
-                Cashier: Alex T.\n\
-                Transaction #: TX-{}\n\n\
-                ITEMS PURCHASED:\n\
-                Scanner Tool Software License\n\
-                SKU: SW-SCAN-001\n\
-                Price: $299.00\n\n\
-                Extended Warranty (1 Year)\n\
-                SKU: WR-001\n\
-                Price: $49.99\n\n\
-                Installation Service\n\
-                SKU: SV-INSTALL\n\
-                Price: $75.00\n\n\
-                -----------------------------\n\
-                Subtotal:              $423.99\n\
-                Tax (8.75%):            $37.10\n\
-                TOTAL:                 $461.09\n\n\
-                Payment Method: Credit Card\n\
-                Card: ****1234 (VISA)\n\
-                Authorization: ABC123\n\n\
-                THANK YOU FOR YOUR PURCHASE!\n\
-                \n\
-                Return Policy: 30 days with receipt\n\
-                Customer Service: support@techstore.com",
-                quality_note,
-                chrono::Utc::now().format("%Y-%m-%d"),
-                chrono::Utc::now().format("%H:%M:%S"),
-                rand::thread_rng().gen_range(100000..999999)
-            ),
+    async fn generate_tiff(
+        render: &impl Fn(&ScanSettings, u32, u32) -> RgbImage,
+        settings: &ScanSettings,
+        output_path: &PathBuf,
+    ) -> Result<ScanResult, PageFailure> {
+        let (width, height) = settings.paper_size.pixel_dimensions(settings.resolution);
+        let paper = settings.background_color.unwrap_or([255, 255, 255]);
 
-            DocumentType::BusinessCard => format!(
-                "BUSINESS CARD{}\
-                ═══════════════════════════════════════\n\
-                           JOHN SMITH\n\
-                        Senior Developer\n\n\
-                      Scanner Tool Corp.\n\n\
-                   📧 john.smith@scantech.com\n\
-                   📱 +1 (555) 123-4567\n\
-                   🌐 www.scannertool.com\n\n\
-                   LinkedIn: /in/johnsmith-dev\n\
-                   GitHub: @johnsmith-scanner\n\n\
-                ═══════════════════════════════════════\n\
-                  'Scanning the future, one document\n\
-                           at a time.'",
-                quality_note
-            ),
+        let file = std::fs::File::create(output_path)
+            .map_err(|e| format!("Failed to create TIFF file: {}", e))?;
+        // Frames are written a strip at a time, so the file is left unbuffered
+        let compression = match settings.compression {
+            CompressionLevel::None => Compression::Uncompressed,
+            CompressionLevel::Fast => Compression::Packbits,
+            CompressionLevel::Balanced => Compression::Lzw,
+            CompressionLevel::Maximum => Compression::Deflate(DeflateLevel::Best),
+        };
+        let mut encoder = TiffEncoder::new(file)
+            .map_err(|e| format!("Failed to start TIFF file: {}", e))?
+            .with_compression(compression);
 
-            DocumentType::Photo => format!(
-                "PHOTO SCAN METADATA{}\
-                Original Photo Details:\n\
-                - Scanned: {}\n\
-                - Resolution: {} DPI\n\
-                - Color Mode: {:?}\n\
-                - Estimated Size: 4x6 inches\n\
-                - File Format: {:?}\n\n\
-                Photo Description:\n\
-                This represents a scanned photograph. In a real implementation,\n\
-                this would be an actual image file containing the photographed\n\
-                content with appropriate color reproduction and detail.\n\n\
-                Scan Quality Notes:\n\
-                - Dust removal: Applied\n\
-                - Color correction: Auto\n\
-                - Sharpening: Moderate\n\
-                - Noise reduction: {}%",
-                quality_note,
-                chrono::Utc::now().format("%Y-%m-%d %H:%M:%S"),
-                settings.resolution,
-                settings.color_mode,
-                settings.output_format,
-                settings.quality
-            ),
+        // One frame per scanned side; reverse sides come back blank
+        let page_count = Self::output_page_count(settings);
+        for page_number in 1..=page_count {
+            let mut page = if settings.duplex && page_number % 2 == 0 {
+                RgbImage::from_pixel(width, height, ::image::Rgb(paper))
+            } else {
+                Self::render_original(render, settings, width, height)
+            };
+            Self::adjust_tone(&mut page, settings);
 
-            _ => format!(
-                "SCANNED DOCUMENT{}\
-                Document Type: {:?}\n\
-                Scan Date: {}\n\
-                Scanner: Simulated Scanner\n\n\
-                This is a generic scanned document created by the Scanner Tool\n\
-                simulation system. In a real scanning scenario, this would contain\n\
-                the actual content from the physical document.\n\n\
-                Scan Parameters:\n\
-                - Resolution: {} DPI\n\
-                - Color Mode: {:?}\n\
-                - Paper Size: {:?}\n\
-                - Quality: {}%\n\
-                - Duplex: {}\n\
-                - Output Format: {:?}\n\n\
-                File Information:\n\
-                - Generated by: Scanner Tool v1.0\n\
-                - Platform: Cross-platform scanner simulator\n\
-                - Purpose: Development and testing tool",
-                quality_note,
-                document_type,
-                chrono::Utc::now().format("%Y-%m-%d %H:%M:%S"),
-                settings.resolution,
-                settings.color_mode,
-                settings.paper_size,
-                settings.quality,
-                if settings.duplex { "Yes" } else { "No" },
-                settings.output_format
-            ),
+            // Grayscale and black and white frames keep a single sample per pixel
+            match Self::convert_to_color_mode(page, settings) {
+                DynamicImage::ImageLuma8(frame) => Self::write_tiff_frame::<colortype::Gray8>(
+                    &mut encoder,
+                    (width, height),
+                    frame.as_raw(),
+                    settings.resolution,
+                ),
+                frame => Self::write_tiff_frame::<colortype::RGB8>(
+                    &mut encoder,
+                    (width, height),
+                    frame.to_rgb8().as_raw(),
+                    settings.resolution,
+                ),
+            }
+            .map_err(|message| PageFailure {
+                message,
+                pages_completed: page_number - 1,
+            })?;
         }
+
+        let file_size = std::fs::metadata(output_path)
+            .map_err(|e| format!("Failed to get file size: {}", e))?
+            .len();
+
+        Ok(ScanResult {
+            file_path: output_path.clone(),
+            file_size,
+            pages: page_count,
+            resolution: settings.resolution,
+            color_mode: settings.color_mode,
+            format: OutputFormat::Tiff,
+            scan_time: chrono::Utc::now(),
+            is_test_scan: false,
+            exif_embedded: false,
+            pdf_a_level: None,
+            upload: None,
+            preview_path: None,
+            ocr_text: None,
+            checksum: String::new(),
+            encrypted: false,
+            page_info: Vec::new(),
+            is_preview: false,
+        })
     }
 
-    pub fn generate_filename(
-        document_type: &DocumentType,
-        format: &OutputFormat,
-        timestamp: &chrono::DateTime<chrono::Utc>,
-    ) -> String {
-        let type_prefix = match document_type {
-            DocumentType::Text => "text_document",
-            DocumentType::Image => "scanned_image",
-            DocumentType::Mixed => "mixed_content",
-            DocumentType::Photo => "photo_scan",
-            DocumentType::BusinessCard => "business_card",
-            DocumentType::Receipt => "receipt",
-            DocumentType::Contract => "contract",
-            DocumentType::Invoice => "invoice",
-        };
+    /// Append one image directory holding an 8-bit frame tagged with the scan DPI
+    fn write_tiff_frame<C: colortype::ColorType<Inner = u8>>(
+        encoder: &mut TiffEncoder<std::fs::File>,
+        (width, height): (u32, u32),
+        samples: &[u8],
+        dpi: u32,
+    ) -> Result<(), String> {
+        let mut image = encoder
+            .new_image::<C>(width, height)
+            .map_err(|e| format!("Failed to add TIFF frame: {}", e))?;
+        image.resolution(ResolutionUnit::Inch, Rational { n: dpi, d: 1 });
+        image
+            .write_data(samples)
+            .map_err(|e| format!("Failed to encode TIFF frame: {}", e))
+    }
+
+    /// Apply the brightness and contrast settings to a rendered page. Runs before
+    /// the color mode conversion, so brightness also moves the black and white
+    /// threshold the way it does on a scanner.
+    fn adjust_tone(page: &mut RgbImage, settings: &ScanSettings) {
+        if settings.contrast != 0 {
+            ::image::imageops::colorops::contrast_in_place(page, settings.contrast as f32);
+        }
+        if settings.brightness != 0 {
+            ::image::imageops::colorops::brighten_in_place(
+                page,
+                settings.brightness as i32 * 255 / 100,
+            );
+        }
+    }
 
-        let extension = match format {
-            OutputFormat::Pdf => "pdf",
-            OutputFormat::Jpeg => "txt", // Simplified to txt for now
-            OutputFormat::Png => "txt",
-            OutputFormat::Tiff => "txt",
+    /// Render the page as it lies on the scanner. Black and white is captured from
+    /// the colored original and reduced to two levels by convert_to_color_mode, so
+    /// dithering has the original's gray levels to work with.
+    fn render_original(
+        render: &impl Fn(&ScanSettings, u32, u32) -> RgbImage,
+        settings: &ScanSettings,
+        width: u32,
+        height: u32,
+    ) -> RgbImage {
+        if settings.color_mode != ColorMode::BlackAndWhite {
+            return render(settings, width, height);
+        }
+        let original = ScanSettings {
+            color_mode: ColorMode::Color,
+            ..settings.clone()
         };
+        render(&original, width, height)
+    }
 
-        format!(
-            "{}_{}.{}",
-            type_prefix,
-            timestamp.format("%Y%m%d_%H%M%S"),
-            extension
-        )
+    /// Match the channel layout a scanner produces in each color mode: RGB for
+    /// color, 8-bit gray for grayscale and pure black/white gray for line art,
+    /// dithered unless the settings turn it off
+    fn convert_to_color_mode(page: RgbImage, settings: &ScanSettings) -> DynamicImage {
+        match settings.color_mode {
+            ColorMode::Color => DynamicImage::ImageRgb8(page),
+            ColorMode::Grayscale => {
+                DynamicImage::ImageLuma8(DynamicImage::ImageRgb8(page).to_luma8())
+            }
+            ColorMode::BlackAndWhite => {
+                let mut gray = DynamicImage::ImageRgb8(page).to_luma8();
+                if settings.dither {
+                    Self::dither_to_black_and_white(&mut gray);
+                } else {
+                    for pixel in gray.pixels_mut() {
+                        *pixel = if pixel.0[0] < 128 {
+                            Luma([0])
+                        } else {
+                            Luma([255])
+                        };
+                    }
+                }
+                DynamicImage::ImageLuma8(gray)
+            }
+        }
     }
 
-    pub fn get_output_directory() -> Result<PathBuf, String> {
-        let documents_dir = dirs::document_dir().ok_or("Could not find documents directory")?;
-        let scan_dir = documents_dir.join("Scanner Tool Outputs");
+    // Floyd-Steinberg dithering: each pixel is set to black or white and the
+    // difference is spread over the neighbours not visited yet, so grey areas
+    // come out as a dot pattern rather than solid black or white
+    fn dither_to_black_and_white(gray: &mut GrayImage) {
+        let width = gray.width() as usize;
+        // Error carried into the current and the next row, padded by a pixel each side
+        let mut current = vec![0i16; width + 2];
+        let mut next = vec![0i16; width + 2];
 
-        if !scan_dir.exists() {
-            fs::create_dir_all(&scan_dir)
-                .map_err(|e| format!("Failed to create output directory: {}", e))?;
+        for row in gray.chunks_exact_mut(width) {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                let value = *pixel as i16 + current[x + 1];
+                let output = if value < 128 { 0 } else { 255 };
+                let error = value - output;
+                *pixel = output as u8;
+
+                current[x + 2] += error * 7 / 16;
+                next[x] += error * 3 / 16;
+                next[x + 1] += error * 5 / 16;
+                next[x + 2] += error / 16;
+            }
+            std::mem::swap(&mut current, &mut next);
+            next.fill(0);
+        }
+    }
+
+    /// Generate a standard test pattern (grid, gradient, resolution lines),
+    /// independent of document type, for verifying the scan pipeline end to end.
+    /// The file follows the output format, paper size and the other settings of a
+    /// single page scan.
+    pub async fn generate_test_pattern(
+        settings: &ScanSettings,
+        output_path: &PathBuf,
+    ) -> Result<ScanResult, String> {
+        // Always one page, however many the settings ask for
+        let settings = &ScanSettings {
+            page_count: 1,
+            duplex: false,
+            ..Self::with_raster_resolution(settings)
+        };
+        if let Some(parent) = output_path.parent() {
+            check_disk_space(parent, estimated_file_size(settings, 1))?;
         }
 
-        Ok(scan_dir)
+        let pattern =
+            |settings: &ScanSettings, width, height| render_test_pattern(settings, width, height);
+        let result = match settings.output_format {
+            OutputFormat::Pdf => Self::generate_test_pattern_pdf(settings, output_path)?,
+            OutputFormat::Jpeg => Self::generate_jpeg(&pattern, settings, output_path).await?,
+            OutputFormat::Png => Self::generate_png(&pattern, settings, output_path).await?,
+            OutputFormat::Tiff => Self::generate_tiff(&pattern, settings, output_path)
+                .await
+                .map_err(|failure| failure.message)?,
+        };
+        let result = ScanResult {
+            is_test_scan: true,
+            ..result
+        };
+
+        Self::finish_scan_file(settings, result).inspect_err(|_| {
+            let _ = fs::remove_file(output_path);
+        })
     }
 
-    // PDF-specific content generation helpers
-    fn add_text_content(layer: &PdfLayerReference, font: &IndirectFontRef) -> Result<(), String> {
-        layer.use_text("MEMORANDUM", 18.0, Mm(20.0), Mm(250.0), font);
-        layer.use_text("TO: Development Team", 12.0, Mm(20.0), Mm(230.0), font);
-        layer.use_text(
-            "FROM: Scanner Tool Project Manager",
-            12.0,
-            Mm(20.0),
-            Mm(220.0),
-            font,
-        );
-        layer.use_text("DATE: Today's Date", 12.0, Mm(20.0), Mm(210.0), font);
-        layer.use_text(
-            "RE: Scanner Tool Implementation",
-            12.0,
-            Mm(20.0),
-            Mm(200.0),
-            font,
-        );
+    fn generate_test_pattern_pdf(
+        settings: &ScanSettings,
+        output_path: &PathBuf,
+    ) -> Result<ScanResult, String> {
+        let scan_time = chrono::Utc::now();
+        let (page_width, page_height) = Self::page_size(&settings.paper_size);
+        let (doc, page1, layer1) =
+            PdfDocument::new("Scanner Test Pattern", page_width, page_height, "Layer 1");
+        let doc = Self::apply_conformance(doc, settings);
 
-        layer.use_text(
-            "This document serves as a test of the scanner simulation functionality.",
-            10.0,
-            Mm(20.0),
-            Mm(180.0),
-            font,
-        );
-        layer.use_text(
-            "The implementation includes document type recognition, multiple output formats,",
-            10.0,
-            Mm(20.0),
-            Mm(170.0),
-            font,
+        let font = Self::add_document_font(&doc, settings, BuiltinFont::Helvetica)?;
+
+        let layer = doc.get_page(page1).get_layer(layer1);
+        Self::fill_page_background(
+            &layer,
+            page_width.0,
+            page_height.0,
+            settings.background_color,
+            &settings.color_mode,
         );
+
+        // Everything below is drawn in the coordinates of the A4 layout
+        let (scale, offset_x, offset_y) = Self::test_pattern_placement(page_width.0, page_height.0);
+        layer.save_graphics_state();
+        layer.set_ctm(CurTransMat::Translate(
+            Mm(offset_x).into_pt(),
+            Mm(offset_y).into_pt(),
+        ));
+        layer.set_ctm(CurTransMat::Scale(scale, scale));
+
+        layer.use_text("SCANNER TEST PATTERN", 18.0, Mm(20.0), Mm(275.0), &font);
         layer.use_text(
-            "realistic scan quality simulation, and file generation capabilities.",
+            Self::test_pattern_caption(settings),
             10.0,
             Mm(20.0),
-            Mm(160.0),
-            font,
+            Mm(267.0),
+            &font,
         );
 
-        Ok(())
-    }
+        Self::add_test_grid(&layer);
+        Self::add_test_gradient(&layer, &font, &settings.color_mode);
+        Self::add_test_resolution_lines(&layer, &font, scale);
+        layer.restore_graphics_state();
 
-    fn add_invoice_content(
-        layer: &PdfLayerReference,
-        font: &IndirectFontRef,
-    ) -> Result<(), String> {
-        layer.use_text("INVOICE", 24.0, Mm(20.0), Mm(270.0), font);
-        layer.use_text("Invoice #: INV-2024-001", 12.0, Mm(20.0), Mm(250.0), font);
-        layer.use_text("Date: 2024-01-15", 12.0, Mm(20.0), Mm(240.0), font);
+        doc.save(&mut BufWriter::new(
+            std::fs::File::create(output_path)
+                .map_err(|e| format!("Failed to create PDF file: {}", e))?,
+        ))
+        .map_err(|e| format!("Failed to save PDF: {}", e))?;
 
-        layer.use_text("Bill To:", 12.0, Mm(20.0), Mm(220.0), font);
-        layer.use_text(
-            "Scanner Tool Test Customer",
-            10.0,
-            Mm(20.0),
-            Mm(210.0),
-            font,
-        );
-        layer.use_text("123 Business Street", 10.0, Mm(20.0), Mm(200.0), font);
-        layer.use_text("Technology City, TC 12345", 10.0, Mm(20.0), Mm(190.0), font);
+        if let Some(level) = &settings.pdf_a_level {
+            apply_pdf_a_conformance(output_path, level, "Scanner Test Pattern")?;
+        }
+        Self::compress_pdf_streams(output_path, settings.compression)?;
+        if let Some(password) = &settings.pdf_password {
+            encrypt_pdf(output_path, password)?;
+        }
 
-        layer.use_text(
-            "Scanner Tool License    $299.00",
-            10.0,
-            Mm(20.0),
-            Mm(160.0),
-            font,
-        );
-        layer.use_text(
-            "Technical Support       $250.00",
-            10.0,
-            Mm(20.0),
-            Mm(150.0),
-            font,
-        );
-        layer.use_text("TOTAL: $598.41", 14.0, Mm(20.0), Mm(120.0), font);
+        let file_size = std::fs::metadata(output_path)
+            .map_err(|e| format!("Failed to get file size: {}", e))?
+            .len();
+
+        Ok(ScanResult {
+            file_path: output_path.clone(),
+            file_size,
+            pages: 1,
+            resolution: settings.resolution,
+            color_mode: settings.color_mode,
+            format: OutputFormat::Pdf,
+            scan_time,
+            is_test_scan: true,
+            exif_embedded: false,
+            pdf_a_level: settings.pdf_a_level,
+            upload: None,
+            preview_path: None,
+            ocr_text: None,
+            checksum: String::new(),
+            encrypted: settings.pdf_password.is_some(),
+            page_info: Vec::new(),
+            is_preview: false,
+        })
+    }
+
+    /// Scale and bottom left offset, in millimetres, placing the A4 test pattern
+    /// layout on a page. Smaller paper gets the layout shrunk to fit, larger paper
+    /// gets it at full size in the top middle.
+    pub fn test_pattern_placement(page_width: f32, page_height: f32) -> (f32, f32, f32) {
+        let (layout_width, layout_height) = Self::TEST_PATTERN_PAGE;
+        let scale = (page_width / layout_width)
+            .min(page_height / layout_height)
+            .min(1.0);
+        (
+            scale,
+            (page_width - layout_width * scale) / 2.0,
+            page_height - layout_height * scale,
+        )
+    }
+
+    /// Settings line printed under the test pattern heading
+    pub fn test_pattern_caption(settings: &ScanSettings) -> String {
+        format!(
+            "{} DPI | {:?} | {:?} | Quality {}%",
+            settings.resolution,
+            settings.color_mode,
+            settings.paper_size,
+            settings.quality.clamp(1, 100)
+        )
+    }
+
+    /// Label of a resolution target, giving the line pairs per millimetre it has
+    /// on paper once the layout is scaled
+    pub fn line_pair_label(line_pairs_per_mm: f32, scale: f32) -> String {
+        format!("{}", (line_pairs_per_mm / scale * 10.0).round() / 10.0)
+    }
+
+    /// Name for a scan file, following the settings' filename template or
+    /// `{type}_{date}_{time}.{ext}` without one. The name can include
+    /// subdirectories of `output_dir`, which is also where `{counter}` looks for
+    /// names already taken.
+    pub fn generate_filename(
+        document_type: &DocumentType,
+        settings: &ScanSettings,
+        scanner_name: &str,
+        output_dir: &Path,
+        timestamp: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<String, String> {
+        let type_prefix = match document_type {
+            DocumentType::Text => "text_document",
+            DocumentType::Image => "scanned_image",
+            DocumentType::Mixed => "mixed_content",
+            DocumentType::Photo => "photo_scan",
+            DocumentType::BusinessCard => "business_card",
+            DocumentType::Receipt => "receipt",
+            DocumentType::Contract => "contract",
+            DocumentType::Invoice => "invoice",
+            DocumentType::Barcode => "barcode",
+            DocumentType::Auto => "document",
+        };
+
+        render_filename_template(
+            settings
+                .filename_template
+                .as_deref()
+                .unwrap_or(DEFAULT_FILENAME_TEMPLATE),
+            &FilenameFields {
+                document_type: type_prefix,
+                extension: settings.output_format.extension(),
+                scanner: scanner_name,
+                timestamp,
+            },
+            output_dir,
+        )
+    }
+
+    pub fn generate_test_pattern_filename(
+        settings: &ScanSettings,
+        timestamp: &chrono::DateTime<chrono::Utc>,
+    ) -> String {
+        format!(
+            "test_pattern_{}.{}",
+            timestamp.format("%Y%m%d_%H%M%S"),
+            settings.output_format.extension()
+        )
+    }
+
+    pub fn get_output_directory() -> Result<PathBuf, String> {
+        // Headless and containerized setups often have no Documents folder
+        let output_folder = |base_dir: PathBuf| base_dir.join("Scanner Tool Outputs");
+        let candidates = [
+            ("configured", Self::configured_output_directory()),
+            ("documents", dirs::document_dir().map(output_folder)),
+            ("home", dirs::home_dir().map(output_folder)),
+            ("temp", Some(output_folder(std::env::temp_dir()))),
+        ];
+
+        let mut failures = Vec::new();
+        for (label, scan_dir) in candidates {
+            let Some(scan_dir) = scan_dir else {
+                if label != "configured" {
+                    failures.push(format!("no {} directory", label));
+                }
+                continue;
+            };
+
+            match Self::ensure_writable_directory(&scan_dir) {
+                Ok(()) => {
+                    if !failures.is_empty() {
+                        println!(
+                            "Using {} directory fallback: {} ({})",
+                            label,
+                            scan_dir.display(),
+                            failures.join("; ")
+                        );
+                    }
+                    return Ok(scan_dir);
+                }
+                Err(e) => failures.push(format!("{} directory: {}", label, e)),
+            }
+        }
+
+        Err(format!(
+            "No writable output directory found ({})",
+            failures.join("; ")
+        ))
+    }
+
+    /// Make `path` the directory new scans are written to, creating it if needed.
+    /// Files already in the previous directory stay where they are.
+    pub fn set_output_directory(path: &Path) -> Result<PathBuf, String> {
+        if path.as_os_str().is_empty() || !path.is_absolute() {
+            return Err(format!(
+                "Output directory must be an absolute path: {}",
+                path.display()
+            ));
+        }
+        if path.exists() && !path.is_dir() {
+            return Err(format!("{} is not a directory", path.display()));
+        }
+        Self::ensure_writable_directory(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let output_dir = path
+            .canonicalize()
+            .map_err(|e| format!("Failed to resolve {}: {}", path.display(), e))?;
+
+        let setting_path =
+            Self::output_directory_setting_path().ok_or("Could not find app data directory")?;
+        if let Some(parent) = setting_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+        }
+        let contents = serde_json::to_string_pretty(&OutputDirectorySetting {
+            output_directory: output_dir.clone(),
+        })
+        .map_err(|e| format!("Failed to serialize output directory: {}", e))?;
+
+        // Write then rename so a crash mid-write never leaves a truncated file
+        let temp_path = setting_path.with_extension("json.tmp");
+        fs::write(&temp_path, contents)
+            .map_err(|e| format!("Failed to write {:?}: {}", setting_path, e))?;
+        fs::rename(&temp_path, &setting_path)
+            .map_err(|e| format!("Failed to replace {:?}: {}", setting_path, e))?;
+
+        println!("Output directory set to {}", output_dir.display());
+        Ok(output_dir)
+    }
+
+    /// The directory chosen with `set_output_directory`, if any
+    fn configured_output_directory() -> Option<PathBuf> {
+        let contents = fs::read_to_string(Self::output_directory_setting_path()?).ok()?;
+        match serde_json::from_str::<OutputDirectorySetting>(&contents) {
+            Ok(setting) => Some(setting.output_directory),
+            Err(e) => {
+                println!("Ignoring unreadable output directory setting: {}", e);
+                None
+            }
+        }
+    }
+
+    // Kept next to the state the scanner service saves in the app data directory
+    fn output_directory_setting_path() -> Option<PathBuf> {
+        Some(
+            dirs::data_dir()?
+                .join("com.linzell.scanner-tool")
+                .join("output_directory.json"),
+        )
+    }
+
+    /// Temporary directory holding the proofs of preview scans
+    pub fn get_preview_directory() -> Result<PathBuf, String> {
+        let preview_dir = std::env::temp_dir().join("Scanner Tool Previews");
+        Self::ensure_writable_directory(&preview_dir)
+            .map_err(|e| format!("{}: {}", preview_dir.display(), e))?;
+        Ok(preview_dir)
+    }
+
+    /// Files in the default output directory and the subdirectories filename
+    /// templates create, newest first. Names are relative to the output directory.
+    pub fn list_output_files() -> Result<Vec<OutputFileInfo>, String> {
+        let output_dir = Self::get_output_directory()?;
+
+        let mut files = Vec::new();
+        let mut directories = vec![output_dir.clone()];
+        while let Some(directory) = directories.pop() {
+            let entries = fs::read_dir(&directory)
+                .map_err(|e| format!("Failed to read output directory: {}", e))?;
+            for entry in entries {
+                let entry = entry.map_err(|e| format!("Failed to read output directory: {}", e))?;
+                let path = entry.path();
+                let name = path
+                    .strip_prefix(&output_dir)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .into_owned();
+                // Skip the write probe and anything else hidden
+                if entry.file_name().to_string_lossy().starts_with('.') {
+                    continue;
+                }
+
+                let metadata = entry
+                    .metadata()
+                    .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+                if metadata.is_dir() {
+                    directories.push(path);
+                    continue;
+                }
+                if !metadata.is_file() {
+                    continue;
+                }
+
+                let format = path
+                    .extension()
+                    .and_then(|ext| OutputFormat::from_extension(&ext.to_string_lossy()));
+                let modified = metadata
+                    .modified()
+                    .map(chrono::DateTime::<chrono::Utc>::from)
+                    .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+
+                files.push(OutputFileInfo {
+                    name,
+                    path,
+                    size: metadata.len(),
+                    modified,
+                    format,
+                });
+            }
+        }
+
+        files.sort_by_key(|file| std::cmp::Reverse(file.modified));
+        Ok(files)
+    }
+
+    /// Delete a file from the default output directory or one of its subdirectories.
+    /// Paths that resolve anywhere else, e.g. through `..` or a symlink, are refused.
+    pub fn delete_output_file(path: &Path) -> Result<(), String> {
+        let output_dir = Self::get_output_directory()?
+            .canonicalize()
+            .map_err(|e| format!("Failed to resolve output directory: {}", e))?;
+        let target = path
+            .canonicalize()
+            .map_err(|e| format!("Failed to resolve {}: {}", path.display(), e))?;
+
+        // Subdirectories come from filename templates
+        if target == output_dir || !target.starts_with(&output_dir) {
+            return Err(format!("{} is not in the output directory", path.display()));
+        }
+        if !target.is_file() {
+            return Err(format!("{} is not a file", path.display()));
+        }
+
+        fs::remove_file(&target).map_err(|e| format!("Failed to delete {}: {}", path.display(), e))
+    }
+
+    /// Directory a job's scan is written to: its own `output_dir` when set, created
+    /// if missing, otherwise the default output directory
+    pub fn resolve_output_directory(settings: &ScanSettings) -> Result<PathBuf, String> {
+        match &settings.output_dir {
+            Some(dir) => {
+                Self::validate_output_directory(dir)?;
+                Self::ensure_writable_directory(dir)
+                    .map_err(|e| format!("{}: {}", dir.display(), e))?;
+                Ok(dir.clone())
+            }
+            None => Self::get_output_directory(),
+        }
+    }
+
+    /// Check that a barcode payload is non-empty and fits in a QR code
+    pub fn validate_barcode_payload(payload: &str) -> Result<(), String> {
+        if payload.trim().is_empty() {
+            return Err("Barcode payload cannot be empty".to_string());
+        }
+
+        QrCode::new(payload.as_bytes())
+            .map(|_| ())
+            .map_err(|e| format!("Barcode payload cannot be encoded: {}", e))
+    }
+
+    /// Check a custom output directory without creating it
+    pub fn validate_output_directory(dir: &Path) -> Result<(), String> {
+        if !dir.is_absolute() {
+            return Err(format!(
+                "Output directory must be an absolute path: {}",
+                dir.display()
+            ));
+        }
+
+        if dir.exists() && !dir.is_dir() {
+            return Err(format!(
+                "Output directory is not a directory: {}",
+                dir.display()
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn ensure_writable_directory(dir: &Path) -> Result<(), String> {
+        if !dir.exists() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create output directory: {}", e))?;
+        }
+
+        // An existing directory can still be read-only, so probe with a real write
+        let probe = dir.join(".write_test");
+        fs::write(&probe, b"").map_err(|e| format!("Directory is not writable: {}", e))?;
+        let _ = fs::remove_file(&probe);
 
         Ok(())
     }
 
-    fn add_contract_content(
+    // PDF-specific content generation helpers
+    fn apply_conformance(
+        doc: PdfDocumentReference,
+        settings: &ScanSettings,
+    ) -> PdfDocumentReference {
+        match &settings.pdf_a_level {
+            Some(level) => doc.with_conformance(pdf_a_conformance(level)),
+            None => doc,
+        }
+    }
+
+    fn add_document_font(
+        doc: &PdfDocumentReference,
+        settings: &ScanSettings,
+        builtin_font: BuiltinFont,
+    ) -> Result<IndirectFontRef, String> {
+        let font = if settings.pdf_a_level.is_some() {
+            doc.add_external_font(EMBEDDED_SERIF_FONT)
+        } else {
+            doc.add_builtin_font(builtin_font)
+        };
+        font.map_err(|e| format!("Failed to add font: {}", e))
+    }
+
+    /// PDF page dimensions for a paper size; custom sizes are in millimetres
+    fn page_size(paper_size: &PaperSize) -> (Mm, Mm) {
+        let (width, height) = paper_size.dimensions_mm();
+        (Mm(width), Mm(height))
+    }
+
+    fn fill_page_background(
+        layer: &PdfLayerReference,
+        width: f32,
+        height: f32,
+        background_color: Option<[u8; 3]>,
+        color_mode: &ColorMode,
+    ) {
+        // White paper needs no fill
+        let Some(background_color) = background_color else {
+            return;
+        };
+
+        layer.set_fill_color(Self::mode_color(background_color, color_mode));
+        layer.add_rect(Rect::new(Mm(0.0), Mm(0.0), Mm(width), Mm(height)));
+
+        // Text is painted with the fill color, so restore black ink
+        layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
+    }
+
+    /// PDF fill for an RGB color as a scanner would capture it in `color_mode`:
+    /// unchanged in color, its luma in grayscale and thresholded to pure black or
+    /// white in black and white, matching the raster conversion
+    fn mode_color(color: [u8; 3], color_mode: &ColorMode) -> Color {
+        let [r, g, b] = color;
+        let luma = ::image::Rgb(color).to_luma().0[0];
+        match color_mode {
+            ColorMode::Color => Color::Rgb(Rgb::new(
+                r as f32 / 255.0,
+                g as f32 / 255.0,
+                b as f32 / 255.0,
+                None,
+            )),
+            ColorMode::Grayscale => Color::Greyscale(Greyscale::new(luma as f32 / 255.0, None)),
+            ColorMode::BlackAndWhite => {
+                Color::Greyscale(Greyscale::new(if luma < 128 { 0.0 } else { 1.0 }, None))
+            }
+        }
+    }
+
+    // Accent bar across the top margin: blue in color, dark grey in grayscale and
+    // solid black in black and white
+    fn add_header_bar(layer: &PdfLayerReference, width: f32, height: f32, color_mode: &ColorMode) {
+        const ACCENT_COLOR: [u8; 3] = [30, 70, 160];
+
+        let margins = Margins::default();
+        layer.set_fill_color(Self::mode_color(ACCENT_COLOR, color_mode));
+        layer.add_rect(Rect::new(
+            Mm(margins.left),
+            Mm(height - 15.0),
+            Mm(width - margins.left),
+            Mm(height - 10.0),
+        ));
+        layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
+    }
+
+    // Light grey text at 45 degrees across the middle of the page, shrunk to fit
+    // on wide or short pages. Black and white has no grey, so the text is outlined
+    // in black there instead
+    fn add_watermark(
         layer: &PdfLayerReference,
         font: &IndirectFontRef,
-    ) -> Result<(), String> {
-        layer.use_text(
-            "SOFTWARE LICENSE AGREEMENT",
-            18.0,
-            Mm(20.0),
-            Mm(270.0),
-            font,
+        text: &str,
+        width: f32,
+        height: f32,
+        color_mode: &ColorMode,
+    ) {
+        const MAX_FONT_SIZE: f32 = 60.0;
+        // Rough average glyph width of the serif fonts, in ems
+        const AVERAGE_CHAR_WIDTH: f32 = 0.5;
+        const MM_PER_PT: f32 = 25.4 / 72.0;
+
+        // Longest 45 degree line through the center, less a margin at each end
+        let available = width.min(height) * std::f32::consts::SQRT_2 * 0.8;
+        let em_count = text.chars().count().max(1) as f32 * AVERAGE_CHAR_WIDTH;
+        let font_size = MAX_FONT_SIZE.min(available / (em_count * MM_PER_PT));
+        let text_width = em_count * font_size * MM_PER_PT;
+
+        // Step back half the text width along the diagonal so the text is centered
+        let offset = text_width / 2.0 * std::f32::consts::FRAC_1_SQRT_2;
+        let x = width / 2.0 - offset;
+        let y = height / 2.0 - offset;
+
+        let outlined = matches!(color_mode, ColorMode::BlackAndWhite);
+        layer.begin_text_section();
+        if outlined {
+            layer.set_outline_color(Color::Greyscale(Greyscale::new(0.0, None)));
+            layer.set_outline_thickness(0.5);
+            layer.set_text_rendering_mode(TextRenderingMode::Stroke);
+        } else {
+            layer.set_fill_color(Color::Greyscale(Greyscale::new(0.85, None)));
+        }
+        layer.set_font(font, font_size);
+        layer.set_text_matrix(TextMatrix::TranslateRotate(
+            Mm(x).into_pt(),
+            Mm(y).into_pt(),
+            45.0,
+        ));
+        layer.write_text(text, font);
+        if outlined {
+            layer.set_text_rendering_mode(TextRenderingMode::Fill);
+        }
+        layer.end_text_section();
+        layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
+    }
+
+    fn add_text_content(layout: &mut PageLayout) -> Result<(), String> {
+        layout.skip(20.0);
+        layout.line("MEMORANDUM", 18.0);
+        layout.skip(10.0);
+        layout.line("TO: Development Team", 12.0);
+        layout.line("FROM: Scanner Tool Project Manager", 12.0);
+        layout.line("DATE: Today's Date", 12.0);
+        layout.line("RE: Scanner Tool Implementation", 12.0);
+
+        layout.skip(10.0);
+        layout.line(
+            "This document serves as a test of the scanner simulation functionality.",
+            10.0,
         );
-        layer.use_text(
-            "This Software License Agreement ('Agreement') is entered into",
+        layout.line(
+            "The implementation includes document type recognition, multiple output formats,",
             10.0,
-            Mm(20.0),
-            Mm(250.0),
-            font,
         );
-        layer.use_text(
-            "between Scanner Tool Corp. ('Licensor') and the end user ('Licensee').",
+        layout.line(
+            "realistic scan quality simulation, and file generation capabilities.",
             10.0,
-            Mm(20.0),
-            Mm(240.0),
-            font,
         );
 
-        layer.use_text("1. GRANT OF LICENSE", 12.0, Mm(20.0), Mm(210.0), font);
-        layer.use_text(
-            "Licensor hereby grants to Licensee a non-exclusive, non-transferable",
-            10.0,
-            Mm(20.0),
-            Mm(200.0),
-            font,
+        Ok(())
+    }
+
+    fn add_invoice_content(layout: &mut PageLayout, locale: ContentLocale) -> Result<(), String> {
+        // Sales tax for en-US, VAT for the European locales
+        let (title, number, date, bill_to, customer, items, tax, total, tax_rate) = match locale {
+            ContentLocale::EnUs => (
+                "INVOICE",
+                "Invoice #: INV-2024-001",
+                "Date:",
+                "Bill To:",
+                [
+                    "Scanner Tool Test Customer",
+                    "123 Business Street",
+                    "Technology City, TC 12345",
+                ],
+                [
+                    ("Scanner Tool License", 29_900),
+                    ("Technical Support", 25_000),
+                ],
+                None,
+                "TOTAL:",
+                900,
+            ),
+            ContentLocale::DeDe => (
+                "RECHNUNG",
+                "Rechnungsnr.: RE-2024-001",
+                "Datum:",
+                "Rechnung an:",
+                [
+                    "Scanner Tool Testkunde",
+                    "Geschäftsstraße 123",
+                    "12345 Technologiestadt",
+                ],
+                [
+                    ("Scanner Tool Lizenz", 29_900),
+                    ("Technischer Support", 25_000),
+                ],
+                Some("MwSt. (19 %):"),
+                "GESAMT:",
+                1900,
+            ),
+            ContentLocale::FrFr => (
+                "FACTURE",
+                "Facture n° : FAC-2024-001",
+                "Date :",
+                "Facturer à :",
+                [
+                    "Client test Scanner Tool",
+                    "123 rue des Affaires",
+                    "75001 Paris",
+                ],
+                [
+                    ("Licence Scanner Tool", 29_900),
+                    ("Support technique", 25_000),
+                ],
+                Some("TVA (20 %) :"),
+                "TOTAL TTC :",
+                2000,
+            ),
+        };
+        let subtotal: u64 = items.iter().map(|(_, cents)| cents).sum();
+        let tax_amount = Self::tax_cents(subtotal, tax_rate);
+
+        layout.line(title, 24.0);
+        layout.skip(10.0);
+        layout.line(number, 12.0);
+        layout.line(
+            &format!("{} {}", date, locale.date(&Self::sample_date())),
+            12.0,
         );
-        layer.use_text(
-            "license to use the Scanner Tool software in accordance with the terms herein.",
-            10.0,
-            Mm(20.0),
-            Mm(190.0),
-            font,
+
+        layout.skip(10.0);
+        layout.line(bill_to, 12.0);
+        for line in customer {
+            layout.line(line, 10.0);
+        }
+
+        layout.skip(20.0);
+        for (item, cents) in items {
+            layout.line(&Self::priced_line(item, &locale.amount(cents), 31), 10.0);
+        }
+        if let Some(tax) = tax {
+            layout.line(
+                &Self::priced_line(tax, &locale.amount(tax_amount), 31),
+                10.0,
+            );
+        }
+        layout.skip(20.0);
+        layout.line(
+            &format!("{} {}", total, locale.amount(subtotal + tax_amount)),
+            14.0,
         );
 
         Ok(())
     }
 
-    fn add_receipt_content(
-        layer: &PdfLayerReference,
-        font: &IndirectFontRef,
-    ) -> Result<(), String> {
-        layer.use_text("TECH STORE RECEIPT", 14.0, Mm(60.0), Mm(270.0), font);
-        layer.use_text("123 Technology Avenue", 10.0, Mm(65.0), Mm(260.0), font);
-        layer.use_text("Phone: (555) 123-4567", 10.0, Mm(70.0), Mm(250.0), font);
+    fn add_contract_content(layout: &mut PageLayout, locale: ContentLocale) -> Result<(), String> {
+        let (title, preamble, clause, grant) = match locale {
+            ContentLocale::EnUs => (
+                "SOFTWARE LICENSE AGREEMENT",
+                [
+                    "This Software License Agreement ('Agreement') is entered into",
+                    "between Scanner Tool Corp. ('Licensor') and the end user ('Licensee').",
+                ],
+                "1. GRANT OF LICENSE",
+                [
+                    "Licensor hereby grants to Licensee a non-exclusive, non-transferable",
+                    "license to use the Scanner Tool software in accordance with the terms herein.",
+                ],
+            ),
+            ContentLocale::DeDe => (
+                "SOFTWARE-LIZENZVERTRAG",
+                [
+                    "Dieser Software-Lizenzvertrag („Vertrag“) wird geschlossen zwischen der",
+                    "Scanner Tool GmbH („Lizenzgeber“) und dem Endnutzer („Lizenznehmer“).",
+                ],
+                "1. EINRÄUMUNG DER LIZENZ",
+                [
+                    "Der Lizenzgeber räumt dem Lizenznehmer ein nicht ausschließliches, nicht",
+                    "übertragbares Recht zur Nutzung der Software Scanner Tool nach diesem Vertrag ein.",
+                ],
+            ),
+            ContentLocale::FrFr => (
+                "CONTRAT DE LICENCE DE LOGICIEL",
+                [
+                    "Le présent contrat de licence de logiciel (« Contrat ») est conclu entre",
+                    "Scanner Tool SAS (« Concédant ») et l'utilisateur final (« Licencié »).",
+                ],
+                "1. OCTROI DE LA LICENCE",
+                [
+                    "Le Concédant accorde au Licencié une licence non exclusive et non cessible",
+                    "d'utilisation du logiciel Scanner Tool conformément aux présentes conditions.",
+                ],
+            ),
+        };
 
-        layer.use_text("Date: 2024-01-15 14:32", 10.0, Mm(20.0), Mm(220.0), font);
-        layer.use_text(
-            "Scanner Tool Software    $299.00",
+        layout.line(title, 18.0);
+        layout.skip(10.0);
+        for line in preamble {
+            layout.line(line, 10.0);
+        }
+
+        layout.skip(20.0);
+        layout.line(clause, 12.0);
+        for line in grant {
+            layout.line(line, 10.0);
+        }
+
+        Ok(())
+    }
+
+    fn add_receipt_content(layout: &mut PageLayout, locale: ContentLocale) -> Result<(), String> {
+        let (header, date, item, tax, total, tax_rate) = match locale {
+            ContentLocale::EnUs => (
+                [
+                    "TECH STORE RECEIPT",
+                    "123 Technology Avenue",
+                    "Phone: (555) 123-4567",
+                ],
+                "Date:",
+                "Scanner Tool Software",
+                "Tax (8.25%):",
+                "TOTAL:",
+                825,
+            ),
+            ContentLocale::DeDe => (
+                [
+                    "TECH STORE KASSENBON",
+                    "Technologieallee 123",
+                    "Tel.: 030 1234567",
+                ],
+                "Datum:",
+                "Scanner Tool Software",
+                "MwSt. (19 %):",
+                "SUMME:",
+                1900,
+            ),
+            ContentLocale::FrFr => (
+                [
+                    "TICKET DE CAISSE",
+                    "123 avenue de la Technologie",
+                    "Tél. : 01 23 45 67 89",
+                ],
+                "Date :",
+                "Logiciel Scanner Tool",
+                "TVA (20 %) :",
+                "TOTAL :",
+                2000,
+            ),
+        };
+        let price = 29_900;
+        let tax_amount = Self::tax_cents(price, tax_rate);
+
+        layout.indented_line(header[0], 14.0, 40.0);
+        layout.indented_line(header[1], 10.0, 45.0);
+        layout.indented_line(header[2], 10.0, 50.0);
+
+        layout.skip(20.0);
+        layout.line(
+            &format!("{} {}", date, locale.date_time(&Self::sample_date())),
             10.0,
-            Mm(20.0),
-            Mm(200.0),
-            font,
         );
-        layer.use_text(
-            "Tax (8.25%):              $24.67",
+        layout.skip(10.0);
+        layout.line(&Self::priced_line(item, &locale.amount(price), 32), 10.0);
+        layout.skip(10.0);
+        layout.line(
+            &Self::priced_line(tax, &locale.amount(tax_amount), 32),
             10.0,
-            Mm(20.0),
-            Mm(180.0),
-            font,
         );
-        layer.use_text(
-            "TOTAL:                   $323.67",
+        layout.skip(10.0);
+        layout.line(
+            &Self::priced_line(total, &locale.amount(price + tax_amount), 32),
             12.0,
-            Mm(20.0),
-            Mm(160.0),
-            font,
         );
 
         Ok(())
     }
 
-    fn add_business_card_content(
-        layer: &PdfLayerReference,
-        font: &IndirectFontRef,
-    ) -> Result<(), String> {
-        layer.use_text("JOHN SMITH", 16.0, Mm(50.0), Mm(200.0), font);
-        layer.use_text("Senior Developer", 12.0, Mm(50.0), Mm(190.0), font);
-        layer.use_text("Scanner Tool Corp.", 10.0, Mm(50.0), Mm(175.0), font);
-        layer.use_text("john.smith@scantech.com", 10.0, Mm(50.0), Mm(165.0), font);
-        layer.use_text("+1 (555) 123-4567", 10.0, Mm(50.0), Mm(155.0), font);
+    // When the sample invoice and receipt were issued
+    fn sample_date() -> chrono::NaiveDateTime {
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 15)
+            .and_then(|date| date.and_hms_opt(14, 32, 0))
+            .unwrap_or_default()
+    }
+
+    // Tax on an amount at a rate in basis points, rounded to the nearest cent
+    fn tax_cents(cents: u64, rate: u64) -> u64 {
+        (cents * rate + 5_000) / 10_000
+    }
+
+    // A label with its amount right-aligned to `width` characters
+    fn priced_line(label: &str, amount: &str, width: usize) -> String {
+        let padding = width.saturating_sub(label.chars().count());
+        format!("{}{:>padding$}", label, amount)
+    }
+
+    fn add_business_card_content(layout: &mut PageLayout) -> Result<(), String> {
+        layout.skip(70.0);
+        layout.indented_line("JOHN SMITH", 16.0, 30.0);
+        layout.indented_line("Senior Developer", 12.0, 30.0);
+        layout.skip(5.0);
+        layout.indented_line("Scanner Tool Corp.", 10.0, 30.0);
+        layout.indented_line("john.smith@scantech.com", 10.0, 30.0);
+        layout.indented_line("+1 (555) 123-4567", 10.0, 30.0);
+
+        Ok(())
+    }
+
+    fn add_barcode_content(layout: &mut PageLayout, payload: &str) -> Result<(), String> {
+        // Side of the printed code in millimetres, quiet zone included
+        const CODE_SIZE: f32 = 40.0;
+
+        let code = QrCode::new(payload.as_bytes())
+            .map_err(|e| format!("Failed to encode barcode payload: {}", e))?;
+        if let Some((x, y)) = layout.block(CODE_SIZE) {
+            Self::draw_qr_code(layout.layer(), &code, x, y, CODE_SIZE);
+        }
+        layout.line("BARCODE LABEL", 16.0);
+        layout.line(&format!("Payload: {}", payload), 10.0);
+        layout.skip(5.0);
+        layout.line("Scan the code above to look up this document.", 10.0);
+
+        Ok(())
+    }
+
+    // Dark modules as black squares on a white square, keeping the four module
+    // quiet zone scanners need to find the code on a tinted page
+    fn draw_qr_code(layer: &PdfLayerReference, code: &QrCode, x: f32, y: f32, size: f32) {
+        const QUIET_ZONE: usize = 4;
+
+        let width = code.width();
+        let module = size / (width + 2 * QUIET_ZONE) as f32;
+        layer.set_fill_color(Color::Greyscale(Greyscale::new(1.0, None)));
+        layer.add_rect(Rect::new(Mm(x), Mm(y), Mm(x + size), Mm(y + size)));
+        layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
+        for (index, color) in code.to_colors().into_iter().enumerate() {
+            if color == qrcode::Color::Dark {
+                let left = x + (index % width + QUIET_ZONE) as f32 * module;
+                let top = y + size - (index / width + QUIET_ZONE) as f32 * module;
+                layer.add_rect(Rect::new(
+                    Mm(left),
+                    Mm(top - module),
+                    Mm(left + module),
+                    Mm(top),
+                ));
+            }
+        }
+    }
+
+    fn add_reverse_side_content(layout: &mut PageLayout) -> Result<(), String> {
+        layout.skip(120.0);
+        layout.indented_line("[reverse side]", 12.0, 70.0);
 
         Ok(())
     }
 
     fn add_generic_content(
-        layer: &PdfLayerReference,
-        font: &IndirectFontRef,
+        layout: &mut PageLayout,
         document_type: &DocumentType,
     ) -> Result<(), String> {
         let title = match document_type {
@@ -555,22 +1819,257 @@ impl ScanGenerator {
             _ => "GENERIC DOCUMENT",
         };
 
-        layer.use_text(title, 18.0, Mm(20.0), Mm(270.0), font);
-        layer.use_text(
+        layout.line(title, 18.0);
+        layout.skip(20.0);
+        layout.line(
             "This is a simulated scan of a document generated by Scanner Tool.",
             12.0,
-            Mm(20.0),
-            Mm(240.0),
-            font,
         );
+        layout.skip(10.0);
+        layout.line("Generated for testing and development purposes.", 10.0);
+
+        Ok(())
+    }
+
+    // Test pattern drawing helpers
+    fn add_test_grid(layer: &PdfLayerReference) {
+        // 10mm grid covering the upper part of the page
+        let (left, right, bottom, top) = (20.0, 190.0, 150.0, 260.0);
+
+        layer.set_outline_color(Color::Greyscale(Greyscale::new(0.0, None)));
+        layer.set_outline_thickness(0.3);
+
+        let mut x = left;
+        while x <= right {
+            layer.add_line(Self::straight_line(x, bottom, x, top));
+            x += 10.0;
+        }
+
+        let mut y = bottom;
+        while y <= top {
+            layer.add_line(Self::straight_line(left, y, right, y));
+            y += 10.0;
+        }
+    }
+
+    fn add_test_gradient(
+        layer: &PdfLayerReference,
+        font: &IndirectFontRef,
+        color_mode: &ColorMode,
+    ) {
+        let steps = 17;
+        let step_width = 170.0 / steps as f32;
+
+        layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
+        layer.use_text("Grayscale ramp", 9.0, Mm(20.0), Mm(142.0), font);
+
+        // Grayscale ramp from black to white
+        for step in 0..steps {
+            let level = step as f32 / (steps - 1) as f32;
+            let x = 20.0 + step as f32 * step_width;
+            layer.set_fill_color(Color::Greyscale(Greyscale::new(level, None)));
+            layer.add_rect(Rect::new(Mm(x), Mm(120.0), Mm(x + step_width), Mm(138.0)));
+        }
+
+        // Primary color bars only make sense when scanning in color
+        if matches!(color_mode, ColorMode::Color) {
+            let bars = Self::TEST_PATTERN_COLOR_BARS;
+            let bar_width = 170.0 / bars.len() as f32;
+            for (index, bar) in bars.into_iter().enumerate() {
+                let x = 20.0 + index as f32 * bar_width;
+                layer.set_fill_color(Self::mode_color(bar, color_mode));
+                layer.add_rect(Rect::new(Mm(x), Mm(98.0), Mm(x + bar_width), Mm(116.0)));
+            }
+        }
+
+        layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
+    }
+
+    fn add_test_resolution_lines(layer: &PdfLayerReference, font: &IndirectFontRef, scale: f32) {
         layer.use_text(
-            "Generated for testing and development purposes.",
-            10.0,
+            "Resolution targets (line pairs per mm)",
+            9.0,
             Mm(20.0),
-            Mm(220.0),
+            Mm(88.0),
             font,
         );
 
-        Ok(())
+        layer.set_outline_color(Color::Greyscale(Greyscale::new(0.0, None)));
+
+        // Each block packs progressively finer line pairs into the same width
+        for (index, lp_per_mm) in Self::TEST_PATTERN_LINE_PAIRS.iter().enumerate() {
+            let block_left = 20.0 + index as f32 * 34.0;
+            let pitch = 1.0 / lp_per_mm;
+            layer.set_outline_thickness(pitch * 0.5 * 72.0 / 25.4);
+
+            let mut x = block_left;
+            while x < block_left + 28.0 {
+                layer.add_line(Self::straight_line(x, 50.0, x, 80.0));
+                x += pitch;
+            }
+
+            layer.use_text(
+                Self::line_pair_label(*lp_per_mm, scale),
+                8.0,
+                Mm(block_left),
+                Mm(44.0),
+                font,
+            );
+        }
+    }
+
+    fn straight_line(x1: f32, y1: f32, x2: f32, y2: f32) -> Line {
+        Line {
+            points: vec![
+                (Point::new(Mm(x1), Mm(y1)), false),
+                (Point::new(Mm(x2), Mm(y2)), false),
+            ],
+            is_closed: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_output_path(extension: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "scan_generator_test_{}.{}",
+            uuid::Uuid::new_v4(),
+            extension
+        ))
+    }
+
+    #[tokio::test]
+    async fn image_corners_match_background_color() {
+        const CREAM: [u8; 3] = [240, 228, 196];
+        let settings = ScanSettings {
+            resolution: 75,
+            output_format: OutputFormat::Png,
+            background_color: Some(CREAM),
+            ..ScanSettings::default()
+        };
+        let path = temp_output_path("png");
+
+        let generated =
+            ScanGenerator::generate_scan_file("job", &DocumentType::Text, &settings, &path).await;
+        let page = generated
+            .map_err(|e| e.message)
+            .and_then(|_| ::image::open(&path).map_err(|e| e.to_string()));
+        let _ = fs::remove_file(&path);
+        let page = page.unwrap().to_rgb8();
+
+        let (right, bottom) = (page.width() - 1, page.height() - 1);
+        for (x, y) in [(0, 0), (right, 0), (0, bottom), (right, bottom)] {
+            assert_eq!(page.get_pixel(x, y).0, CREAM, "corner ({}, {})", x, y);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pattern_follows_output_format_and_paper_size() {
+        let settings = ScanSettings {
+            resolution: 75,
+            paper_size: PaperSize::A5,
+            output_format: OutputFormat::Png,
+            ..ScanSettings::default()
+        };
+        let path = temp_output_path("png");
+
+        let generated = ScanGenerator::generate_test_pattern(&settings, &path).await;
+        let page = generated.and_then(|result| {
+            assert!(result.is_test_scan);
+            assert_eq!(result.format, OutputFormat::Png);
+            ::image::open(&path).map_err(|e| e.to_string())
+        });
+        let _ = fs::remove_file(&path);
+        let page = page.unwrap();
+
+        assert_eq!(
+            (page.width(), page.height()),
+            PaperSize::A5.pixel_dimensions(75)
+        );
+
+        let settings = ScanSettings {
+            paper_size: PaperSize::Letter,
+            ..ScanSettings::default()
+        };
+        let path = temp_output_path("pdf");
+
+        let generated = ScanGenerator::generate_test_pattern(&settings, &path).await;
+        let doc = generated.and_then(|_| lopdf::Document::load(&path).map_err(|e| e.to_string()));
+        let _ = fs::remove_file(&path);
+        let doc = doc.unwrap();
+
+        let page_id = doc.page_iter().next().unwrap();
+        let media_box = doc
+            .get_dictionary(page_id)
+            .and_then(|page| page.get(b"MediaBox"))
+            .and_then(lopdf::Object::as_array)
+            .unwrap();
+        let points: Vec<f32> = media_box
+            .iter()
+            .map(|value| value.as_float().unwrap())
+            .collect();
+        let (width, height) = PaperSize::Letter.dimensions_mm();
+        assert!((points[2] - width * 72.0 / 25.4).abs() < 1.0);
+        assert!((points[3] - height * 72.0 / 25.4).abs() < 1.0);
+    }
+
+    #[tokio::test]
+    async fn pdf_a_output_declares_conformance() {
+        for (level, part) in [(PdfALevel::PdfA1b, "1"), (PdfALevel::PdfA2b, "2")] {
+            // Left at the default compression, which must not filter the metadata
+            let settings = ScanSettings {
+                pdf_a_level: Some(level),
+                ..ScanSettings::default()
+            };
+            let path = temp_output_path("pdf");
+
+            let generated =
+                ScanGenerator::generate_scan_file("job", &DocumentType::Text, &settings, &path)
+                    .await;
+            let doc = generated
+                .map_err(|e| e.message)
+                .and_then(|_| lopdf::Document::load(&path).map_err(|e| e.to_string()));
+            let _ = fs::remove_file(&path);
+            let doc = doc.unwrap();
+            let catalog = doc.catalog().unwrap();
+
+            let metadata = catalog
+                .get(b"Metadata")
+                .and_then(lopdf::Object::as_reference)
+                .and_then(|id| doc.get_object(id))
+                .and_then(lopdf::Object::as_stream)
+                .unwrap();
+            assert!(
+                !metadata.dict.has(b"Filter"),
+                "{:?} metadata stream is filtered",
+                level
+            );
+            let xmp = String::from_utf8_lossy(&metadata.content);
+            assert!(
+                xmp.contains(&format!("<pdfaid:part>{}</pdfaid:part>", part)),
+                "{:?} XMP declares the wrong part",
+                level
+            );
+
+            let intents = catalog
+                .get(b"OutputIntents")
+                .and_then(lopdf::Object::as_array)
+                .unwrap();
+            assert!(!intents.is_empty(), "{:?} has no output intent", level);
+            for intent in intents {
+                let intent = match intent {
+                    lopdf::Object::Reference(id) => doc.get_dictionary(*id),
+                    intent => intent.as_dict(),
+                }
+                .unwrap();
+                assert_eq!(
+                    intent.get(b"S").and_then(lopdf::Object::as_name).unwrap(),
+                    b"GTS_PDFA1"
+                );
+            }
+        }
     }
 }
@@ -5,6 +5,7 @@ mod services;
 
 use commands::*;
 use services::ScannerService;
+use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -13,34 +14,112 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .manage(scanner_service)
+        .setup(|app| {
+            let scanner_service = app.state::<ScannerService>();
+            scanner_service.set_app_handle(app.handle().clone());
+            scanner_service.start_event_loop(ScannerService::DEFAULT_EVENT_INTERVAL)?;
+            scanner_service.resume_schedules()?;
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             get_system_info,
+            get_platform_capabilities,
             get_scanners,
             get_scanners_by_system,
+            find_scanners,
+            get_scanners_by_tag,
+            add_scanner_tag,
+            remove_scanner_tag,
             get_scanner,
             get_scanner_capabilities,
             test_scanner_connection,
+            test_scanner_connection_detailed,
             create_scan_job,
+            validate_scan_job,
+            create_batch_jobs,
+            append_to_job,
+            save_profile,
+            list_profiles,
+            delete_profile,
+            create_job_from_profile,
+            set_job_document_type,
+            set_job_priority,
+            update_job_settings,
+            describe_scan_pipeline,
             start_scan_job,
+            schedule_scan_job,
+            cancel_schedule,
+            start_batch,
+            retry_scan_job,
+            run_test_scan,
+            run_preview_scan,
+            scan_from_preview,
+            set_max_concurrent,
+            set_generation_concurrency,
+            set_max_jobs,
             get_scan_job,
+            get_scanner_for_job,
+            get_job_history,
             get_all_jobs,
+            export_history,
+            import_history,
             cancel_scan_job,
+            cancel_all_jobs,
+            pause_scan_job,
+            resume_scan_job,
+            delete_job,
+            clear_completed_jobs,
+            get_queue_status,
+            get_job_statistics,
+            get_scanner_metrics,
             get_document_types,
             get_color_modes,
             get_paper_sizes,
             get_output_formats,
+            get_pdf_a_levels,
             get_scanner_types,
+            get_supported_locales,
             get_default_scan_settings,
+            estimate_file_size,
             open_output_directory,
+            set_output_directory,
+            list_output_files,
+            delete_output_file,
             get_scan_result,
+            invalidate_result_cache,
+            get_scan_output,
+            read_scan_file,
+            merge_jobs,
+            reorder_pages,
+            generate_preview,
+            ocr_scan,
+            verify_scan_result,
             preview_scan_file,
             discover_scanners,
+            discover_network_scanners,
+            start_scanner_discovery,
             get_all_scanners,
             add_scanner,
+            add_network_scanner,
+            clone_scanner,
             remove_scanner,
             simulate_scanner_events,
-            reset_scanner_status
+            set_simulation_params,
+            start_event_loop,
+            stop_event_loop,
+            reconnect_scanner,
+            reset_scanner_status,
+            clear_jam
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Closing mid-scan would otherwise leave partial files behind
+            if let tauri::RunEvent::Exit = event {
+                let scanner_service = app.state::<ScannerService>();
+                if let Err(e) = tauri::async_runtime::block_on(scanner_service.shutdown()) {
+                    println!("Scanner service shutdown failed: {}", e);
+                }
+            }
+        });
 }
@@ -1,3 +1,7 @@
+pub mod ocr_service;
 pub mod scanner_service;
+pub mod upload_service;
 
+pub use ocr_service::*;
 pub use scanner_service::*;
+pub use upload_service::*;
@@ -0,0 +1,335 @@
+use crate::domain::*;
+use printpdf::lopdf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use tokio::process::Command;
+
+const TESSERACT_COMMAND: &str = "tesseract";
+
+/// Extract the text of every page of a scan.
+///
+/// PDF scans carry a text layer, which is read directly. Raster scans (JPEG, PNG
+/// and multi-page TIFF) are recognized by the tesseract command-line tool, which
+/// must be installed and on the PATH.
+pub async fn extract_scan_text(result: &ScanResult) -> Result<String, String> {
+    match result.format {
+        OutputFormat::Pdf => extract_pdf_text(&result.file_path),
+        OutputFormat::Jpeg | OutputFormat::Png | OutputFormat::Tiff => {
+            run_tesseract(&result.file_path).await
+        }
+    }
+}
+
+/// Guess the document type from a scan's text by its telltale headings and
+/// contact details. None when nothing matches.
+pub fn classify_document_text(text: &str) -> Option<DocumentType> {
+    let upper = text.to_uppercase();
+    let lines = text.lines().filter(|line| !line.trim().is_empty()).count();
+
+    // Receipts are checked before invoices since both carry totals and prices
+    if upper.contains("BARCODE LABEL") {
+        Some(DocumentType::Barcode)
+    } else if contains_any(&upper, &["RECEIPT", "KASSENBON", "TICKET DE CAISSE"]) {
+        Some(DocumentType::Receipt)
+    } else if contains_any(&upper, &["INVOICE", "RECHNUNG", "FACTURE"]) {
+        Some(DocumentType::Invoice)
+    } else if contains_any(&upper, &["AGREEMENT", "CONTRACT", "VERTRAG", "CONTRAT"]) {
+        Some(DocumentType::Contract)
+    } else if upper.contains("MEMORANDUM") {
+        Some(DocumentType::Text)
+    } else if upper.contains("MIXED CONTENT") {
+        Some(DocumentType::Mixed)
+    } else if text.contains('@') && lines <= 10 {
+        // A few short lines around an email address
+        Some(DocumentType::BusinessCard)
+    } else {
+        None
+    }
+}
+
+// Headings in each supported content locale
+fn contains_any(text: &str, keywords: &[&str]) -> bool {
+    keywords.iter().any(|keyword| text.contains(keyword))
+}
+
+// Text layer of every page in order. lopdf's own extract_text leaves Identity-H
+// text undecoded, so fonts with a ToUnicode map are decoded through it instead.
+fn extract_pdf_text(path: &Path) -> Result<String, String> {
+    let document =
+        lopdf::Document::load(path).map_err(|e| format!("Failed to open scan PDF: {}", e))?;
+    if document.is_encrypted() {
+        return Err("Scan PDF is password protected".to_string());
+    }
+
+    let mut text = String::new();
+    for page_id in document.get_pages().into_values() {
+        let decoders = page_font_decoders(&document, page_id);
+        let content = document
+            .get_and_decode_page_content(page_id)
+            .map_err(|e| format!("Failed to read PDF page content: {}", e))?;
+
+        // Fonts missing from the page resources decode like lopdf's StandardEncoding default
+        let standard = FontDecoder::standard();
+        let mut current_font = &standard;
+        for operation in &content.operations {
+            match operation.operator.as_str() {
+                "Tf" => {
+                    current_font = operation
+                        .operands
+                        .first()
+                        .and_then(|operand| operand.as_name().ok())
+                        .and_then(|name| decoders.get(name))
+                        .unwrap_or(&standard);
+                }
+                "Tj" | "TJ" | "'" | "\"" => {
+                    current_font.push_text(&mut text, &operation.operands);
+                }
+                "ET" if !text.ends_with('\n') => text.push('\n'),
+                _ => {}
+            }
+        }
+    }
+
+    if text.trim().is_empty() {
+        return Err("The PDF has no readable text layer".to_string());
+    }
+
+    Ok(text.trim().to_string())
+}
+
+// Decoders for each font a page's resources name. lopdf's get_page_fonts skips a
+// /Font entry given by reference, which is how printpdf writes PDF/A resources.
+fn page_font_decoders(
+    document: &lopdf::Document,
+    page_id: lopdf::ObjectId,
+) -> BTreeMap<Vec<u8>, FontDecoder> {
+    let (direct, resource_ids) = document.get_page_resources(page_id);
+    let resources = direct.into_iter().chain(
+        resource_ids
+            .into_iter()
+            .filter_map(|id| document.get_dictionary(id).ok()),
+    );
+
+    let mut decoders = BTreeMap::new();
+    for resources in resources {
+        let Ok(fonts) = resources
+            .get(b"Font")
+            .and_then(|fonts| document.dereference(fonts))
+            .and_then(|(_, fonts)| fonts.as_dict())
+        else {
+            continue;
+        };
+
+        for (name, font) in fonts {
+            let font = document
+                .dereference(font)
+                .and_then(|(_, font)| font.as_dict());
+            if let Ok(font) = font {
+                decoders
+                    .entry(name.clone())
+                    .or_insert_with(|| FontDecoder::new(document, font));
+            }
+        }
+    }
+
+    decoders
+}
+
+// How the string operands of text operators map to characters for one font
+struct FontDecoder {
+    encoding: Option<String>,
+    code_width: usize,
+    to_unicode: Option<HashMap<u32, String>>,
+}
+
+impl FontDecoder {
+    fn new(document: &lopdf::Document, font: &lopdf::Dictionary) -> Self {
+        // Composite (Type0) fonts use two-byte character codes
+        let composite = font
+            .get(b"Subtype")
+            .and_then(|subtype| subtype.as_name())
+            .map(|subtype| subtype == b"Type0")
+            .unwrap_or(false);
+        let to_unicode = font
+            .get(b"ToUnicode")
+            .and_then(|object| object.as_reference())
+            .and_then(|id| document.get_object(id))
+            .and_then(|object| object.as_stream())
+            .and_then(|stream| stream.decompressed_content())
+            .ok()
+            .map(|cmap| parse_to_unicode(&String::from_utf8_lossy(&cmap)));
+
+        Self {
+            encoding: Some(font.get_font_encoding().to_string()),
+            code_width: if composite { 2 } else { 1 },
+            to_unicode,
+        }
+    }
+
+    fn standard() -> Self {
+        Self {
+            encoding: None,
+            code_width: 1,
+            to_unicode: None,
+        }
+    }
+
+    fn push_text(&self, text: &mut String, operands: &[lopdf::Object]) {
+        for operand in operands {
+            match operand {
+                lopdf::Object::String(bytes, _) => text.push_str(&self.decode(bytes)),
+                lopdf::Object::Array(items) => {
+                    self.push_text(text, items);
+                    text.push(' ');
+                }
+                // Large negative kerning in a TJ array stands for a word gap
+                lopdf::Object::Integer(adjustment) if *adjustment < -100 => text.push(' '),
+                _ => {}
+            }
+        }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String {
+        match &self.to_unicode {
+            Some(map) => bytes
+                .chunks(self.code_width)
+                .filter_map(|code| {
+                    let code = code.iter().fold(0u32, |acc, byte| acc << 8 | *byte as u32);
+                    map.get(&code).map(String::as_str)
+                })
+                .collect(),
+            None => lopdf::Document::decode_text(self.encoding.as_deref(), bytes),
+        }
+    }
+}
+
+// Read the bfchar and bfrange sections of a ToUnicode CMap
+fn parse_to_unicode(cmap: &str) -> HashMap<u32, String> {
+    let mut map = HashMap::new();
+    let mut tokens = cmap_tokens(cmap).into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        match token.as_str() {
+            "beginbfchar" => {
+                while let Some(code) = tokens.next_if(|token| token != "endbfchar") {
+                    let target = tokens.next().unwrap_or_default();
+                    if let (Some(code), Some(target)) = (hex_code(&code), hex_text(&target)) {
+                        map.insert(code, target);
+                    }
+                }
+            }
+            "beginbfrange" => {
+                while let Some(low) = tokens.next_if(|token| token != "endbfrange") {
+                    let (Some(low), Some(high)) =
+                        (hex_code(&low), tokens.next().and_then(|t| hex_code(&t)))
+                    else {
+                        break;
+                    };
+
+                    if tokens.peek().map(String::as_str) == Some("[") {
+                        tokens.next();
+                        let targets = tokens.by_ref().take_while(|token| token != "]");
+                        for (code, target) in (low..).zip(targets) {
+                            if let Some(target) = hex_text(&target) {
+                                map.insert(code, target);
+                            }
+                        }
+                    } else if let Some(start) = tokens.next().and_then(|t| hex_text(&t)) {
+                        // Consecutive codes map to consecutive characters
+                        let mut units: Vec<u16> = start.encode_utf16().collect();
+                        for code in low..=high {
+                            map.insert(code, String::from_utf16_lossy(&units));
+                            if let Some(last) = units.last_mut() {
+                                *last = last.wrapping_add(1);
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    map
+}
+
+// Hex strings, array brackets and bare keywords, which is all bfchar and bfrange use
+fn cmap_tokens(cmap: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = cmap.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '<' => {
+                let hex: String = chars.by_ref().take_while(|c| *c != '>').collect();
+                tokens.push(format!("<{}>", hex));
+            }
+            '[' | ']' => tokens.push(c.to_string()),
+            c if c.is_whitespace() => {}
+            c => {
+                let mut word = c.to_string();
+                while let Some(next) = chars.peek() {
+                    if next.is_whitespace() || matches!(next, '<' | '[' | ']') {
+                        break;
+                    }
+                    word.push(*next);
+                    chars.next();
+                }
+                tokens.push(word);
+            }
+        }
+    }
+
+    tokens
+}
+
+fn hex_bytes(token: &str) -> Option<Vec<u8>> {
+    let hex: String = token
+        .strip_prefix('<')?
+        .strip_suffix('>')?
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn hex_code(token: &str) -> Option<u32> {
+    let bytes = hex_bytes(token)?;
+    Some(bytes.iter().fold(0u32, |acc, byte| acc << 8 | *byte as u32))
+}
+
+// Destination strings are UTF-16BE
+fn hex_text(token: &str) -> Option<String> {
+    let units: Vec<u16> = hex_bytes(token)?
+        .chunks(2)
+        .map(|pair| pair.iter().fold(0u16, |acc, byte| acc << 8 | *byte as u16))
+        .collect();
+    Some(String::from_utf16_lossy(&units))
+}
+
+async fn run_tesseract(path: &Path) -> Result<String, String> {
+    let output = Command::new(TESSERACT_COMMAND)
+        .arg(path)
+        .arg("stdout")
+        .output()
+        .await
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                "OCR needs the tesseract command-line tool, which was not found on the PATH"
+                    .to_string()
+            }
+            _ => format!("Failed to run tesseract: {}", e),
+        })?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Tesseract failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
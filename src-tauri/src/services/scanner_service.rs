@@ -1,33 +1,313 @@
 use crate::domain::*;
-use crate::generators::ScanGenerator;
-use rand::Rng;
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use crate::generators::{
+    append_pdf_pages, file_checksum, page_breakdown, reorder_pdf_pages, save_contact_sheet,
+    save_thumbnail, validate_filename_template, validate_pdf_password, GenerationError,
+    ScanGenerator,
+};
+use crate::services::{
+    classify_document_text, extract_scan_text, upload_scan_file, validate_upload_target,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, VecDeque};
+use std::io::{Read, Seek, SeekFrom};
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{AcquireError, OwnedSemaphorePermit, Semaphore};
 use tokio::time::{sleep, Duration};
 
 #[derive(Clone)]
 pub struct ScannerService {
     scanners: Arc<Mutex<HashMap<String, Scanner>>>,
     jobs: Arc<Mutex<HashMap<String, ScanJob>>>,
+    // Bounds how many scan files are generated at once across all jobs
+    generation_limiter: Arc<GenerationLimiter>,
+    // Finished jobs beyond this count are evicted, least recently updated first
+    max_jobs: Arc<Mutex<usize>>,
+    // Started jobs waiting for a free slot on their scanner, in start order
+    queues: Arc<Mutex<HashMap<String, VecDeque<String>>>>,
+    // Per-scanner limit on simultaneously running jobs; unset means DEFAULT_MAX_CONCURRENT
+    max_concurrent: Arc<Mutex<HashMap<String, usize>>>,
+    // Saved scan profiles keyed by name
+    profiles: Arc<Mutex<HashMap<String, ScanProfile>>>,
+    // Background task running simulate_scanner_events, if started
+    event_loop: Arc<Mutex<Option<tauri::async_runtime::JoinHandle<()>>>>,
+    // Timers waiting to start scheduled jobs, keyed by job ID
+    schedules: Arc<Mutex<HashMap<String, tauri::async_runtime::JoinHandle<()>>>>,
+    // Failure rates and the RNG behind every simulated outcome
+    simulation: Arc<Mutex<Simulation>>,
+    // Usage counters keyed by scanner ID, saved with the scanners and jobs
+    usage: Arc<Mutex<HashMap<String, ScannerMetrics>>>,
+    // Set once discover_scanners has completed since the service started
+    discovery_ran: Arc<AtomicBool>,
+    // Scan results as last read from disk, keyed by job ID
+    result_cache: Arc<Mutex<HashMap<String, CachedScanResult>>>,
+    // Scan tasks spawned and not yet finished
+    running_scans: Arc<AtomicUsize>,
+    // Set by shutdown, after which queued jobs are no longer started
+    shutting_down: Arc<AtomicBool>,
+    app_handle: Arc<Mutex<Option<AppHandle>>>,
 }
 
 impl ScannerService {
+    const DEFAULT_MAX_JOBS: usize = 1000;
+    const DEFAULT_MAX_CONCURRENT: usize = 1;
+    const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(200);
+    // Simulated time each enabled post-processing adjustment takes
+    const POST_PROCESSING_STEP_DURATION: Duration = Duration::from_millis(500);
+    // Share of a job's progress covered by scanning when post-processing follows it
+    const SCAN_PROGRESS_SHARE: f32 = 0.8;
+    // Preview scans trade detail for speed
+    const PREVIEW_RESOLUTION: u32 = 75;
+    const PREVIEW_QUALITY: u8 = 50;
+    const PREVIEW_PROGRESS_STEPS: u32 = 5;
+    // Simulated time a scanner needs after power-on or reconnecting before it takes jobs
+    const WARM_UP_DURATION: Duration = Duration::from_secs(3);
+    // Scanner error that pauses its scanning jobs until clear_jam is called
+    const PAPER_JAM: &'static str = "Paper jam detected";
+    // Settings that asked for a password protected PDF after the password was dropped
+    const PDF_PASSWORD_LOST: &'static str =
+        "The PDF password was not kept with these settings, enter it again to scan a protected file";
+    // Random scan failure, retried while the job's max_retries allow
+    const HARDWARE_ERROR: &'static str = "Scanner hardware error";
+    // Pause before rescanning after a hardware error
+    const SCAN_RETRY_DELAY: Duration = Duration::from_secs(1);
+    // Error of a document feeder job that finds no paper to feed
+    const ADF_EMPTY: &'static str = "ADF empty";
+    // How long shutdown waits for cancelled scans to stop and clean up their files
+    const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(5);
+    pub const DEFAULT_EVENT_INTERVAL: Duration = Duration::from_secs(30);
+    /// Most bytes read_scan_file returns in one call
+    pub const MAX_SCAN_READ_BYTES: u64 = 8 * 1024 * 1024;
+
     pub fn new() -> Self {
         let service = Self {
             scanners: Arc::new(Mutex::new(HashMap::new())),
             jobs: Arc::new(Mutex::new(HashMap::new())),
+            generation_limiter: Arc::new(GenerationLimiter::new(
+                Self::default_generation_concurrency(),
+            )),
+            max_jobs: Arc::new(Mutex::new(Self::DEFAULT_MAX_JOBS)),
+            queues: Arc::new(Mutex::new(HashMap::new())),
+            max_concurrent: Arc::new(Mutex::new(HashMap::new())),
+            profiles: Arc::new(Mutex::new(HashMap::new())),
+            event_loop: Arc::new(Mutex::new(None)),
+            schedules: Arc::new(Mutex::new(HashMap::new())),
+            simulation: Arc::new(Mutex::new(Simulation::new(SimulationParams::default()))),
+            usage: Arc::new(Mutex::new(HashMap::new())),
+            discovery_ran: Arc::new(AtomicBool::new(false)),
+            result_cache: Arc::new(Mutex::new(HashMap::new())),
+            running_scans: Arc::new(AtomicUsize::new(0)),
+            shutting_down: Arc::new(AtomicBool::new(false)),
+            app_handle: Arc::new(Mutex::new(None)),
         };
 
+        if let Err(e) = service.load_state() {
+            println!("Failed to load saved state: {}", e);
+        }
+        if let Err(e) = service.load_profiles() {
+            println!("Failed to load scan profiles: {}", e);
+        }
+
         println!("ScannerService initialized. Use discover_scanners() to detect system scanners.");
         service
     }
 
+    fn app_data_dir() -> Result<PathBuf, String> {
+        // Tests save their state apart from the user's
+        if cfg!(test) {
+            return Ok(std::env::temp_dir().join("scanner-tool-tests"));
+        }
+        let data_dir = dirs::data_dir().ok_or("Could not find app data directory")?;
+        Ok(data_dir.join("com.linzell.scanner-tool"))
+    }
+
+    fn state_file_path() -> Result<PathBuf, String> {
+        Ok(Self::app_data_dir()?.join("scanner_state.json"))
+    }
+
+    fn profiles_file_path() -> Result<PathBuf, String> {
+        Ok(Self::app_data_dir()?.join("scan_profiles.json"))
+    }
+
+    /// Restore scanners, job history and usage metrics saved by a previous run
+    pub fn load_state(&self) -> Result<(), String> {
+        let path = Self::state_file_path()?;
+        if !path.exists() {
+            return Ok(());
+        }
+
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read state file: {}", e))?;
+        let state: PersistedState = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse state file: {}", e))?;
+
+        let mut scanners = self.scanners.lock().map_err(|e| e.to_string())?;
+        let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+
+        for mut scanner in state.scanners {
+            // No scan can still be running, nor a warm-up timer, on a freshly started app
+            if matches!(
+                scanner.status,
+                ScannerStatus::Busy | ScannerStatus::WarmingUp
+            ) {
+                scanner.status = ScannerStatus::Available;
+            }
+            scanners.insert(scanner.id.clone(), scanner);
+        }
+
+        for mut job in state.jobs {
+            if matches!(
+                job.status,
+                JobStatus::Scanning | JobStatus::Paused | JobStatus::Processing
+            ) {
+                job.fail("Interrupted by application restart".to_string());
+            }
+            jobs.insert(job.id.clone(), job);
+        }
+
+        self.usage
+            .lock()
+            .map_err(|e| e.to_string())?
+            .extend(state.usage);
+
+        println!(
+            "Restored {} scanners and {} jobs from {:?}",
+            scanners.len(),
+            jobs.len(),
+            path
+        );
+        Ok(())
+    }
+
+    /// Write scanners, jobs and usage metrics to the app data directory
+    pub fn save_state(&self) -> Result<(), String> {
+        Self::write_state(&self.scanners, &self.jobs, &self.usage)
+    }
+
+    fn write_state(
+        scanners: &Arc<Mutex<HashMap<String, Scanner>>>,
+        jobs: &Arc<Mutex<HashMap<String, ScanJob>>>,
+        usage: &Arc<Mutex<HashMap<String, ScannerMetrics>>>,
+    ) -> Result<(), String> {
+        // Snapshot each map separately; holding both locks at once could deadlock
+        // against code that takes them in the other order
+        let scanners: Vec<Scanner> = {
+            let scanners = scanners.lock().map_err(|e| e.to_string())?;
+            scanners.values().cloned().collect()
+        };
+        let jobs: Vec<ScanJob> = {
+            let jobs = jobs.lock().map_err(|e| e.to_string())?;
+            jobs.values().cloned().collect()
+        };
+        let usage = usage.lock().map_err(|e| e.to_string())?.clone();
+        let state = PersistedState {
+            scanners,
+            jobs,
+            usage,
+        };
+        let contents = serde_json::to_string_pretty(&state)
+            .map_err(|e| format!("Failed to serialize state: {}", e))?;
+
+        Self::write_app_data_file(&Self::state_file_path()?, &contents)
+    }
+
+    fn write_app_data_file(path: &Path, contents: &str) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+        }
+
+        // Write then rename so a crash mid-write never leaves a truncated file
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, contents)
+            .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+        std::fs::rename(&temp_path, path)
+            .map_err(|e| format!("Failed to replace {:?}: {}", path, e))?;
+
+        Ok(())
+    }
+
+    /// Restore scan profiles saved by a previous run
+    pub fn load_profiles(&self) -> Result<(), String> {
+        let path = Self::profiles_file_path()?;
+        if !path.exists() {
+            return Ok(());
+        }
+
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read profiles file: {}", e))?;
+        let saved: Vec<ScanProfile> = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse profiles file: {}", e))?;
+
+        let mut profiles = self.profiles.lock().map_err(|e| e.to_string())?;
+        for profile in saved {
+            profiles.insert(profile.name.clone(), profile);
+        }
+        Ok(())
+    }
+
+    fn save_profiles(&self) -> Result<(), String> {
+        let profiles = self.list_profiles().map_err(|e| e.to_string())?;
+        let contents = serde_json::to_string_pretty(&profiles)
+            .map_err(|e| format!("Failed to serialize profiles: {}", e))?;
+
+        Self::write_app_data_file(&Self::profiles_file_path()?, &contents)
+    }
+
+    // Persistence is best effort; a failed save never fails the operation itself
+    fn persist_state(
+        scanners: &Arc<Mutex<HashMap<String, Scanner>>>,
+        jobs: &Arc<Mutex<HashMap<String, ScanJob>>>,
+        usage: &Arc<Mutex<HashMap<String, ScannerMetrics>>>,
+    ) {
+        if let Err(e) = Self::write_state(scanners, jobs, usage) {
+            println!("Failed to save state: {}", e);
+        }
+    }
+
+    fn persist(&self) {
+        Self::persist_state(&self.scanners, &self.jobs, &self.usage);
+    }
+
+    /// Attach the Tauri app handle so the service can emit events to the frontend
+    pub fn set_app_handle(&self, app_handle: AppHandle) {
+        if let Ok(mut handle) = self.app_handle.lock() {
+            *handle = Some(app_handle);
+        }
+    }
+
+    fn emit_event<S: serde::Serialize + Clone>(&self, event: &str, payload: S) {
+        Self::emit_with(&self.app_handle, event, payload);
+    }
+
+    // Used by background tasks, which hold the shared handle rather than the service
+    fn emit_with<S: serde::Serialize + Clone>(
+        app_handle: &Arc<Mutex<Option<AppHandle>>>,
+        event: &str,
+        payload: S,
+    ) {
+        let handle = match app_handle.lock() {
+            Ok(handle) => handle.clone(),
+            Err(_) => return,
+        };
+
+        if let Some(handle) = handle {
+            if let Err(e) = handle.emit(event, payload) {
+                println!("Failed to emit {} event: {}", event, e);
+            }
+        }
+    }
+
     // Scanner discovery is now handled by the discover_scanners() method
     // which simulates system-specific scanner detection APIs
 
-    pub fn get_scanners(&self) -> Result<Vec<Scanner>, String> {
+    pub fn get_scanners(&self) -> Result<Vec<Scanner>, ScannerError> {
         let scanners = self.scanners.lock().map_err(|e| e.to_string())?;
-        let current_system = self.detect_platform();
+        let current_system = Self::detect_platform();
 
         // Return scanners for the current system, but if none found, suggest discovery
         let system_scanners: Vec<Scanner> = scanners
@@ -36,19 +316,36 @@ impl ScannerService {
             .cloned()
             .collect();
 
-        if system_scanners.is_empty() {
+        if system_scanners.is_empty() && !self.discovery_ran() {
             println!("No scanners found. Use discover_scanners() to detect system scanners.");
         }
 
         Ok(system_scanners)
     }
 
-    pub fn get_all_scanners(&self) -> Result<Vec<Scanner>, String> {
+    /// Like get_scanners, but runs discovery first if it has not run yet, so a
+    /// fresh start does not report "no scanners" before looking for any
+    pub async fn get_scanners_or_discover(&self) -> Result<Vec<Scanner>, ScannerError> {
+        if !self.discovery_ran() {
+            self.discover_scanners().await?;
+        }
+        self.get_scanners()
+    }
+
+    /// Whether discover_scanners has completed since the service started
+    pub fn discovery_ran(&self) -> bool {
+        self.discovery_ran.load(Ordering::SeqCst)
+    }
+
+    pub fn get_all_scanners(&self) -> Result<Vec<Scanner>, ScannerError> {
         let scanners = self.scanners.lock().map_err(|e| e.to_string())?;
         Ok(scanners.values().cloned().collect())
     }
 
-    pub fn get_scanners_by_system(&self, system_type: SystemType) -> Result<Vec<Scanner>, String> {
+    pub fn get_scanners_by_system(
+        &self,
+        system_type: SystemType,
+    ) -> Result<Vec<Scanner>, ScannerError> {
         let scanners = self.scanners.lock().map_err(|e| e.to_string())?;
         Ok(scanners
             .values()
@@ -57,47 +354,179 @@ impl ScannerService {
             .collect())
     }
 
-    pub async fn discover_scanners(&self) -> Result<Vec<Scanner>, String> {
+    /// Scanners on any platform that satisfy every criterion in the filter
+    pub fn find_scanners(&self, filter: &ScannerFilter) -> Result<Vec<Scanner>, ScannerError> {
+        let scanners = self.scanners.lock().map_err(|e| e.to_string())?;
+        Ok(scanners
+            .values()
+            .filter(|scanner| filter.matches(scanner))
+            .cloned()
+            .collect())
+    }
+
+    /// Scanners on any platform carrying the tag, ignoring case
+    pub fn get_scanners_by_tag(&self, tag: &str) -> Result<Vec<Scanner>, ScannerError> {
+        let scanners = self.scanners.lock().map_err(|e| e.to_string())?;
+        Ok(scanners
+            .values()
+            .filter(|scanner| scanner.has_tag(tag))
+            .cloned()
+            .collect())
+    }
+
+    /// Tag a scanner, for example with its location. Adding a tag it already
+    /// carries leaves it unchanged.
+    pub fn add_scanner_tag(&self, scanner_id: &str, tag: &str) -> Result<Scanner, ScannerError> {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            return Err(ScannerError::InvalidSettings(
+                "Scanner tag cannot be empty".to_string(),
+            ));
+        }
+
+        let scanner = {
+            let mut scanners = self.scanners.lock().map_err(|e| e.to_string())?;
+            let scanner = scanners
+                .get_mut(scanner_id)
+                .ok_or_else(|| ScannerError::scanner_not_found(scanner_id))?;
+            if scanner.has_tag(tag) {
+                return Ok(scanner.clone());
+            }
+            scanner.tags.push(tag.to_string());
+            scanner.clone()
+        };
+
+        println!("Tagged scanner {} with {}", scanner_id, tag);
+        self.persist();
+        Ok(scanner)
+    }
+
+    /// Remove a tag from a scanner, ignoring case. Removing a tag it does not
+    /// carry leaves it unchanged.
+    pub fn remove_scanner_tag(&self, scanner_id: &str, tag: &str) -> Result<Scanner, ScannerError> {
+        let tag = tag.trim();
+        let scanner = {
+            let mut scanners = self.scanners.lock().map_err(|e| e.to_string())?;
+            let scanner = scanners
+                .get_mut(scanner_id)
+                .ok_or_else(|| ScannerError::scanner_not_found(scanner_id))?;
+            let tag_count = scanner.tags.len();
+            scanner
+                .tags
+                .retain(|existing| !existing.eq_ignore_ascii_case(tag));
+            if scanner.tags.len() == tag_count {
+                return Ok(scanner.clone());
+            }
+            scanner.clone()
+        };
+
+        println!("Removed tag {} from scanner {}", tag, scanner_id);
+        self.persist();
+        Ok(scanner)
+    }
+
+    /// Detect the scanners attached to this system and return them once discovery
+    /// completes. Each device is also announced with a scanner-discovered event as
+    /// soon as it is found, and the full list with a final discovery-complete event.
+    pub async fn discover_scanners(&self) -> Result<Vec<Scanner>, ScannerError> {
         // Simulate scanner discovery process with system detection delay
         sleep(Duration::from_millis(1500)).await;
 
-        let current_system = self.detect_platform();
+        let current_system = Self::detect_platform();
         println!("Discovering scanners for system: {:?}", current_system);
 
-        // Clear existing scanners before discovery
-        {
-            let mut scanners = self.scanners.lock().map_err(|e| e.to_string())?;
-            scanners.clear();
-        } // Release lock before async operations
-
         // Discover scanners based on system type
         let discovered_scanners = match current_system {
             SystemType::Windows => {
                 println!("Simulating WIA scanner discovery...");
-                self.simulate_windows_discovery().await?
+                self.simulate_windows_discovery()
+                    .await
+                    .map_err(ScannerError::Internal)?
             }
             SystemType::MacOS => {
                 println!("Simulating Image Capture framework discovery...");
-                self.simulate_macos_discovery().await?
+                self.simulate_macos_discovery()
+                    .await
+                    .map_err(ScannerError::Internal)?
             }
             SystemType::Linux => {
                 println!("Simulating SANE scanner discovery...");
-                self.simulate_linux_discovery().await?
+                self.simulate_linux_discovery()
+                    .await
+                    .map_err(ScannerError::Internal)?
             }
         };
 
-        // Add discovered scanners to the collection
+        // Devices found earlier that did not show up this time are dropped, manually
+        // added scanners and network scanners are left alone
         {
             let mut scanners = self.scanners.lock().map_err(|e| e.to_string())?;
-            for scanner in &discovered_scanners {
-                scanners.insert(scanner.id.clone(), scanner.clone());
-            }
-            println!("Discovery completed. Found {} scanners", scanners.len());
+            scanners.retain(|scanner_id, existing| {
+                existing.source == ScannerSource::Manual
+                    || existing.network_address.is_some()
+                    || discovered_scanners
+                        .iter()
+                        .any(|scanner| &scanner.id == scanner_id)
+            });
+            println!(
+                "Discovery completed. Found {} scanners, {} in total",
+                discovered_scanners.len(),
+                scanners.len()
+            );
         }
 
+        self.discovery_ran.store(true, Ordering::SeqCst);
+        self.persist();
+        self.emit_event("discovery-complete", discovered_scanners.clone());
         Ok(discovered_scanners)
     }
 
+    /// Run discover_scanners in the background and return right away; results
+    /// arrive as scanner-discovered and discovery-complete events
+    pub fn start_scanner_discovery(&self) {
+        let service = self.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = service.discover_scanners().await {
+                println!("Scanner discovery failed: {}", e);
+            }
+        });
+    }
+
+    // Merge a device into the collection as soon as discovery finds it: a known
+    // device keeps its ID and stays busy or warming up, while a new one or one back
+    // from offline or an error warms up first
+    fn merge_discovered(&self, mut scanner: Scanner) -> Result<Scanner, String> {
+        let warm_up = {
+            let mut scanners = self.scanners.lock().map_err(|e| e.to_string())?;
+            let existing = scanners.values().find(|existing| {
+                existing.source == ScannerSource::Discovered && existing.is_same_device(&scanner)
+            });
+            if let Some(existing) = existing {
+                scanner.id = existing.id.clone();
+                scanner.tags = existing.tags.clone();
+            }
+            let warm_up = match existing.map(|existing| &existing.status) {
+                Some(status @ (ScannerStatus::Busy | ScannerStatus::WarmingUp)) => {
+                    scanner.status = status.clone();
+                    false
+                }
+                Some(ScannerStatus::Available) => false,
+                _ => scanner.status == ScannerStatus::Available,
+            };
+            if warm_up {
+                scanner.status = ScannerStatus::WarmingUp;
+            }
+            scanners.insert(scanner.id.clone(), scanner.clone());
+            warm_up
+        };
+
+        if warm_up {
+            self.schedule_warm_up(scanner.id.clone());
+        }
+        self.emit_event("scanner-discovered", scanner.clone());
+        Ok(scanner)
+    }
+
     async fn simulate_windows_discovery(&self) -> Result<Vec<Scanner>, String> {
         // Simulate WIA API calls with realistic delays
         let mut discovered = Vec::new();
@@ -107,27 +536,40 @@ impl ScannerService {
 
         sleep(Duration::from_millis(200)).await;
         println!("Found WIA-compatible device: HP ScanJet Pro 2500 f1");
-        let mut scanner1 = Scanner::new(
+        let mut scanner1 = Scanner::discovered(
             "HP ScanJet Pro 2500 f1 (WIA)".to_string(),
             ScannerType::DocumentFeeder,
             SystemType::Windows,
+            "CN79KBH0ZT",
         );
         scanner1.capabilities.max_resolution = 1200;
         scanner1.capabilities.has_duplex = true;
         scanner1.capabilities.has_adf = true;
-        discovered.push(scanner1);
+        scanner1.capabilities.adf_min_size = Some(PageDimensions {
+            width: 52,
+            height: 74,
+        });
+        scanner1.capabilities.adf_max_size = Some(PageDimensions {
+            width: 216,
+            height: 3100,
+        });
+        scanner1.capabilities.supported_resolutions =
+            ScannerCapabilities::standard_resolutions(1200);
+        discovered.push(self.merge_discovered(scanner1)?);
 
         sleep(Duration::from_millis(200)).await;
         println!("Found WIA-compatible device: Canon CanoScan LiDE 400");
-        let mut scanner2 = Scanner::new(
+        let mut scanner2 = Scanner::discovered(
             "Canon CanoScan LiDE 400 (WIA)".to_string(),
             ScannerType::Flatbed,
             SystemType::Windows,
+            "KQF04812",
         );
         scanner2.capabilities.max_resolution = 4800;
         scanner2.capabilities.has_duplex = false;
         scanner2.capabilities.has_adf = false;
-        discovered.push(scanner2);
+        scanner2.capabilities.supported_resolutions = vec![75, 150, 300, 600, 1200, 2400, 4800];
+        discovered.push(self.merge_discovered(scanner2)?);
 
         Ok(discovered)
     }
@@ -141,39 +583,66 @@ impl ScannerService {
 
         sleep(Duration::from_millis(250)).await;
         println!("Found Image Capture device: Brother MFC-L3770CDW");
-        let mut scanner1 = Scanner::new(
+        let mut scanner1 = Scanner::discovered(
             "Brother MFC-L3770CDW".to_string(),
             ScannerType::DocumentFeeder,
             SystemType::MacOS,
+            "E78412H8N539102",
         );
         scanner1.capabilities.max_resolution = 1200;
         scanner1.capabilities.has_duplex = true;
         scanner1.capabilities.has_adf = true;
-        discovered.push(scanner1);
+        scanner1.capabilities.adf_min_size = Some(PageDimensions {
+            width: 105,
+            height: 148,
+        });
+        scanner1.capabilities.adf_max_size = Some(PageDimensions {
+            width: 216,
+            height: 356,
+        });
+        scanner1.capabilities.supported_resolutions = vec![100, 150, 200, 300, 600, 1200];
+        scanner1.capabilities.supported_formats =
+            vec![OutputFormat::Pdf, OutputFormat::Jpeg, OutputFormat::Tiff];
+        discovered.push(self.merge_discovered(scanner1)?);
 
         sleep(Duration::from_millis(300)).await;
         println!("Found Image Capture device: Epson Perfection V850 Pro");
-        let mut scanner2 = Scanner::new(
+        let mut scanner2 = Scanner::discovered(
             "Epson Perfection V850 Pro".to_string(),
             ScannerType::PhotoScanner,
             SystemType::MacOS,
+            "X4GE008231",
         );
         scanner2.capabilities.max_resolution = 6400;
         scanner2.capabilities.has_duplex = false;
         scanner2.capabilities.has_adf = false;
-        discovered.push(scanner2);
+        scanner2.capabilities.supported_resolutions =
+            ScannerCapabilities::standard_resolutions(6400);
+        discovered.push(self.merge_discovered(scanner2)?);
 
         sleep(Duration::from_millis(200)).await;
         println!("Found Image Capture device: Canon imageFORMULA R40");
-        let mut scanner3 = Scanner::new(
+        let mut scanner3 = Scanner::discovered(
             "Canon imageFORMULA R40".to_string(),
             ScannerType::DocumentFeeder,
             SystemType::MacOS,
+            "JB315604",
         );
         scanner3.capabilities.max_resolution = 600;
         scanner3.capabilities.has_duplex = true;
         scanner3.capabilities.has_adf = true;
-        discovered.push(scanner3);
+        scanner3.capabilities.adf_min_size = Some(PageDimensions {
+            width: 51,
+            height: 54,
+        });
+        scanner3.capabilities.adf_max_size = Some(PageDimensions {
+            width: 216,
+            height: 1000,
+        });
+        scanner3.capabilities.supported_resolutions = vec![150, 200, 300, 600];
+        scanner3.capabilities.supported_formats =
+            vec![OutputFormat::Pdf, OutputFormat::Jpeg, OutputFormat::Png];
+        discovered.push(self.merge_discovered(scanner3)?);
 
         Ok(discovered)
     }
@@ -187,55 +656,180 @@ impl ScannerService {
 
         sleep(Duration::from_millis(300)).await;
         println!("Found SANE device: HP LaserJet MFP M28w");
-        let mut scanner1 = Scanner::new(
+        let mut scanner1 = Scanner::discovered(
             "HP LaserJet MFP M28w (SANE)".to_string(),
             ScannerType::Flatbed,
             SystemType::Linux,
+            "VNC3R81204",
         );
         scanner1.capabilities.max_resolution = 1200;
         scanner1.capabilities.has_duplex = false;
         scanner1.capabilities.has_adf = false;
-        discovered.push(scanner1);
+        scanner1.capabilities.supported_resolutions =
+            ScannerCapabilities::standard_resolutions(1200);
+        discovered.push(self.merge_discovered(scanner1)?);
 
         sleep(Duration::from_millis(250)).await;
         println!("Found SANE device: Epson ET-4850");
-        let mut scanner2 = Scanner::new(
+        let mut scanner2 = Scanner::discovered(
             "Epson ET-4850 (SANE)".to_string(),
             ScannerType::Flatbed,
             SystemType::Linux,
+            "X8HS031557",
         );
         scanner2.capabilities.max_resolution = 1200;
         scanner2.capabilities.has_duplex = false;
         scanner2.capabilities.has_adf = true;
-        discovered.push(scanner2);
+        scanner2.capabilities.adf_min_size = Some(PageDimensions {
+            width: 148,
+            height: 210,
+        });
+        scanner2.capabilities.adf_max_size = Some(PageDimensions {
+            width: 216,
+            height: 356,
+        });
+        scanner2.capabilities.supported_resolutions = vec![75, 150, 300, 600, 1200];
+        discovered.push(self.merge_discovered(scanner2)?);
+
+        Ok(discovered)
+    }
+
+    /// Look for network scanners on an IPv4 subnet such as "192.168.1.0/24" by
+    /// browsing for eSCL services over mDNS. Found devices are merged into the
+    /// scanner list like locally attached ones, and network scanners previously
+    /// found on the subnet that no longer answer are dropped.
+    pub async fn discover_network_scanners(
+        &self,
+        subnet: &str,
+    ) -> Result<Vec<Scanner>, ScannerError> {
+        let capabilities = self.get_platform_capabilities();
+        if !capabilities.network_discovery {
+            return Err(ScannerError::ScannerUnavailable(format!(
+                "Network scanner discovery is not supported by {}",
+                capabilities.scanner_api
+            )));
+        }
+        let subnet = Ipv4Subnet::parse(subnet).map_err(ScannerError::InvalidSettings)?;
+        let current_system = capabilities.platform;
+
+        println!("Browsing for _uscan._tcp services on {}...", subnet);
+        sleep(Duration::from_millis(800)).await;
+
+        // Hosts answering on the subnet, as (host number, name, type, serial)
+        let devices = [
+            (
+                23,
+                "Brother ADS-2800W (eSCL)",
+                ScannerType::DocumentFeeder,
+                "U63882E7J201144",
+            ),
+            (
+                57,
+                "HP Color LaserJet MFP M479fdw (eSCL)",
+                ScannerType::Flatbed,
+                "CNB1R4K2QW",
+            ),
+            (
+                140,
+                "Canon imageRUNNER C3226i (eSCL)",
+                ScannerType::DocumentFeeder,
+                "XNL04137",
+            ),
+        ];
+
+        let mut discovered = Vec::new();
+        for (host, name, scanner_type, serial_number) in devices {
+            let Some(address) = subnet.host(host) else {
+                continue;
+            };
+            sleep(Duration::from_millis(250)).await;
+            println!("Found eSCL device: {} at {}", name, address);
 
+            let mut scanner = Scanner::discovered(
+                name.to_string(),
+                scanner_type,
+                current_system,
+                serial_number,
+            );
+            scanner.network_address = Some(address.to_string());
+            scanner.capabilities.max_resolution = 600;
+            scanner.capabilities.supported_resolutions = vec![150, 200, 300, 600];
+            if scanner_type == ScannerType::DocumentFeeder {
+                scanner.capabilities.has_duplex = true;
+                scanner.capabilities.has_adf = true;
+                scanner.capabilities.adf_max_size = Some(PageDimensions {
+                    width: 216,
+                    height: 356,
+                });
+            }
+            discovered.push(
+                self.merge_discovered(scanner)
+                    .map_err(ScannerError::Internal)?,
+            );
+        }
+
+        {
+            let mut scanners = self.scanners.lock().map_err(|e| e.to_string())?;
+            scanners.retain(|scanner_id, existing| {
+                let on_subnet = existing
+                    .network_address
+                    .as_deref()
+                    .and_then(|address| address.parse::<Ipv4Addr>().ok())
+                    .is_some_and(|address| subnet.contains(address));
+                existing.source == ScannerSource::Manual
+                    || !on_subnet
+                    || discovered.iter().any(|scanner| &scanner.id == scanner_id)
+            });
+            println!(
+                "Network discovery on {} completed. Found {} scanners, {} in total",
+                subnet,
+                discovered.len(),
+                scanners.len()
+            );
+        }
+
+        self.persist();
+        self.emit_event("network-discovery-complete", discovered.clone());
         Ok(discovered)
     }
 
-    pub fn get_scanner(&self, scanner_id: &str) -> Result<Scanner, String> {
+    pub fn get_scanner(&self, scanner_id: &str) -> Result<Scanner, ScannerError> {
         let scanners = self.scanners.lock().map_err(|e| e.to_string())?;
         scanners
             .get(scanner_id)
             .cloned()
-            .ok_or_else(|| format!("Scanner with ID {} not found", scanner_id))
+            .ok_or_else(|| ScannerError::scanner_not_found(scanner_id))
     }
 
     pub fn get_scanner_capabilities(
         &self,
         scanner_id: &str,
-    ) -> Result<ScannerCapabilities, String> {
+    ) -> Result<ScannerCapabilities, ScannerError> {
         let scanner = self.get_scanner(scanner_id)?;
         Ok(scanner.capabilities)
     }
 
-    pub async fn test_scanner_connection(&self, scanner_id: &str) -> Result<bool, String> {
-        let scanner = self.get_scanner(scanner_id)?;
+    pub async fn test_scanner_connection(&self, scanner_id: &str) -> Result<bool, ScannerError> {
+        Ok(self
+            .test_scanner_connection_detailed(scanner_id)
+            .await?
+            .success)
+    }
 
-        // Simulate connection test delay
-        sleep(Duration::from_millis(500)).await;
+    /// Connection test reporting the measured round trip and, on failure, why
+    pub async fn test_scanner_connection_detailed(
+        &self,
+        scanner_id: &str,
+    ) -> Result<ConnectionTestResult, ScannerError> {
+        const FAILURE_REASONS: [&str; 3] = [
+            "Device not responding",
+            "Connection reset by the device",
+            "Device rejected the connection handshake",
+        ];
+
+        let scanner = self.get_scanner(scanner_id)?;
 
         // Simulate random connection success/failure
-        let mut rng = rand::thread_rng();
         let success_rate = match scanner.scanner_type {
             ScannerType::Flatbed => 0.95,
             ScannerType::DocumentFeeder => 0.90,
@@ -244,8 +838,35 @@ impl ScannerService {
             ScannerType::FilmScanner => 0.88,
             ScannerType::PhotoScanner => 0.92,
         };
+        let (success, delay, failure_reason) = {
+            let mut simulation = Self::lock_simulation(&self.simulation);
+            let success_rate = simulation
+                .params
+                .connection_failure_rate
+                .map_or(success_rate, |failure_rate| 1.0 - failure_rate);
+            let rng = &mut simulation.rng;
+            (
+                rng.gen::<f32>() < success_rate,
+                Duration::from_millis(rng.gen_range(200..800)),
+                FAILURE_REASONS[rng.gen_range(0..FAILURE_REASONS.len())],
+            )
+        };
+
+        // Simulate the round trip to the device
+        let started = std::time::Instant::now();
+        sleep(delay).await;
+        let latency_ms = started.elapsed().as_millis() as u64;
 
-        Ok(rng.gen::<f32>() < success_rate)
+        let message = if success {
+            format!("{} responded in {} ms", scanner.name, latency_ms)
+        } else {
+            format!("{}: {}", failure_reason, scanner.name)
+        };
+        Ok(ConnectionTestResult {
+            success,
+            latency_ms,
+            message,
+        })
     }
 
     pub async fn create_scan_job(
@@ -253,254 +874,3020 @@ impl ScannerService {
         scanner_id: String,
         document_type: DocumentType,
         scan_settings: ScanSettings,
-    ) -> Result<String, String> {
-        // Verify scanner exists and is available
-        let scanner = self.get_scanner(&scanner_id)?;
-        if !scanner.is_available() {
-            return Err("Scanner is not available".to_string());
-        }
+        then: Option<PostAction>,
+    ) -> Result<String, ScannerError> {
+        self.validate_scan_job(&scanner_id, &document_type, &scan_settings)?;
 
         // Create new scan job
-        let job = ScanJob::new(scanner_id, document_type, scan_settings);
+        let mut job = ScanJob::new(scanner_id.clone(), document_type, scan_settings);
+        job.then = then;
         let job_id = job.id.clone();
 
         // Store the job
-        let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
-        jobs.insert(job_id.clone(), job);
+        {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            jobs.insert(job_id.clone(), job);
+        }
 
+        self.evict_finished_jobs().map_err(ScannerError::Internal)?;
+        self.persist();
+        self.notify_queue_changed(&scanner_id);
         Ok(job_id)
     }
 
-    pub async fn start_scan_job(&self, job_id: &str) -> Result<(), String> {
-        let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
-        let job = jobs
-            .get_mut(job_id)
-            .ok_or_else(|| format!("Job with ID {} not found", job_id))?;
-        job.start_scanning();
+    /// Run every check create_scan_job makes without creating a job, so a settings
+    /// form can tell whether the job would be accepted
+    pub fn validate_scan_job(
+        &self,
+        scanner_id: &str,
+        document_type: &DocumentType,
+        settings: &ScanSettings,
+    ) -> Result<(), ScannerError> {
+        // Verify scanner exists and can take jobs; busy scanners queue them
+        let scanner = self.get_scanner(scanner_id)?;
+        Self::check_accepts_jobs(&scanner)?;
 
-        // Clone job data for async processing
-        let job_clone = job.clone();
-        let jobs_arc = Arc::clone(&self.jobs);
-        let scanners_arc = Arc::clone(&self.scanners);
+        Self::validate_job_request(&scanner, document_type, settings)
+            .map_err(ScannerError::InvalidSettings)
+    }
 
-        // Spawn async task to simulate scanning process
-        tokio::spawn(async move {
-            Self::simulate_scanning_process(job_clone, jobs_arc, scanners_arc).await;
-        });
+    /// Create one job per entry on the same scanner, returning their IDs in order.
+    /// Every entry is validated first, so a single invalid entry rejects the whole
+    /// batch without creating any jobs.
+    pub async fn create_batch_jobs(
+        &self,
+        scanner_id: String,
+        requests: Vec<(DocumentType, ScanSettings)>,
+    ) -> Result<Vec<String>, ScannerError> {
+        if requests.is_empty() {
+            return Err(ScannerError::InvalidSettings(
+                "Batch must contain at least one job".to_string(),
+            ));
+        }
 
-        Ok(())
-    }
+        let scanner = self.get_scanner(&scanner_id)?;
+        Self::check_accepts_jobs(&scanner)?;
 
-    async fn simulate_scanning_process(
-        job: ScanJob,
-        jobs: Arc<Mutex<HashMap<String, ScanJob>>>,
-        scanners: Arc<Mutex<HashMap<String, Scanner>>>,
-    ) {
-        // Set scanner to busy
-        if let Ok(mut scanners_lock) = scanners.lock() {
-            if let Some(scanner) = scanners_lock.get_mut(&job.scanner_id) {
-                scanner.status = ScannerStatus::Busy;
-            }
+        for (index, (document_type, settings)) in requests.iter().enumerate() {
+            Self::validate_job_request(&scanner, document_type, settings).map_err(|e| {
+                ScannerError::InvalidSettings(format!("Batch job {}: {}", index + 1, e))
+            })?;
         }
 
-        // Generate random values at the start to avoid Send issues
-        let scan_duration_ms = {
-            let mut rng = rand::thread_rng();
-            rng.gen_range(3000..8000)
-        };
-        let should_fail = {
-            let mut rng = rand::thread_rng();
-            rng.gen::<f32>() < 0.05
+        let job_ids = {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            requests
+                .into_iter()
+                .map(|(document_type, settings)| {
+                    let job = ScanJob::new(scanner_id.clone(), document_type, settings);
+                    let job_id = job.id.clone();
+                    jobs.insert(job_id.clone(), job);
+                    job_id
+                })
+                .collect()
         };
 
-        let scan_duration = Duration::from_millis(scan_duration_ms);
-        let steps = 20;
-        let step_duration = scan_duration / steps;
+        self.evict_finished_jobs().map_err(ScannerError::Internal)?;
+        self.persist();
+        self.notify_queue_changed(&scanner.id);
+        Ok(job_ids)
+    }
 
-        // Simulate scanning progress
-        for step in 1..=steps {
-            sleep(step_duration).await;
+    fn validate_job_request(
+        scanner: &Scanner,
+        document_type: &DocumentType,
+        settings: &ScanSettings,
+    ) -> Result<(), String> {
+        let settings = &settings.clone().with_paper_for(*document_type);
+        if settings.password_lost() {
+            return Err(Self::PDF_PASSWORD_LOST.to_string());
+        }
+        Self::validate_settings(scanner, settings)?;
+        Self::validate_input_source(scanner, settings)?;
+        Self::validate_document_type(scanner, document_type, settings)?;
 
-            let progress = step as f32 / steps as f32;
+        if settings.page_count == 0 {
+            return Err("Page count must be at least 1".to_string());
+        }
 
-            // Update job progress
-            if let Ok(mut jobs_lock) = jobs.lock() {
-                if let Some(stored_job) = jobs_lock.get_mut(&job.id) {
-                    stored_job.update_progress(progress);
-                }
+        if let Some(steps) = settings.progress_steps {
+            if !(1..=ScanSettings::MAX_PROGRESS_STEPS).contains(&steps) {
+                return Err(format!(
+                    "Progress steps must be between 1 and {}",
+                    ScanSettings::MAX_PROGRESS_STEPS
+                ));
             }
+        }
 
-            // Small chance of random failure
-            if should_fail && step > 10 {
-                println!("Simulating scanner failure for job: {}", job.id);
-                if let Ok(mut jobs_lock) = jobs.lock() {
-                    if let Some(stored_job) = jobs_lock.get_mut(&job.id) {
-                        stored_job.fail("Scanner hardware error".to_string());
-                    }
-                }
-
-                // Set scanner back to available
-                if let Ok(mut scanners_lock) = scanners.lock() {
-                    if let Some(scanner) = scanners_lock.get_mut(&job.scanner_id) {
-                        println!(
-                            "Setting scanner {} back to available after failure",
-                            scanner.name
-                        );
-                        scanner.status = ScannerStatus::Available;
-                    }
-                }
-                return;
-            }
+        if settings.timeout_secs == 0 {
+            return Err("Scan timeout must be at least 1 second".to_string());
         }
 
-        // Generate scan file
-        println!("Generating scan file for job: {}", job.id);
-        let output_dir = match ScanGenerator::get_output_directory() {
-            Ok(dir) => dir,
-            Err(e) => {
-                println!("Failed to get output directory: {}", e);
-                if let Ok(mut jobs_lock) = jobs.lock() {
-                    if let Some(stored_job) = jobs_lock.get_mut(&job.id) {
-                        stored_job.fail(format!("Failed to create output directory: {}", e));
-                    }
-                }
-                return;
-            }
-        };
+        if settings.max_retries > ScanSettings::MAX_RETRIES {
+            return Err(format!(
+                "Max retries must be at most {}",
+                ScanSettings::MAX_RETRIES
+            ));
+        }
 
-        let filename = ScanGenerator::generate_filename(
-            &job.document_type,
-            &job.scan_settings.output_format,
-            &chrono::Utc::now(),
-        );
-        let output_path = output_dir.join(filename);
+        if settings.pdf_a_level.is_some() && settings.output_format != OutputFormat::Pdf {
+            return Err("PDF/A compliance requires PDF output".to_string());
+        }
 
-        let scan_result = match ScanGenerator::generate_scan_file(
-            &job.document_type,
-            &job.scan_settings,
-            &output_path,
-        )
-        .await
-        {
-            Ok(result) => {
-                println!("Scan file generated: {:?}", output_path);
-                Some(result)
+        if let Some(password) = &settings.pdf_password {
+            if settings.output_format != OutputFormat::Pdf {
+                return Err("Password protection requires PDF output".to_string());
             }
-            Err(e) => {
-                println!("Failed to generate scan file: {}", e);
-                if let Ok(mut jobs_lock) = jobs.lock() {
-                    if let Some(stored_job) = jobs_lock.get_mut(&job.id) {
-                        stored_job.fail(format!("Failed to generate file: {}", e));
-                    }
-                }
-                return;
+            if settings.pdf_a_level.is_some() {
+                return Err("PDF/A files cannot be password protected".to_string());
             }
-        };
+            validate_pdf_password(password)?;
+        }
 
-        // Complete the job
-        println!("Completing scan job: {}", job.id);
-        if let Ok(mut jobs_lock) = jobs.lock() {
-            if let Some(stored_job) = jobs_lock.get_mut(&job.id) {
-                stored_job.complete();
-                stored_job.scan_result = scan_result;
+        if let Some(watermark) = &settings.watermark {
+            if settings.output_format != OutputFormat::Pdf {
+                return Err("Watermarks require PDF output".to_string());
+            }
+            if watermark.trim().is_empty() {
+                return Err("Watermark text cannot be empty".to_string());
             }
         }
 
-        // Set scanner back to available
+        if let Some(payload) = &settings.barcode_payload {
+            if *document_type != DocumentType::Barcode {
+                return Err("A barcode payload requires the Barcode document type".to_string());
+            }
+            ScanGenerator::validate_barcode_payload(payload)?;
+        }
+
+        for (name, value) in [
+            ("Brightness", settings.brightness),
+            ("Contrast", settings.contrast),
+        ] {
+            if !(-100..=100).contains(&value) {
+                return Err(format!("{} must be between -100 and 100", name));
+            }
+        }
+
+        if let Some(locale) = &settings.locale {
+            if !ScanGenerator::is_locale_supported(locale) {
+                return Err(format!("Unsupported locale: {}", locale));
+            }
+        }
+
+        if let Some(target) = &settings.upload {
+            validate_upload_target(target)?;
+        }
+
+        if let Some(dir) = &settings.output_dir {
+            ScanGenerator::validate_output_directory(dir)?;
+        }
+
+        if let Some(template) = &settings.filename_template {
+            validate_filename_template(template)?;
+        }
+
+        Ok(())
+    }
+
+    /// Save a profile, replacing any existing profile with the same name
+    pub fn save_profile(&self, mut profile: ScanProfile) -> Result<(), ScannerError> {
+        profile.name = profile.name.trim().to_string();
+        if profile.name.is_empty() {
+            return Err(ScannerError::InvalidSettings(
+                "Profile name cannot be empty".to_string(),
+            ));
+        }
+
+        // The saved profile drops the password but remembers one was set
+        profile.settings = profile.settings.with_encryption_recorded();
+        {
+            let mut profiles = self.profiles.lock().map_err(|e| e.to_string())?;
+            profiles.insert(profile.name.clone(), profile);
+        }
+
+        self.save_profiles().map_err(ScannerError::IoError)
+    }
+
+    /// Saved profiles in name order
+    pub fn list_profiles(&self) -> Result<Vec<ScanProfile>, ScannerError> {
+        let profiles = self.profiles.lock().map_err(|e| e.to_string())?;
+        let mut list: Vec<ScanProfile> = profiles.values().cloned().collect();
+        list.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(list)
+    }
+
+    pub fn delete_profile(&self, profile_name: &str) -> Result<(), ScannerError> {
+        {
+            let mut profiles = self.profiles.lock().map_err(|e| e.to_string())?;
+            profiles.remove(profile_name).ok_or_else(|| {
+                ScannerError::ProfileNotFound(format!("Profile '{}' not found", profile_name))
+            })?;
+        }
+
+        self.save_profiles().map_err(ScannerError::IoError)
+    }
+
+    /// Create a job on the scanner using a saved profile's document type and settings
+    pub async fn create_job_from_profile(
+        &self,
+        scanner_id: String,
+        profile_name: &str,
+    ) -> Result<String, ScannerError> {
+        let profile = {
+            let profiles = self.profiles.lock().map_err(|e| e.to_string())?;
+            profiles.get(profile_name).cloned().ok_or_else(|| {
+                ScannerError::ProfileNotFound(format!("Profile '{}' not found", profile_name))
+            })?
+        };
+
+        self.create_scan_job(scanner_id, profile.document_type, profile.settings, None)
+            .await
+    }
+
+    /// List, in order, the steps a scan job with these settings would go through,
+    /// without creating or running anything
+    pub fn describe_scan_pipeline(
+        &self,
+        scanner_id: &str,
+        document_type: DocumentType,
+        settings: ScanSettings,
+        then: Option<PostAction>,
+    ) -> Result<Vec<String>, ScannerError> {
+        let scanner = self.get_scanner(scanner_id)?;
+        Self::validate_job_request(&scanner, &document_type, &settings)
+            .map_err(ScannerError::InvalidSettings)?;
+        let settings = settings.with_adf_sheets().with_paper_for(document_type);
+
+        let source = match settings.input_source {
+            InputSource::Flatbed => "flatbed",
+            InputSource::Adf => "document feeder",
+        };
+        // JPEG and PNG hold a single page
+        let pages = match settings.output_format {
+            OutputFormat::Pdf | OutputFormat::Tiff => settings.page_count,
+            OutputFormat::Jpeg | OutputFormat::Png => 1,
+        };
+        let sides = if settings.duplex { "duplex" } else { "simplex" };
+        let mut steps = vec![
+            format!(
+                "Scan {} {} page(s) at {} DPI in {:?} from the {} of {}",
+                pages, sides, settings.resolution, settings.color_mode, source, scanner.name
+            ),
+            format!(
+                "Fail the job if scanning takes longer than {} second(s)",
+                settings.timeout_secs
+            ),
+        ];
+
+        let post_processing = settings.post_processing.steps();
+        if !post_processing.is_empty() {
+            steps.push(format!("Post-process: {}", post_processing.join(", ")));
+        }
+        steps.push("Wait for a free generation slot".to_string());
+        steps.extend(ScanGenerator::describe_generation(
+            &document_type,
+            &settings,
+        ));
+        if document_type == DocumentType::Auto {
+            steps.push("Detect the document type from the scanned text".to_string());
+        }
+
+        // {counter} is resolved against the directory as it is now
+        let output_dir = settings
+            .output_dir
+            .clone()
+            .or_else(|| ScanGenerator::get_output_directory().ok())
+            .unwrap_or_default();
+        let filename = ScanGenerator::generate_filename(
+            &document_type,
+            &settings,
+            &scanner.name,
+            &output_dir,
+            &chrono::Utc::now(),
+        )
+        .map_err(ScannerError::InvalidSettings)?;
+        steps.push(match &settings.output_dir {
+            Some(dir) => format!("Save as {} in {}", filename, dir.display()),
+            None => format!("Save as {} in the output directory", filename),
+        });
+
+        if let Some(target) = &settings.upload {
+            steps.push(format!("Upload via {:?} to {}", target.method, target.url));
+        }
+
+        if let Some(action) = &then {
+            steps.push(match action {
+                PostAction::GenerateThumbnail => {
+                    "Generate a PNG thumbnail next to the scan".to_string()
+                }
+                PostAction::Export(destination) => {
+                    format!("Copy the scan to {}", destination.display())
+                }
+                PostAction::RunComposite(job_ids) => format!(
+                    "Combine with {} other job(s) into a PNG contact sheet",
+                    job_ids.len()
+                ),
+                PostAction::AppendTo(job_id) => {
+                    format!("Append the scanned pages to the PDF of job {}", job_id)
+                }
+            });
+        }
+
+        Ok(steps)
+    }
+
+    /// Reject settings the scanner hardware cannot honour
+    pub fn validate_settings(scanner: &Scanner, settings: &ScanSettings) -> Result<(), String> {
+        let capabilities = &scanner.capabilities;
+
+        if settings.resolution == 0 || settings.resolution > capabilities.max_resolution {
+            return Err(format!(
+                "Resolution {} DPI is not supported by {} (max {} DPI)",
+                settings.resolution, scanner.name, capabilities.max_resolution
+            ));
+        }
+
+        if !capabilities.supported_resolutions.is_empty()
+            && !capabilities
+                .supported_resolutions
+                .contains(&settings.resolution)
+        {
+            return Err(format!(
+                "Resolution {} DPI is not supported by {} (supported: {:?})",
+                settings.resolution, scanner.name, capabilities.supported_resolutions
+            ));
+        }
+
+        if !(1..=100).contains(&settings.quality) {
+            return Err(format!(
+                "Quality {} is out of range, it must be between 1 and 100",
+                settings.quality
+            ));
+        }
+
+        if !capabilities.supported_formats.is_empty()
+            && !capabilities
+                .supported_formats
+                .contains(&settings.output_format)
+        {
+            return Err(format!(
+                "Output format {:?} is not supported by {}",
+                settings.output_format, scanner.name
+            ));
+        }
+
+        if settings.duplex && !capabilities.has_duplex {
+            return Err(format!(
+                "Scanner {} does not support duplex scanning",
+                scanner.name
+            ));
+        }
+
+        if !capabilities.color_modes.contains(&settings.color_mode) {
+            return Err(format!(
+                "Color mode {:?} is not supported by {}",
+                settings.color_mode, scanner.name
+            ));
+        }
+
+        settings.paper_size.validate()?;
+        if let Some(area) = &settings.scan_area {
+            area.validate(&settings.paper_size)?;
+        }
+
+        match settings.paper_size {
+            PaperSize::Custom { width, height } => {
+                // Custom sizes must fit within the largest paper the scanner takes
+                let fits = capabilities.paper_sizes.iter().any(|size| {
+                    let (max_width, max_height) = size.dimensions_mm();
+                    width as f32 <= max_width && height as f32 <= max_height
+                });
+                if !fits {
+                    return Err(format!(
+                        "Custom paper size {}x{} mm is larger than any paper {} supports",
+                        width, height, scanner.name
+                    ));
+                }
+            }
+            _ => {
+                if !capabilities.paper_sizes.contains(&settings.paper_size) {
+                    return Err(format!(
+                        "Paper size {:?} is not supported by {}",
+                        settings.paper_size, scanner.name
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_input_source(scanner: &Scanner, settings: &ScanSettings) -> Result<(), String> {
+        if let Some(sheets) = settings.adf_sheet_count {
+            if settings.input_source != InputSource::Adf {
+                return Err(
+                    "An ADF sheet count requires the document feeder input source".to_string(),
+                );
+            }
+            if sheets == 0 {
+                return Err("ADF sheet count must be at least 1".to_string());
+            }
+        }
+
+        // Flatbed scans accept whatever fits on the glass
+        if settings.input_source != InputSource::Adf {
+            return Ok(());
+        }
+
+        let capabilities = &scanner.capabilities;
+        if !capabilities.has_adf {
+            return Err(format!(
+                "Scanner {} has no automatic document feeder",
+                scanner.name
+            ));
+        }
+
+        let (width, height) = settings.paper_size.dimensions_mm();
+        if let Some(max) = capabilities.adf_max_size {
+            if width > max.width as f32 || height > max.height as f32 {
+                return Err(format!(
+                    "{:?} too large for this ADF (max {}x{} mm)",
+                    settings.paper_size, max.width, max.height
+                ));
+            }
+        }
+        if let Some(min) = capabilities.adf_min_size {
+            if width < min.width as f32 || height < min.height as f32 {
+                return Err(format!(
+                    "{:?} too small for this ADF (min {}x{} mm)",
+                    settings.paper_size, min.width, min.height
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_document_type(
+        scanner: &Scanner,
+        document_type: &DocumentType,
+        settings: &ScanSettings,
+    ) -> Result<(), String> {
+        if scanner.scanner_type == ScannerType::FilmScanner
+            && !matches!(document_type, DocumentType::Photo | DocumentType::Image)
+        {
+            return Err(format!(
+                "Film scanner {} only supports photo and image documents",
+                scanner.name
+            ));
+        }
+
+        if *document_type == DocumentType::Photo && settings.color_mode == ColorMode::BlackAndWhite
+        {
+            return Err("Photos cannot be scanned in black and white".to_string());
+        }
+
+        Ok(())
+    }
+
+    pub fn set_job_document_type(
+        &self,
+        job_id: &str,
+        document_type: DocumentType,
+    ) -> Result<(), ScannerError> {
+        let job = self.get_scan_job(job_id)?;
+        if !matches!(job.status, JobStatus::Pending) {
+            return Err(ScannerError::InvalidJobState(
+                "Document type can only be changed before scanning starts".to_string(),
+            ));
+        }
+
+        // The new type goes through the same checks as at creation, barcode payload included
+        let scanner = self.get_scanner(&job.scanner_id)?;
+        Self::validate_job_request(&scanner, &document_type, &job.scan_settings)
+            .map_err(ScannerError::InvalidSettings)?;
+
+        let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+        let job = jobs
+            .get_mut(job_id)
+            .ok_or_else(|| ScannerError::job_not_found(job_id))?;
+
+        // The job may have been started while validation ran without the lock
+        if !matches!(job.status, JobStatus::Pending) {
+            return Err(ScannerError::InvalidJobState(
+                "Document type can only be changed before scanning starts".to_string(),
+            ));
+        }
+        job.document_type = document_type;
+        job.touch();
+        drop(jobs);
+
+        self.persist();
+        Ok(())
+    }
+
+    /// Change where a pending job sits in its scanner's queue
+    pub fn set_job_priority(
+        &self,
+        job_id: &str,
+        priority: JobPriority,
+    ) -> Result<(), ScannerError> {
+        let scanner_id = {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            let job = jobs
+                .get_mut(job_id)
+                .ok_or_else(|| ScannerError::job_not_found(job_id))?;
+            if !matches!(job.status, JobStatus::Pending) {
+                return Err(ScannerError::InvalidJobState(
+                    "Priority can only be changed before scanning starts".to_string(),
+                ));
+            }
+            job.scan_settings.priority = priority;
+            job.touch();
+            job.scanner_id.clone()
+        };
+        println!("Job {} priority set to {:?}", job_id, priority);
+
+        self.persist();
+        self.notify_queue_changed(&scanner_id);
+        Ok(())
+    }
+
+    /// Replace the settings of a job that has not started scanning yet
+    pub fn update_job_settings(
+        &self,
+        job_id: &str,
+        scan_settings: ScanSettings,
+    ) -> Result<(), ScannerError> {
+        let job = self.get_scan_job(job_id)?;
+        if !matches!(job.status, JobStatus::Pending) {
+            return Err(ScannerError::InvalidJobState(
+                "Settings can only be changed before scanning starts".to_string(),
+            ));
+        }
+
+        let scanner = self.get_scanner(&job.scanner_id)?;
+        Self::validate_job_request(&scanner, &job.document_type, &scan_settings)
+            .map_err(ScannerError::InvalidSettings)?;
+        if matches!(job.then, Some(PostAction::AppendTo(_)))
+            && scan_settings.output_format != OutputFormat::Pdf
+        {
+            return Err(ScannerError::InvalidSettings(
+                "Pages appended to a PDF must be scanned as PDF".to_string(),
+            ));
+        }
+
+        {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            let job = jobs
+                .get_mut(job_id)
+                .ok_or_else(|| ScannerError::job_not_found(job_id))?;
+
+            // The job may have been started while validation ran without the lock
+            if !matches!(job.status, JobStatus::Pending) {
+                return Err(ScannerError::InvalidJobState(
+                    "Settings can only be changed before scanning starts".to_string(),
+                ));
+            }
+            job.scan_settings = scan_settings.with_adf_sheets().with_encryption_recorded();
+            job.touch();
+        }
+        println!("Job {} settings updated", job_id);
+
+        self.persist();
+        // The new settings may carry a different priority
+        self.notify_queue_changed(&scanner.id);
+        Ok(())
+    }
+
+    pub async fn run_test_scan(
+        &self,
+        scanner_id: String,
+        scan_settings: Option<ScanSettings>,
+    ) -> Result<String, ScannerError> {
+        let job_id = self
+            .create_scan_job(
+                scanner_id,
+                DocumentType::Image,
+                scan_settings.unwrap_or_default(),
+                None,
+            )
+            .await?;
+
+        // Flag the job so the scanning process renders the test pattern
+        {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            if let Some(job) = jobs.get_mut(&job_id) {
+                job.is_test_scan = true;
+            }
+        }
+        self.persist();
+
+        self.start_scan_job(&job_id).await?;
+        Ok(job_id)
+    }
+
+    /// Scan a quick low-resolution proof to the temp directory before committing
+    /// to a full scan. Returns the ID of the started preview job.
+    pub async fn run_preview_scan(
+        &self,
+        scanner_id: String,
+        document_type: DocumentType,
+        scan_settings: ScanSettings,
+    ) -> Result<String, ScannerError> {
+        let job_id = self
+            .create_scan_job(scanner_id, document_type, scan_settings, None)
+            .await?;
+
+        {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            if let Some(job) = jobs.get_mut(&job_id) {
+                job.is_preview = true;
+            }
+        }
+        self.persist();
+
+        self.start_scan_job(&job_id).await?;
+        Ok(job_id)
+    }
+
+    /// Start the full-quality scan of a completed preview, with the scanner, document
+    /// type and settings the preview was created with. Returns the new job's ID.
+    pub async fn scan_from_preview(&self, preview_job_id: &str) -> Result<String, ScannerError> {
+        let preview = self.get_scan_job(preview_job_id)?;
+        if !preview.is_preview || !matches!(preview.status, JobStatus::Completed) {
+            return Err(ScannerError::InvalidJobState(format!(
+                "Job {} is not a completed preview scan",
+                preview_job_id
+            )));
+        }
+
+        let job_id = self
+            .create_scan_job(
+                preview.scanner_id,
+                preview.document_type,
+                preview.scan_settings,
+                None,
+            )
+            .await?;
+        self.start_scan_job(&job_id).await?;
+        println!(
+            "Scanning preview {} at full quality as {}",
+            preview_job_id, job_id
+        );
+        Ok(job_id)
+    }
+
+    // Settings a preview scans with: low resolution and a single quick pass, with
+    // nothing that only matters for the archival file
+    fn preview_settings(settings: &ScanSettings) -> ScanSettings {
+        ScanSettings {
+            resolution: settings.resolution.min(Self::PREVIEW_RESOLUTION),
+            quality: settings.quality.min(Self::PREVIEW_QUALITY),
+            pdf_a_level: None,
+            pdf_password: None,
+            encryption_requested: false,
+            upload: None,
+            output_dir: None,
+            filename_template: None,
+            progress_steps: Some(Self::PREVIEW_PROGRESS_STEPS),
+            ..settings.clone()
+        }
+    }
+
+    /// Scan more pages on the scanner of a completed PDF job and, once they are
+    /// done, append them to that job's file. Returns the ID of the started job
+    /// doing the new scan.
+    pub async fn append_to_job(
+        &self,
+        existing_job_id: &str,
+        document_type: DocumentType,
+        scan_settings: ScanSettings,
+    ) -> Result<String, ScannerError> {
+        let existing = self.get_scan_job(existing_job_id)?;
+        Self::appendable_pdf(&existing).map_err(ScannerError::InvalidJobState)?;
+        if scan_settings.output_format != OutputFormat::Pdf {
+            return Err(ScannerError::InvalidSettings(
+                "Pages appended to a PDF must be scanned as PDF".to_string(),
+            ));
+        }
+        if scan_settings.pdf_password.is_some() {
+            return Err(ScannerError::InvalidSettings(
+                "Appended pages take on the PDF they join and cannot have their own password"
+                    .to_string(),
+            ));
+        }
+
+        let job_id = self
+            .create_scan_job(
+                existing.scanner_id,
+                document_type,
+                scan_settings,
+                Some(PostAction::AppendTo(existing_job_id.to_string())),
+            )
+            .await?;
+        self.start_scan_job(&job_id).await?;
+        Ok(job_id)
+    }
+
+    // File of a job that pages can be appended to: a completed PDF scan
+    fn appendable_pdf(job: &ScanJob) -> Result<PathBuf, String> {
+        match &job.scan_result {
+            Some(result) if result.encrypted => {
+                Err(format!("Job {} is a password protected PDF", job.id))
+            }
+            Some(result)
+                if matches!(job.status, JobStatus::Completed)
+                    && result.format == OutputFormat::Pdf =>
+            {
+                Ok(result.file_path.clone())
+            }
+            _ => Err(format!("Job {} is not a completed PDF scan", job.id)),
+        }
+    }
+
+    /// Create a fresh pending job with the same scanner, document type, settings and
+    /// post-action as a failed or cancelled one
+    pub async fn retry_scan_job(&self, job_id: &str) -> Result<String, ScannerError> {
+        let source = self.get_scan_job(job_id)?;
+        if !matches!(source.status, JobStatus::Failed(_) | JobStatus::Cancelled) {
+            return Err(ScannerError::InvalidJobState(
+                "Only failed or cancelled jobs can be retried".to_string(),
+            ));
+        }
+        // Its password is gone once the file was encrypted, and a retry must not
+        // quietly produce an unprotected copy
+        let was_encrypted = source
+            .scan_result
+            .iter()
+            .chain(&source.partial_result)
+            .any(|result| result.encrypted);
+        if was_encrypted && source.scan_settings.pdf_password.is_none() {
+            return Err(ScannerError::InvalidJobState(
+                "The PDF password of this job is no longer stored, create a new job to rescan it"
+                    .to_string(),
+            ));
+        }
+
+        let new_job_id = self
+            .create_scan_job(
+                source.scanner_id,
+                source.document_type,
+                source.scan_settings,
+                source.then,
+            )
+            .await?;
+
+        if source.is_test_scan || source.is_preview {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            if let Some(job) = jobs.get_mut(&new_job_id) {
+                job.is_test_scan = source.is_test_scan;
+                job.is_preview = source.is_preview;
+            }
+        }
+        self.persist();
+
+        println!("Retrying job {} as {}", job_id, new_job_id);
+        Ok(new_job_id)
+    }
+
+    /// Queue several pending jobs in the given order. Nothing is queued unless
+    /// every job exists and is still pending.
+    pub async fn start_batch(&self, job_ids: &[String]) -> Result<(), ScannerError> {
+        let mut scanner_ids: Vec<String> = Vec::new();
+        {
+            let jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            for job_id in job_ids {
+                let job = jobs
+                    .get(job_id)
+                    .ok_or_else(|| ScannerError::job_not_found(job_id))?;
+                if !matches!(job.status, JobStatus::Pending) {
+                    return Err(ScannerError::InvalidJobState(format!(
+                        "Job {} is not pending and cannot be started",
+                        job_id
+                    )));
+                }
+                if !scanner_ids.contains(&job.scanner_id) {
+                    scanner_ids.push(job.scanner_id.clone());
+                }
+            }
+        }
+
+        {
+            let mut queues = self.queues.lock().map_err(|e| e.to_string())?;
+            let jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            for job_id in job_ids {
+                let Some(job) = jobs.get(job_id) else {
+                    continue;
+                };
+                let queue = queues.entry(job.scanner_id.clone()).or_default();
+                if !queue.iter().any(|queued_id| queued_id == job_id) {
+                    queue.push_back(job_id.clone());
+                }
+            }
+        }
+
+        for scanner_id in scanner_ids {
+            self.dispatch_queue(&scanner_id)?;
+        }
+        Ok(())
+    }
+
+    /// Queue a pending job on its scanner. It starts right away if the scanner has a
+    /// free slot, otherwise it stays pending until a running job finishes.
+    pub async fn start_scan_job(&self, job_id: &str) -> Result<(), ScannerError> {
+        let scanner_id = {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            let job = jobs
+                .get_mut(job_id)
+                .ok_or_else(|| ScannerError::job_not_found(job_id))?;
+            if !matches!(job.status, JobStatus::Pending) {
+                return Err(ScannerError::InvalidJobState(
+                    "Only pending jobs can be started".to_string(),
+                ));
+            }
+            // Starting a scheduled job by hand replaces its schedule
+            job.scheduled_at = None;
+            // A job restored or imported without its PDF password fails rather
+            // than scanning an unprotected file
+            if job.scan_settings.password_lost() {
+                job.fail(Self::PDF_PASSWORD_LOST.to_string());
+                None
+            } else {
+                Some(job.scanner_id.clone())
+            }
+        };
+        self.abort_schedule(job_id)?;
+        let Some(scanner_id) = scanner_id else {
+            self.persist();
+            if let Ok(job) = self.get_scan_job(job_id) {
+                self.emit_event("scan-failed", job);
+            }
+            return Err(ScannerError::InvalidSettings(
+                Self::PDF_PASSWORD_LOST.to_string(),
+            ));
+        };
+
+        {
+            let mut queues = self.queues.lock().map_err(|e| e.to_string())?;
+            let queue = queues.entry(scanner_id.clone()).or_default();
+            if !queue.iter().any(|queued_id| queued_id == job_id) {
+                queue.push_back(job_id.to_string());
+            }
+        }
+
+        self.dispatch_queue(&scanner_id)
+    }
+
+    /// Start a pending job at the given time instead of right away. The job stays
+    /// Pending, showing its start time, until then.
+    pub fn schedule_scan_job(
+        &self,
+        job_id: &str,
+        start_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), ScannerError> {
+        if start_at <= chrono::Utc::now() {
+            return Err(ScannerError::InvalidSettings(
+                "Scheduled start time must be in the future".to_string(),
+            ));
+        }
+
+        {
+            let queues = self.queues.lock().map_err(|e| e.to_string())?;
+            if queues
+                .values()
+                .flatten()
+                .any(|queued_id| queued_id == job_id)
+            {
+                return Err(ScannerError::InvalidJobState(format!(
+                    "Job {} has already been started and is waiting for its scanner",
+                    job_id
+                )));
+            }
+        }
+
+        {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            let job = jobs
+                .get_mut(job_id)
+                .ok_or_else(|| ScannerError::job_not_found(job_id))?;
+            if !matches!(job.status, JobStatus::Pending) {
+                return Err(ScannerError::InvalidJobState(
+                    "Only pending jobs can be scheduled".to_string(),
+                ));
+            }
+            job.scheduled_at = Some(start_at);
+            job.touch();
+        }
+        println!("Job {} scheduled to start at {}", job_id, start_at);
+
+        self.arm_schedule(job_id, start_at)?;
+        self.persist();
+        Ok(())
+    }
+
+    /// Drop a job's scheduled start, leaving it pending until started by hand
+    pub fn cancel_schedule(&self, job_id: &str) -> Result<(), ScannerError> {
+        {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            let job = jobs
+                .get_mut(job_id)
+                .ok_or_else(|| ScannerError::job_not_found(job_id))?;
+            if job.scheduled_at.take().is_none() {
+                return Err(ScannerError::InvalidJobState(format!(
+                    "Job {} is not scheduled",
+                    job_id
+                )));
+            }
+            job.touch();
+        }
+        self.abort_schedule(job_id)?;
+        println!("Cancelled the scheduled start of job {}", job_id);
+
+        self.persist();
+        Ok(())
+    }
+
+    /// Restart the timers of scheduled jobs restored from saved state. Jobs whose
+    /// time passed while the app was closed start right away.
+    pub fn resume_schedules(&self) -> Result<(), ScannerError> {
+        let scheduled: Vec<(String, chrono::DateTime<chrono::Utc>)> = {
+            let jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            jobs.values()
+                .filter(|job| matches!(job.status, JobStatus::Pending))
+                .filter_map(|job| Some((job.id.clone(), job.scheduled_at?)))
+                .collect()
+        };
+
+        for (job_id, start_at) in &scheduled {
+            self.arm_schedule(job_id, *start_at)?;
+        }
+        if !scheduled.is_empty() {
+            println!("Resumed {} scheduled jobs", scheduled.len());
+        }
+        Ok(())
+    }
+
+    fn arm_schedule(
+        &self,
+        job_id: &str,
+        start_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), ScannerError> {
+        let delay = (start_at - chrono::Utc::now())
+            .to_std()
+            .unwrap_or(Duration::ZERO);
+        let service = self.clone();
+        let id = job_id.to_string();
+        // Tauri's runtime handle also works from app setup, outside any tokio context
+        let handle = tauri::async_runtime::spawn(async move {
+            sleep(delay).await;
+            service.run_scheduled_job(&id).await;
+        });
+
+        let mut schedules = self.schedules.lock().map_err(|e| e.to_string())?;
+        if let Some(previous) = schedules.insert(job_id.to_string(), handle) {
+            previous.abort();
+        }
+        Ok(())
+    }
+
+    fn abort_schedule(&self, job_id: &str) -> Result<(), ScannerError> {
+        let mut schedules = self.schedules.lock().map_err(|e| e.to_string())?;
+        if let Some(handle) = schedules.remove(job_id) {
+            handle.abort();
+        }
+        Ok(())
+    }
+
+    async fn run_scheduled_job(&self, job_id: &str) {
+        // Dropping rather than aborting the handle, since this is the task it runs
+        if let Ok(mut schedules) = self.schedules.lock() {
+            schedules.remove(job_id);
+        }
+
+        // The job may have been cancelled or deleted while it waited
+        let still_scheduled = self.get_scan_job(job_id).is_ok_and(|job| {
+            matches!(job.status, JobStatus::Pending) && job.scheduled_at.is_some()
+        });
+        if !still_scheduled {
+            return;
+        }
+
+        println!("Starting scheduled job {}", job_id);
+        if let Err(e) = self.start_scan_job(job_id).await {
+            println!("Failed to start scheduled job {}: {}", job_id, e);
+        }
+        self.persist();
+    }
+
+    pub fn set_max_concurrent(
+        &self,
+        scanner_id: &str,
+        max_concurrent: usize,
+    ) -> Result<(), ScannerError> {
+        if max_concurrent == 0 {
+            return Err(ScannerError::InvalidSettings(
+                "Maximum concurrent scans must be at least 1".to_string(),
+            ));
+        }
+        self.get_scanner(scanner_id)?;
+
+        {
+            let mut limits = self.max_concurrent.lock().map_err(|e| e.to_string())?;
+            limits.insert(scanner_id.to_string(), max_concurrent);
+        }
+        println!(
+            "Maximum concurrent scans for scanner {} set to {}",
+            scanner_id, max_concurrent
+        );
+
+        // A higher limit may let queued jobs start now
+        self.dispatch_queue(scanner_id)
+    }
+
+    /// Start queued jobs for a scanner until its concurrency limit is reached
+    fn dispatch_queue(&self, scanner_id: &str) -> Result<(), ScannerError> {
+        // Queued jobs stay pending for the next run
+        if self.shutting_down.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let limit = {
+            let limits = self.max_concurrent.lock().map_err(|e| e.to_string())?;
+            limits
+                .get(scanner_id)
+                .copied()
+                .unwrap_or(Self::DEFAULT_MAX_CONCURRENT)
+        };
+
+        loop {
+            let job_clone = {
+                let mut queues = self.queues.lock().map_err(|e| e.to_string())?;
+                let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+
+                let running = jobs
+                    .values()
+                    .filter(|job| {
+                        job.scanner_id == scanner_id
+                            && matches!(
+                                job.status,
+                                JobStatus::Scanning | JobStatus::Paused | JobStatus::Processing
+                            )
+                    })
+                    .count();
+                if running >= limit {
+                    break;
+                }
+
+                let Some(queue) = queues.get_mut(scanner_id) else {
+                    break;
+                };
+                // Jobs cancelled or evicted while queued are dropped
+                queue.retain(|queued_id| {
+                    jobs.get(queued_id)
+                        .is_some_and(|job| matches!(job.status, JobStatus::Pending))
+                });
+                let Some(position) =
+                    (0..queue.len()).min_by_key(|&index| Self::queue_order(&jobs[&queue[index]]))
+                else {
+                    break;
+                };
+                let Some(job) = queue
+                    .remove(position)
+                    .and_then(|queued_id| jobs.get_mut(&queued_id))
+                else {
+                    break;
+                };
+                job.start_scanning();
+
+                // Clone job data for async processing
+                job.clone()
+            };
+            self.persist();
+            self.notify_queue_changed(scanner_id);
+            self.spawn_scan(job_clone);
+        }
+
+        Ok(())
+    }
+
+    // Sort key for pending jobs: highest priority first, then oldest first
+    fn queue_order(
+        job: &ScanJob,
+    ) -> (
+        std::cmp::Reverse<JobPriority>,
+        chrono::DateTime<chrono::Utc>,
+    ) {
+        (
+            std::cmp::Reverse(job.scan_settings.priority),
+            job.created_at,
+        )
+    }
+
+    fn spawn_scan(&self, job_clone: ScanJob) {
+        let jobs_arc = Arc::clone(&self.jobs);
+        let scanners_arc = Arc::clone(&self.scanners);
+        let generation_limiter = Arc::clone(&self.generation_limiter);
+        let simulation = Arc::clone(&self.simulation);
+        let usage = Arc::clone(&self.usage);
+        let app_handle = Arc::clone(&self.app_handle);
+        let service = self.clone();
+        self.running_scans.fetch_add(1, Ordering::SeqCst);
+
+        // Spawn async task to simulate scanning process
+        tokio::spawn(async move {
+            let scanner_id = job_clone.scanner_id.clone();
+            Self::simulate_scanning_process(
+                job_clone,
+                jobs_arc,
+                scanners_arc,
+                generation_limiter,
+                simulation,
+                usage,
+                app_handle,
+            )
+            .await;
+            service.running_scans.fetch_sub(1, Ordering::SeqCst);
+
+            // However the scan ended, its slot is free for the next queued job
+            if let Err(e) = service.dispatch_queue(&scanner_id) {
+                println!("Failed to start next queued job: {}", e);
+            }
+        });
+    }
+
+    fn progress_event(job: &ScanJob) -> ScanProgressEvent {
+        ScanProgressEvent {
+            job_id: job.id.clone(),
+            progress: job.progress,
+            eta_secs: job.eta_secs,
+            status: job.status.clone(),
+        }
+    }
+
+    // A random text document standing in for whatever the user put on an Auto job's
+    // scanner. Photos are left out since they carry no text to classify.
+    fn simulate_placed_document(rng: &mut StdRng) -> DocumentType {
+        const PLACED_DOCUMENTS: [DocumentType; 6] = [
+            DocumentType::Text,
+            DocumentType::Invoice,
+            DocumentType::Contract,
+            DocumentType::Receipt,
+            DocumentType::BusinessCard,
+            DocumentType::Mixed,
+        ];
+        PLACED_DOCUMENTS[rng.gen_range(0..PLACED_DOCUMENTS.len())]
+    }
+
+    // Classify an Auto scan by its text. Without readable text, e.g. a raster scan
+    // with no tesseract installed, the simulator reports the document it placed.
+    async fn detect_document_type(result: &ScanResult, placed: DocumentType) -> DocumentType {
+        match extract_scan_text(result).await {
+            Ok(text) => classify_document_text(&text).unwrap_or(placed),
+            Err(e) => {
+                println!(
+                    "Document type detection fell back to the simulated type: {}",
+                    e
+                );
+                placed
+            }
+        }
+    }
+
+    async fn simulate_scanning_process(
+        job: ScanJob,
+        jobs: Arc<Mutex<HashMap<String, ScanJob>>>,
+        scanners: Arc<Mutex<HashMap<String, Scanner>>>,
+        generation_limiter: Arc<GenerationLimiter>,
+        simulation: Arc<Mutex<Simulation>>,
+        usage: Arc<Mutex<HashMap<String, ScannerMetrics>>>,
+        app_handle: Arc<Mutex<Option<AppHandle>>>,
+    ) {
+        // The stored job keeps its full settings for the scan that follows a preview
+        let mut job = job;
+        if job.is_preview {
+            job.scan_settings = Self::preview_settings(&job.scan_settings);
+        }
+
+        // Set scanner to busy. A jammed or offline scanner keeps its status for the
+        // scan loop to pause or fail on, as a second job may start while it is down.
         if let Ok(mut scanners_lock) = scanners.lock() {
             if let Some(scanner) = scanners_lock.get_mut(&job.scanner_id) {
+                if !matches!(
+                    scanner.status,
+                    ScannerStatus::Offline | ScannerStatus::Error(_)
+                ) {
+                    scanner.status = ScannerStatus::Busy;
+                }
+            }
+        }
+
+        let steps = job.scan_settings.scan_progress_steps();
+
+        // Generate random values at the start so the lock is never held across an await
+        let (scan_duration_ms, should_fail, feeder_fault) = {
+            let mut simulation = Self::lock_simulation(&simulation);
+            let scan_failure_rate = simulation.params.scan_failure_rate;
+            let scan_duration_ms = if job.is_preview {
+                simulation.rng.gen_range(500..1500)
+            } else {
+                simulation.rng.gen_range(3000..8000)
+            };
+            let should_fail = simulation.rng.gen::<f32>() < scan_failure_rate;
+            let feeder_fault = if job.scan_settings.input_source == InputSource::Adf {
+                Self::simulate_feeder_fault(&mut simulation, job.scan_settings.page_count, steps)
+            } else {
+                None
+            };
+            (scan_duration_ms, should_fail, feeder_fault)
+        };
+
+        let scan_duration = Duration::from_millis(scan_duration_ms);
+        let step_duration = scan_duration / steps;
+        let post_processing_steps = job.scan_settings.post_processing.steps();
+        let scan_share = if post_processing_steps.is_empty() {
+            1.0
+        } else {
+            Self::SCAN_PROGRESS_SHARE
+        };
+
+        // Simulate scanning progress, giving up once the job's timeout elapses, even
+        // partway through a step or while paused. Time spent paused does not count
+        // towards the ETA. A hardware error rescans from the start while the job has
+        // retries left, each attempt with the full timeout.
+        let scan_timeout = Duration::from_secs(job.scan_settings.timeout_secs);
+        let max_retries = u32::from(job.scan_settings.max_retries);
+        let mut should_fail = should_fail;
+        let mut retries = 0;
+        let scanning = loop {
+            let scan_started = std::time::Instant::now();
+            let mut paused_for = Duration::ZERO;
+            let outcome = tokio::time::timeout(scan_timeout, async {
+                for step in 1..=steps {
+                    sleep(step_duration).await;
+
+                    if job.is_cancel_requested() {
+                        return ScanOutcome::Cancelled;
+                    }
+
+                    if Self::scanner_jammed(&scanners, &job.scanner_id) {
+                        println!("Paper jam on the scanner of job {}, pausing it", job.id);
+                        let progress_event = match jobs.lock() {
+                            Ok(mut jobs_lock) => jobs_lock.get_mut(&job.id).map(|stored_job| {
+                                stored_job.pause_with_reason(Self::PAPER_JAM.to_string());
+                                Self::progress_event(stored_job)
+                            }),
+                            Err(_) => None,
+                        };
+                        if let Some(progress_event) = progress_event {
+                            Self::emit_with(&app_handle, "scan-progress", progress_event);
+                        }
+                    }
+
+                    if job.is_pause_requested() {
+                        println!("Scan job {} paused at step {}", job.id, step);
+                        let pause_started = std::time::Instant::now();
+                        while job.is_pause_requested() && !job.is_cancel_requested() {
+                            sleep(Self::PAUSE_POLL_INTERVAL).await;
+                        }
+                        paused_for += pause_started.elapsed();
+
+                        if job.is_cancel_requested() {
+                            return ScanOutcome::Cancelled;
+                        }
+                        println!("Scan job {} resumed", job.id);
+
+                        // Clearing a jam leaves the scanner Available while this job still uses it
+                        if let Ok(mut scanners_lock) = scanners.lock() {
+                            if let Some(scanner) = scanners_lock.get_mut(&job.scanner_id) {
+                                if scanner.status == ScannerStatus::Available {
+                                    scanner.status = ScannerStatus::Busy;
+                                }
+                            }
+                        }
+                    }
+
+                    if Self::scanner_disconnected(&scanners, &job.scanner_id) {
+                        println!("Scanner for job {} disconnected during scan", job.id);
+                        return ScanOutcome::Failed("Scanner disconnected during scan".to_string());
+                    }
+
+                    if let Some((fault_step, error)) = &feeder_fault {
+                        if step >= *fault_step {
+                            println!("Document feeder fault for job {}: {}", job.id, error);
+                            return ScanOutcome::Failed(error.clone());
+                        }
+                    }
+
+                    let scanning_time = scan_started.elapsed().saturating_sub(paused_for);
+                    let scanned = step as f32 / steps as f32;
+
+                    // Update job progress
+                    let progress_event = match jobs.lock() {
+                        Ok(mut jobs_lock) => jobs_lock.get_mut(&job.id).map(|stored_job| {
+                            stored_job.update_progress(scanned * scan_share);
+                            // Remaining time assumes the rest scans at the pace so far, plus
+                            // the fixed post-processing time
+                            stored_job.update_eta(
+                                scanning_time.mul_f32((1.0 - scanned) / scanned)
+                                    + Self::POST_PROCESSING_STEP_DURATION
+                                        * post_processing_steps.len() as u32,
+                            );
+                            Self::progress_event(stored_job)
+                        }),
+                        Err(_) => None,
+                    };
+                    if let Some(progress_event) = progress_event {
+                        Self::emit_with(&app_handle, "scan-progress", progress_event);
+                    }
+
+                    // Small chance of random failure
+                    if should_fail && step > steps / 2 {
+                        println!("Simulating scanner failure for job: {}", job.id);
+                        return ScanOutcome::Failed(Self::HARDWARE_ERROR.to_string());
+                    }
+                }
+                ScanOutcome::Scanned
+            })
+            .await
+            .unwrap_or_else(|_| {
+                println!("Scan job {} timed out after {:?}", job.id, scan_timeout);
+                ScanOutcome::Failed("Scan timed out".to_string())
+            });
+
+            let hardware_error =
+                matches!(&outcome, ScanOutcome::Failed(error) if error == Self::HARDWARE_ERROR);
+            if !hardware_error || retries >= max_retries {
+                break outcome;
+            }
+            retries += 1;
+            println!(
+                "Retrying scan job {} after a hardware error ({} of {})",
+                job.id, retries, max_retries
+            );
+            sleep(Self::SCAN_RETRY_DELAY).await;
+            if job.is_cancel_requested() {
+                break ScanOutcome::Cancelled;
+            }
+
+            let progress_event = match jobs.lock() {
+                Ok(mut jobs_lock) => jobs_lock.get_mut(&job.id).map(|stored_job| {
+                    stored_job.retry_scanning();
+                    Self::progress_event(stored_job)
+                }),
+                Err(_) => None,
+            };
+            if let Some(progress_event) = progress_event {
+                Self::emit_with(&app_handle, "scan-progress", progress_event);
+            }
+
+            let mut simulation = Self::lock_simulation(&simulation);
+            let scan_failure_rate = simulation.params.scan_failure_rate;
+            should_fail = simulation.rng.gen::<f32>() < scan_failure_rate;
+        };
+
+        match scanning {
+            ScanOutcome::Scanned => {}
+            ScanOutcome::Cancelled => {
+                println!("Scan job {} cancelled, stopping scan", job.id);
+                return;
+            }
+            ScanOutcome::Failed(error) => {
+                // A cancel that raced the failure keeps the job cancelled
+                if let Ok(mut jobs_lock) = jobs.lock() {
+                    if let Some(stored_job) = jobs_lock.get_mut(&job.id) {
+                        if stored_job.is_cancel_requested() {
+                            return;
+                        }
+                        stored_job.fail(error);
+                    }
+                }
+                Self::finish_job(
+                    &app_handle,
+                    &scanners,
+                    &jobs,
+                    &usage,
+                    "scan-failed",
+                    &job.id,
+                );
+
+                Self::release_scanner(&scanners, &jobs, &job.scanner_id);
+                return;
+            }
+        }
+
+        let progress_event = match jobs.lock() {
+            Ok(mut jobs_lock) => match jobs_lock.get_mut(&job.id) {
+                Some(stored_job) if stored_job.is_cancel_requested() => return,
+                Some(stored_job) => {
+                    stored_job.start_processing();
+                    if !post_processing_steps.is_empty() {
+                        stored_job.update_eta(
+                            Self::POST_PROCESSING_STEP_DURATION
+                                * post_processing_steps.len() as u32,
+                        );
+                    }
+                    Some(Self::progress_event(stored_job))
+                }
+                None => None,
+            },
+            Err(_) => None,
+        };
+        if let Some(progress_event) = progress_event {
+            Self::emit_with(&app_handle, "scan-progress", progress_event);
+        }
+
+        // Simulated adjustments fill the rest of the progress bar, one step each
+        for (index, step) in post_processing_steps.iter().enumerate() {
+            println!("{} for job: {}", step, job.id);
+            sleep(Self::POST_PROCESSING_STEP_DURATION).await;
+            if job.is_cancel_requested() {
+                println!("Scan job {} cancelled during post-processing", job.id);
+                return;
+            }
+
+            let done = (index + 1) as f32 / post_processing_steps.len() as f32;
+            let remaining = post_processing_steps.len() - index - 1;
+            let progress_event = match jobs.lock() {
+                Ok(mut jobs_lock) => jobs_lock.get_mut(&job.id).map(|stored_job| {
+                    stored_job.update_progress(scan_share + (1.0 - scan_share) * done);
+                    stored_job.update_eta(Self::POST_PROCESSING_STEP_DURATION * remaining as u32);
+                    Self::progress_event(stored_job)
+                }),
+                Err(_) => None,
+            };
+            if let Some(progress_event) = progress_event {
+                Self::emit_with(&app_handle, "scan-progress", progress_event);
+            }
+        }
+
+        // Generate scan file
+        println!("Generating scan file for job: {}", job.id);
+        let output_dir = if job.is_preview {
+            ScanGenerator::get_preview_directory()
+        } else {
+            ScanGenerator::resolve_output_directory(&job.scan_settings)
+        };
+        let output_dir = match output_dir {
+            Ok(dir) => dir,
+            Err(e) => {
+                println!("Failed to get output directory: {}", e);
+                Self::fail_before_generation(
+                    &app_handle,
+                    &scanners,
+                    &jobs,
+                    &usage,
+                    &job,
+                    format!("Failed to create output directory: {}", e),
+                );
+                return;
+            }
+        };
+
+        // The simulator decides what is on the glass for jobs that detect their type
+        let scanned_type = if job.document_type == DocumentType::Auto {
+            Self::simulate_placed_document(&mut Self::lock_simulation(&simulation).rng)
+        } else {
+            job.document_type
+        };
+
+        let filename = if job.is_test_scan {
+            Ok(ScanGenerator::generate_test_pattern_filename(
+                &job.scan_settings,
+                &chrono::Utc::now(),
+            ))
+        } else {
+            let scanner_name = scanners
+                .lock()
+                .ok()
+                .and_then(|scanners| scanners.get(&job.scanner_id).map(|s| s.name.clone()))
+                .unwrap_or_else(|| job.scanner_id.clone());
+            ScanGenerator::generate_filename(
+                &job.document_type,
+                &job.scan_settings,
+                &scanner_name,
+                &output_dir,
+                &chrono::Utc::now(),
+            )
+        };
+        let output_path = match filename {
+            Ok(filename) => output_dir.join(filename),
+            Err(e) => {
+                println!("Failed to name scan file: {}", e);
+                Self::fail_before_generation(
+                    &app_handle,
+                    &scanners,
+                    &jobs,
+                    &usage,
+                    &job,
+                    format!("Failed to name scan file: {}", e),
+                );
+                return;
+            }
+        };
+
+        // Wait for a generation slot so batches don't thrash the disk
+        let permit = match generation_limiter.acquire().await {
+            Ok(permit) => permit,
+            Err(e) => {
+                println!("Generation limiter closed: {}", e);
+                Self::fail_before_generation(
+                    &app_handle,
+                    &scanners,
+                    &jobs,
+                    &usage,
+                    &job,
+                    format!("Generation limiter closed: {}", e),
+                );
+                return;
+            }
+        };
+
+        // The job may have been cancelled while waiting for a slot
+        if job.is_cancel_requested() {
+            println!("Scan job {} cancelled before generation", job.id);
+            return;
+        }
+
+        // Generation gets its own full timeout, so a stuck write can't hold the
+        // scanner forever either
+        let generation = tokio::time::timeout(scan_timeout, async {
+            if job.is_test_scan {
+                ScanGenerator::generate_test_pattern(&job.scan_settings, &output_path)
+                    .await
+                    .map_err(GenerationError::from)
+            } else {
+                ScanGenerator::generate_scan_file(
+                    &job.id,
+                    &scanned_type,
+                    &job.scan_settings,
+                    &output_path,
+                )
+                .await
+            }
+        })
+        .await;
+
+        drop(permit);
+
+        let generation = match generation {
+            Ok(generation) => generation,
+            Err(_) => {
                 println!(
-                    "Setting scanner {} back to available after completion",
-                    scanner.name
+                    "Generating the scan file for job {} timed out after {:?}",
+                    job.id, scan_timeout
                 );
-                scanner.status = ScannerStatus::Available;
+                let _ = std::fs::remove_file(&output_path);
+                if job.is_cancel_requested() {
+                    return;
+                }
+                Self::fail_before_generation(
+                    &app_handle,
+                    &scanners,
+                    &jobs,
+                    &usage,
+                    &job,
+                    "Scan timed out".to_string(),
+                );
+                return;
+            }
+        };
+
+        // A job cancelled while its file was being generated leaves nothing behind
+        if job.is_cancel_requested() {
+            println!("Scan job {} cancelled, discarding generated file", job.id);
+            let result = match &generation {
+                Ok(result) => Some(result),
+                Err(e) => e.partial_result.as_ref(),
+            };
+            if let Some(result) = result {
+                let _ = std::fs::remove_file(&result.file_path);
+            }
+            return;
+        }
+
+        let mut scan_result = match generation {
+            Ok(result) => {
+                println!("Scan file generated: {:?}", output_path);
+                ScanResult {
+                    is_preview: job.is_preview,
+                    ..result
+                }
+            }
+            Err(e) => {
+                println!("Failed to generate scan file: {}", e);
+                if let Ok(mut jobs_lock) = jobs.lock() {
+                    if let Some(stored_job) = jobs_lock.get_mut(&job.id) {
+                        stored_job.fail(format!("Failed to generate file: {}", e));
+                        stored_job.partial_result = e.partial_result;
+                    }
+                }
+                Self::finish_job(
+                    &app_handle,
+                    &scanners,
+                    &jobs,
+                    &usage,
+                    "scan-failed",
+                    &job.id,
+                );
+                Self::release_scanner(&scanners, &jobs, &job.scanner_id);
+                return;
+            }
+        };
+
+        // The password was only needed to encrypt the file
+        if scan_result.encrypted {
+            if let Ok(mut jobs_lock) = jobs.lock() {
+                if let Some(stored_job) = jobs_lock.get_mut(&job.id) {
+                    stored_job.scan_settings.pdf_password = None;
+                }
+            }
+        }
+
+        if job.document_type == DocumentType::Auto {
+            let detected = Self::detect_document_type(&scan_result, scanned_type).await;
+            println!("Detected {:?} for job {}", detected, job.id);
+            if let Ok(mut jobs_lock) = jobs.lock() {
+                if let Some(stored_job) = jobs_lock.get_mut(&job.id) {
+                    stored_job.detected_type = Some(detected);
+                }
+            }
+        }
+
+        if let Some(target) = &job.scan_settings.upload {
+            println!("Uploading scan file for job {} to {}", job.id, target.url);
+            match upload_scan_file(&output_path, target).await {
+                Ok(upload) => scan_result.upload = Some(upload),
+                Err(e) => {
+                    // The local file is kept so the scan can be retrieved or re-sent
+                    println!("Upload failed for job {}: {}", job.id, e);
+                    if let Ok(mut jobs_lock) = jobs.lock() {
+                        if let Some(stored_job) = jobs_lock.get_mut(&job.id) {
+                            if stored_job.is_cancel_requested() {
+                                return;
+                            }
+                            stored_job.fail(format!("Upload failed: {}", e));
+                            stored_job.scan_result = Some(scan_result);
+                        }
+                    }
+                    Self::finish_job(
+                        &app_handle,
+                        &scanners,
+                        &jobs,
+                        &usage,
+                        "scan-failed",
+                        &job.id,
+                    );
+
+                    Self::release_scanner(&scanners, &jobs, &job.scanner_id);
+                    return;
+                }
+            }
+        }
+
+        // Complete the job, unless it was cancelled while uploading. Checking under
+        // the jobs lock means a concurrent cancel cannot be overwritten.
+        println!("Completing scan job: {}", job.id);
+        if let Ok(mut jobs_lock) = jobs.lock() {
+            if let Some(stored_job) = jobs_lock.get_mut(&job.id) {
+                if stored_job.is_cancel_requested() {
+                    println!("Scan job {} cancelled, discarding generated file", job.id);
+                    let _ = std::fs::remove_file(&scan_result.file_path);
+                    return;
+                }
+                stored_job.complete();
+                stored_job.scan_result = Some(scan_result);
+            }
+        }
+
+        Self::release_scanner(&scanners, &jobs, &job.scanner_id);
+
+        // Run any chained post-action; its failure never fails the scan itself
+        if let Some(action) = job.then.clone() {
+            let outcome = Self::run_post_action(action, &job.id, &jobs);
+            if let Ok(mut jobs_lock) = jobs.lock() {
+                if let Some(stored_job) = jobs_lock.get_mut(&job.id) {
+                    // A generated thumbnail doubles as the job's preview
+                    if let (PostAction::GenerateThumbnail, Some(path), Some(result)) = (
+                        &outcome.action,
+                        &outcome.output_path,
+                        stored_job.scan_result.as_mut(),
+                    ) {
+                        result.preview_path = Some(path.clone());
+                    }
+                    stored_job.post_action_outcome = Some(outcome);
+                    stored_job.touch();
+                }
+            }
+        }
+
+        // Sent last so listeners see the post-action outcome too
+        Self::finish_job(
+            &app_handle,
+            &scanners,
+            &jobs,
+            &usage,
+            "scan-completed",
+            &job.id,
+        );
+    }
+
+    // Decide whether the document feeder fails the job, returning the scan step it
+    // fails at and the error. An empty feeder fails before the first sheet is fed; a
+    // misfeed pulls a sheet together with the one after it.
+    fn simulate_feeder_fault(
+        simulation: &mut Simulation,
+        sheets: u32,
+        steps: u32,
+    ) -> Option<(u32, String)> {
+        let empty = simulation.rng.gen::<f32>() < simulation.params.adf_empty_rate;
+        let misfeed = simulation.rng.gen::<f32>() < simulation.params.adf_misfeed_rate;
+        if empty {
+            Some((1, Self::ADF_EMPTY.to_string()))
+        } else if misfeed && sheets > 1 {
+            let sheet = simulation.rng.gen_range(1..sheets);
+            Some((
+                (sheet - 1) * steps / sheets + 1,
+                format!(
+                    "ADF misfeed: sheets {} and {} were fed together",
+                    sheet,
+                    sheet + 1
+                ),
+            ))
+        } else {
+            None
+        }
+    }
+
+    // Whether a running job's scanner was removed or went offline
+    fn scanner_disconnected(
+        scanners: &Arc<Mutex<HashMap<String, Scanner>>>,
+        scanner_id: &str,
+    ) -> bool {
+        match scanners.lock() {
+            Ok(scanners_lock) => scanners_lock
+                .get(scanner_id)
+                .is_none_or(|scanner| matches!(scanner.status, ScannerStatus::Offline)),
+            Err(_) => false,
+        }
+    }
+
+    fn scanner_jammed(scanners: &Arc<Mutex<HashMap<String, Scanner>>>, scanner_id: &str) -> bool {
+        match scanners.lock() {
+            Ok(scanners_lock) => scanners_lock
+                .get(scanner_id)
+                .is_some_and(|scanner| Self::is_paper_jam(&scanner.status)),
+            Err(_) => false,
+        }
+    }
+
+    fn is_paper_jam(status: &ScannerStatus) -> bool {
+        matches!(status, ScannerStatus::Error(reason) if reason == Self::PAPER_JAM)
+    }
+
+    // Fail a job that stopped before its scan file was generated and free its scanner
+    fn fail_before_generation(
+        app_handle: &Arc<Mutex<Option<AppHandle>>>,
+        scanners: &Arc<Mutex<HashMap<String, Scanner>>>,
+        jobs: &Arc<Mutex<HashMap<String, ScanJob>>>,
+        usage: &Arc<Mutex<HashMap<String, ScannerMetrics>>>,
+        job: &ScanJob,
+        error: String,
+    ) {
+        if let Ok(mut jobs_lock) = jobs.lock() {
+            if let Some(stored_job) = jobs_lock.get_mut(&job.id) {
+                stored_job.fail(error);
+            }
+        }
+        Self::finish_job(app_handle, scanners, jobs, usage, "scan-failed", &job.id);
+        Self::release_scanner(scanners, jobs, &job.scanner_id);
+    }
+
+    // Set the scanner of a finished job back to available
+    fn release_scanner(
+        scanners: &Arc<Mutex<HashMap<String, Scanner>>>,
+        jobs: &Arc<Mutex<HashMap<String, ScanJob>>>,
+        scanner_id: &str,
+    ) {
+        if let Ok(jobs_lock) = jobs.lock() {
+            Self::release_idle_scanner(scanners, &jobs_lock, scanner_id);
+        }
+    }
+
+    // Set a scanner back to available once no job is running on it. Only a Busy
+    // scanner is freed: one that went offline or into an error state keeps it, so a
+    // paper jam still has to be cleared with clear_jam. Called with the jobs lock
+    // held, so a job started meanwhile is either seen here or marks the scanner
+    // busy afterwards.
+    fn release_idle_scanner(
+        scanners: &Arc<Mutex<HashMap<String, Scanner>>>,
+        jobs: &HashMap<String, ScanJob>,
+        scanner_id: &str,
+    ) {
+        let in_use = jobs.values().any(|job| {
+            job.scanner_id == scanner_id
+                && matches!(
+                    job.status,
+                    JobStatus::Scanning | JobStatus::Paused | JobStatus::Processing
+                )
+        });
+        if in_use {
+            return;
+        }
+
+        if let Ok(mut scanners_lock) = scanners.lock() {
+            if let Some(scanner) = scanners_lock.get_mut(scanner_id) {
+                if matches!(scanner.status, ScannerStatus::Busy) {
+                    println!("Setting scanner {} back to available", scanner.name);
+                    scanner.status = ScannerStatus::Available;
+                }
+            }
+        }
+    }
+
+    /// Count the finished job towards its scanner's usage unless it was a preview, save it and emit its
+    /// current state as the payload of a terminal scan event
+    fn finish_job(
+        app_handle: &Arc<Mutex<Option<AppHandle>>>,
+        scanners: &Arc<Mutex<HashMap<String, Scanner>>>,
+        jobs: &Arc<Mutex<HashMap<String, ScanJob>>>,
+        usage: &Arc<Mutex<HashMap<String, ScannerMetrics>>>,
+        event: &str,
+        job_id: &str,
+    ) {
+        let job = match jobs.lock() {
+            Ok(jobs_lock) => jobs_lock.get(job_id).cloned(),
+            Err(_) => None,
+        };
+
+        if let (Some(job), Ok(mut usage_lock)) =
+            (job.as_ref().filter(|job| !job.is_preview), usage.lock())
+        {
+            usage_lock
+                .entry(job.scanner_id.clone())
+                .or_default()
+                .record(job);
+        }
+        Self::persist_state(scanners, jobs, usage);
+
+        if let Some(job) = job {
+            Self::emit_with(app_handle, event, job);
+        }
+    }
+
+    fn run_post_action(
+        action: PostAction,
+        job_id: &str,
+        jobs: &Arc<Mutex<HashMap<String, ScanJob>>>,
+    ) -> PostActionOutcome {
+        let result = Self::execute_post_action(&action, job_id, jobs);
+        if let Err(e) = &result {
+            println!("Post-action {:?} failed for job {}: {}", action, job_id, e);
+        }
+
+        PostActionOutcome {
+            action,
+            output_path: result.as_ref().ok().cloned(),
+            error: result.err(),
+            completed_at: chrono::Utc::now(),
+        }
+    }
+
+    fn execute_post_action(
+        action: &PostAction,
+        job_id: &str,
+        jobs: &Arc<Mutex<HashMap<String, ScanJob>>>,
+    ) -> Result<std::path::PathBuf, String> {
+        let (job, related_jobs) = {
+            let jobs_lock = jobs.lock().map_err(|e| e.to_string())?;
+            let job = jobs_lock
+                .get(job_id)
+                .cloned()
+                .ok_or_else(|| format!("Job with ID {} not found", job_id))?;
+
+            let related_ids: &[String] = match action {
+                PostAction::RunComposite(ids) => ids,
+                _ => &[],
+            };
+            let related_jobs = related_ids
+                .iter()
+                .map(|id| match jobs_lock.get(id) {
+                    Some(related) if matches!(related.status, JobStatus::Completed) => {
+                        Ok(related.clone())
+                    }
+                    Some(_) => Err(format!("Job {} has not completed", id)),
+                    None => Err(format!("Job with ID {} not found", id)),
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+
+            (job, related_jobs)
+        };
+
+        let output_path = job
+            .scan_result
+            .as_ref()
+            .map(|result| result.file_path.clone())
+            .ok_or("Job has no scan result")?;
+
+        match action {
+            PostAction::GenerateThumbnail => save_thumbnail(&job, &output_path),
+            PostAction::Export(destination) => Self::export_scan_file(&output_path, destination),
+            PostAction::RunComposite(_) => {
+                let mut composite_jobs = vec![job];
+                composite_jobs.extend(related_jobs);
+                save_contact_sheet(&composite_jobs, &output_path)
+            }
+            PostAction::AppendTo(target_job_id) => {
+                Self::append_scan_pages(&output_path, target_job_id, jobs)
+            }
+        }
+    }
+
+    // Merge a finished scan's pages onto the end of another job's PDF and update
+    // that job's result to match, returning the merged file
+    fn append_scan_pages(
+        source: &Path,
+        target_job_id: &str,
+        jobs: &Arc<Mutex<HashMap<String, ScanJob>>>,
+    ) -> Result<PathBuf, String> {
+        let target_path = {
+            let jobs_lock = jobs.lock().map_err(|e| e.to_string())?;
+            let target = jobs_lock
+                .get(target_job_id)
+                .ok_or_else(|| format!("Job with ID {} not found", target_job_id))?;
+            Self::appendable_pdf(target)?
+        };
+
+        let pages = append_pdf_pages(&target_path, source)?;
+        let file_size = std::fs::metadata(&target_path)
+            .map_err(|e| format!("Failed to get file size: {}", e))?
+            .len();
+        let checksum = file_checksum(&target_path)?;
+
+        let mut jobs_lock = jobs.lock().map_err(|e| e.to_string())?;
+        if let Some(target) = jobs_lock.get_mut(target_job_id) {
+            if let Some(result) = target.scan_result.as_mut() {
+                result.pages = pages;
+                result.file_size = file_size;
+                result.checksum = checksum;
+                result.page_info = page_breakdown(result)?;
+                // Recognized text no longer covers every page
+                result.ocr_text = None;
+            }
+            target.touch();
+        }
+        println!(
+            "Appended pages to job {}, now {} pages",
+            target_job_id, pages
+        );
+        Ok(target_path)
+    }
+
+    /// Rewrite a completed PDF job's file with its pages in `new_order`, a list of
+    /// every current page number, starting at 1. Returns the updated result.
+    pub fn reorder_pages(
+        &self,
+        job_id: &str,
+        new_order: &[u32],
+    ) -> Result<ScanResult, ScannerError> {
+        let job = self.get_scan_job(job_id)?;
+        let path = Self::appendable_pdf(&job).map_err(ScannerError::InvalidJobState)?;
+        reorder_pdf_pages(&path, new_order).map_err(ScannerError::InvalidSettings)?;
+
+        let file_size = std::fs::metadata(&path)
+            .map_err(|e| ScannerError::IoError(format!("Failed to get file size: {}", e)))?
+            .len();
+        let checksum = file_checksum(&path).map_err(ScannerError::IoError)?;
+
+        let result = {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            let job = jobs
+                .get_mut(job_id)
+                .ok_or_else(|| ScannerError::job_not_found(job_id))?;
+            let result = job.scan_result.as_mut().ok_or_else(Self::no_scan_result)?;
+            result.file_size = file_size;
+            result.checksum = checksum;
+            result.page_info = page_breakdown(result).map_err(ScannerError::IoError)?;
+            // Recognized text follows the old page order
+            result.ocr_text = None;
+            // The thumbnail shows what used to be the first page
+            if new_order.first() != Some(&1) {
+                if let Some(preview) = result.preview_path.take() {
+                    let _ = std::fs::remove_file(preview);
+                }
+            }
+            let result = result.clone();
+            job.touch();
+            result
+        };
+        self.persist();
+
+        println!("Reordered the pages of job {} to {:?}", job_id, new_order);
+        Ok(result)
+    }
+
+    /// Combine the PDFs of several completed jobs, in the order given, into a new
+    /// file named `output_name` in the output directory. The jobs keep their own
+    /// files; the combined file is not attached to any job.
+    pub fn merge_jobs(
+        &self,
+        job_ids: &[String],
+        output_name: &str,
+    ) -> Result<ScanResult, ScannerError> {
+        if job_ids.len() < 2 {
+            return Err(ScannerError::InvalidSettings(
+                "Merging needs at least two jobs".to_string(),
+            ));
+        }
+        let output_name =
+            Self::merged_file_name(output_name).map_err(ScannerError::InvalidSettings)?;
+
+        let jobs = job_ids
+            .iter()
+            .map(|job_id| self.get_scan_job(job_id))
+            .collect::<Result<Vec<_>, _>>()?;
+        let sources = jobs
+            .iter()
+            .map(Self::appendable_pdf)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ScannerError::InvalidJobState)?;
+
+        let output_dir = ScanGenerator::get_output_directory().map_err(ScannerError::IoError)?;
+        let output_path = output_dir.join(&output_name);
+        if output_path.exists() {
+            return Err(ScannerError::InvalidSettings(format!(
+                "{} already exists in the output directory",
+                output_name
+            )));
+        }
+
+        let merged = Self::merge_pdf_files(&sources, &output_path);
+        if merged.is_err() {
+            let _ = std::fs::remove_file(&output_path);
+        }
+        let pages = merged.map_err(ScannerError::IoError)?;
+
+        // Resolution and color mode are reported as the first job scanned them
+        let first = jobs[0]
+            .scan_result
+            .as_ref()
+            .ok_or_else(Self::no_scan_result)?;
+        let mut result = ScanResult {
+            file_path: output_path.clone(),
+            file_size: 0,
+            pages,
+            resolution: first.resolution,
+            color_mode: first.color_mode,
+            format: OutputFormat::Pdf,
+            scan_time: chrono::Utc::now(),
+            is_test_scan: false,
+            exif_embedded: false,
+            // The appended pages are not checked against the PDF/A profile
+            pdf_a_level: None,
+            upload: None,
+            preview_path: None,
+            ocr_text: None,
+            checksum: String::new(),
+            encrypted: false,
+            page_info: Vec::new(),
+            is_preview: false,
+        };
+        result.file_size = std::fs::metadata(&output_path)
+            .map_err(|e| ScannerError::IoError(format!("Failed to get file size: {}", e)))?
+            .len();
+        result.checksum = file_checksum(&output_path).map_err(ScannerError::IoError)?;
+        result.page_info = page_breakdown(&result).map_err(ScannerError::IoError)?;
+
+        println!(
+            "Merged {} jobs into {:?}, {} pages",
+            jobs.len(),
+            output_path,
+            pages
+        );
+        Ok(result)
+    }
+
+    // A plain file name for merge output, given the .pdf extension if it has none
+    fn merged_file_name(output_name: &str) -> Result<String, String> {
+        let name = output_name.trim();
+        if name.is_empty() {
+            return Err("Output name cannot be empty".to_string());
+        }
+        if name.starts_with('.') || name.contains(['/', '\\']) {
+            return Err(format!(
+                "Output name must be a file name without a directory: {}",
+                name
+            ));
+        }
+        if name.to_ascii_lowercase().ends_with(".pdf") {
+            Ok(name.to_string())
+        } else {
+            Ok(format!("{}.pdf", name))
+        }
+    }
+
+    // Copy the first PDF to the output and append the others' pages to it,
+    // returning the combined page count
+    fn merge_pdf_files(sources: &[PathBuf], output_path: &Path) -> Result<u32, String> {
+        std::fs::copy(&sources[0], output_path)
+            .map_err(|e| format!("Failed to copy {}: {}", sources[0].display(), e))?;
+        let mut pages = 0;
+        for source in &sources[1..] {
+            pages = append_pdf_pages(output_path, source)?;
+        }
+        Ok(pages)
+    }
+
+    /// A job's scan result with its size and page breakdown as the file on disk
+    /// has them now. Results are cached, so the file is only re-read after it or
+    /// the job changes; a missing file leaves the recorded result as it was.
+    pub fn get_scan_result(&self, job_id: &str) -> Result<Option<ScanResult>, ScannerError> {
+        let job = self.get_scan_job(job_id)?;
+        let Some(mut result) = job.scan_result else {
+            self.invalidate_result_cache(job_id)?;
+            return Ok(None);
+        };
+        let metadata = std::fs::metadata(&result.file_path);
+        let Ok((file_size, modified)) = metadata.and_then(|m| Ok((m.len(), m.modified()?))) else {
+            self.invalidate_result_cache(job_id)?;
+            return Ok(Some(result));
+        };
+
+        {
+            let cache = self.result_cache.lock().map_err(|e| e.to_string())?;
+            if let Some(cached) = cache.get(job_id) {
+                if cached.modified == modified && cached.job_updated_at == job.updated_at {
+                    return Ok(Some(cached.result.clone()));
+                }
+            }
+        }
+
+        result.file_size = file_size;
+        match page_breakdown(&result) {
+            Ok(page_info) => result.page_info = page_info,
+            Err(e) => println!("Failed to read pages of job {}: {}", job_id, e),
+        }
+
+        let mut cache = self.result_cache.lock().map_err(|e| e.to_string())?;
+        cache.insert(
+            job_id.to_string(),
+            CachedScanResult {
+                result: result.clone(),
+                modified,
+                job_updated_at: job.updated_at,
+            },
+        );
+        Ok(Some(result))
+    }
+
+    /// Drop a job's cached scan result so the next get_scan_result re-reads it
+    pub fn invalidate_result_cache(&self, job_id: &str) -> Result<(), ScannerError> {
+        let mut cache = self.result_cache.lock().map_err(|e| e.to_string())?;
+        cache.remove(job_id);
+        Ok(())
+    }
+
+    /// Re-hash a job's scan file and check it against the checksum recorded when it
+    /// was generated. A missing file counts as a mismatch.
+    pub fn verify_scan_result(&self, job_id: &str) -> Result<bool, ScannerError> {
+        let job = self.get_scan_job(job_id)?;
+        let result = job.scan_result.as_ref().ok_or_else(Self::no_scan_result)?;
+        if result.checksum.is_empty() {
+            return Err(ScannerError::InvalidJobState(format!(
+                "Job {} has no recorded checksum",
+                job_id
+            )));
+        }
+
+        if !result.file_path.exists() {
+            println!(
+                "Scan file for job {} is missing: {}",
+                job_id,
+                result.file_path.display()
+            );
+            return Ok(false);
+        }
+
+        let checksum = file_checksum(&result.file_path).map_err(ScannerError::IoError)?;
+        Ok(checksum == result.checksum)
+    }
+
+    /// Read part of a job's scan file, from `offset` up to `length` bytes or the
+    /// end of the file. A call returns at most MAX_SCAN_READ_BYTES so a large
+    /// file is never held in memory whole; read the rest with further calls.
+    pub fn read_scan_file(
+        &self,
+        job_id: &str,
+        offset: u64,
+        length: Option<u64>,
+    ) -> Result<ScanFileChunk, ScannerError> {
+        let result = self
+            .get_scan_job(job_id)?
+            .scan_result
+            .ok_or_else(Self::no_scan_result)?;
+
+        let mut file = std::fs::File::open(&result.file_path).map_err(|e| {
+            ScannerError::IoError(format!(
+                "Failed to open scan file {:?}: {}",
+                result.file_path, e
+            ))
+        })?;
+        let total_size = file
+            .metadata()
+            .map_err(|e| ScannerError::IoError(format!("Failed to get file size: {}", e)))?
+            .len();
+        if offset > total_size {
+            return Err(ScannerError::InvalidSettings(format!(
+                "Offset {} is past the end of the {} byte scan file",
+                offset, total_size
+            )));
+        }
+
+        let length = length
+            .unwrap_or(total_size - offset)
+            .min(Self::MAX_SCAN_READ_BYTES);
+        let mut data = Vec::new();
+        file.seek(SeekFrom::Start(offset))
+            .and_then(|_| file.take(length).read_to_end(&mut data))
+            .map_err(|e| ScannerError::IoError(format!("Failed to read scan file: {}", e)))?;
+
+        Ok(ScanFileChunk {
+            job_id: job_id.to_string(),
+            offset,
+            data,
+            total_size,
+            mime_type: result.format.mime_type().to_string(),
+        })
+    }
+
+    fn no_scan_result() -> ScannerError {
+        ScannerError::InvalidJobState("Job has no scan result".to_string())
+    }
+
+    /// Render a PNG thumbnail of the first page of a completed job and record it as
+    /// the job's preview
+    pub fn generate_preview(&self, job_id: &str) -> Result<std::path::PathBuf, ScannerError> {
+        let job = self.get_scan_job(job_id)?;
+        let output_path = job
+            .scan_result
+            .as_ref()
+            .map(|result| result.file_path.clone())
+            .ok_or_else(Self::no_scan_result)?;
+
+        let preview_path = save_thumbnail(&job, &output_path).map_err(ScannerError::IoError)?;
+
+        {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            if let Some(result) = jobs
+                .get_mut(job_id)
+                .and_then(|job| job.scan_result.as_mut())
+            {
+                result.preview_path = Some(preview_path.clone());
+            }
+        }
+        self.invalidate_result_cache(job_id)?;
+        self.persist();
+
+        Ok(preview_path)
+    }
+
+    fn export_scan_file(
+        source: &std::path::Path,
+        destination: &std::path::Path,
+    ) -> Result<std::path::PathBuf, String> {
+        // Exporting to a directory keeps the original file name
+        let target = if destination.is_dir() {
+            let file_name = source.file_name().ok_or("Scan file has no file name")?;
+            destination.join(file_name)
+        } else {
+            destination.to_path_buf()
+        };
+
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create export directory: {}", e))?;
+        }
+        std::fs::copy(source, &target).map_err(|e| format!("Failed to export scan file: {}", e))?;
+
+        Ok(target)
+    }
+
+    /// Open a scan file in the platform's viewer. Only files in the output
+    /// directory or recorded on a job's result can be opened, and only when their
+    /// content is one of the scan formats.
+    pub fn preview_scan_file(&self, path: &Path) -> Result<(), ScannerError> {
+        let target = path
+            .canonicalize()
+            .map_err(|_| ScannerError::IoError("File does not exist".to_string()))?;
+        if !target.is_file() {
+            return Err(ScannerError::IoError(format!(
+                "{} is not a file",
+                path.display()
+            )));
+        }
+        if !self.is_scan_output(&target)? {
+            return Err(ScannerError::InvalidSettings(format!(
+                "{} is not a scan output file",
+                path.display()
+            )));
+        }
+
+        let format = Self::detect_scan_format(&target).map_err(ScannerError::InvalidSettings)?;
+        println!("Opening {} as {}", target.display(), format.mime_type());
+        Self::open_in_viewer(&target)
+            .map_err(|e| ScannerError::IoError(format!("Failed to open file: {}", e)))
+    }
+
+    // Whether a resolved path lies under the default output directory or is a
+    // file recorded on one of the jobs, which may sit in a custom output directory
+    fn is_scan_output(&self, target: &Path) -> Result<bool, ScannerError> {
+        let in_output_dir = ScanGenerator::get_output_directory()
+            .and_then(|dir| dir.canonicalize().map_err(|e| e.to_string()))
+            .is_ok_and(|dir| target.starts_with(dir));
+        if in_output_dir {
+            return Ok(true);
+        }
+
+        let jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+        Ok(jobs
+            .values()
+            .flat_map(|job| job.scan_result.iter().chain(&job.partial_result))
+            .flat_map(|result| std::iter::once(&result.file_path).chain(&result.preview_path))
+            .filter_map(|path| path.canonicalize().ok())
+            .any(|path| path == target))
+    }
+
+    // The file's format from its content, which must agree with its extension so
+    // the viewer picked by extension is the one for what the file really holds
+    fn detect_scan_format(path: &Path) -> Result<OutputFormat, String> {
+        let mut header = [0u8; 8];
+        let read = std::fs::File::open(path)
+            .and_then(|mut file| std::io::Read::read(&mut file, &mut header))
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let format = OutputFormat::from_magic_bytes(&header[..read])
+            .ok_or_else(|| format!("{} is not a PDF, JPEG, PNG or TIFF file", path.display()))?;
+
+        let extension = path
+            .extension()
+            .and_then(|extension| OutputFormat::from_extension(&extension.to_string_lossy()));
+        if extension != Some(format) {
+            return Err(format!(
+                "{} holds {} data that does not match its extension",
+                path.display(),
+                format.mime_type()
+            ));
+        }
+        Ok(format)
+    }
+
+    // Hand the file to the platform's default viewer for its type, without going
+    // through a shell that would interpret characters in the path
+    fn open_in_viewer(path: &Path) -> std::io::Result<()> {
+        #[cfg(target_os = "macos")]
+        let mut command = std::process::Command::new("open");
+        #[cfg(target_os = "windows")]
+        let mut command = std::process::Command::new("explorer");
+        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+        let mut command = std::process::Command::new("xdg-open");
+
+        command.arg(path).spawn().map(|_| ())
+    }
+
+    /// Recognize the text of a completed scan and store it on the job's result
+    pub async fn ocr_scan(&self, job_id: &str) -> Result<String, ScannerError> {
+        let job = self.get_scan_job(job_id)?;
+        if !matches!(job.status, JobStatus::Completed) {
+            return Err(ScannerError::InvalidJobState(format!(
+                "Job {} is {:?}; only completed scans can be OCRed",
+                job_id, job.status
+            )));
+        }
+        let result = job.scan_result.as_ref().ok_or_else(Self::no_scan_result)?;
+
+        let text = extract_scan_text(result)
+            .await
+            .map_err(ScannerError::IoError)?;
+
+        {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            if let Some(result) = jobs
+                .get_mut(job_id)
+                .and_then(|job| job.scan_result.as_mut())
+            {
+                result.ocr_text = Some(text.clone());
+            }
+        }
+        self.invalidate_result_cache(job_id)?;
+        self.persist();
+
+        Ok(text)
+    }
+
+    pub fn set_generation_concurrency(&self, concurrency: usize) -> Result<(), ScannerError> {
+        if concurrency == 0 {
+            return Err(ScannerError::InvalidSettings(
+                "Generation concurrency must be at least 1".to_string(),
+            ));
+        }
+
+        self.generation_limiter.set_limit(concurrency);
+        println!("Generation concurrency set to {}", concurrency);
+        Ok(())
+    }
+
+    pub fn set_max_jobs(&self, max_jobs: usize) -> Result<(), ScannerError> {
+        if max_jobs == 0 {
+            return Err(ScannerError::InvalidSettings(
+                "Maximum job count must be at least 1".to_string(),
+            ));
+        }
+
+        {
+            let mut limit = self.max_jobs.lock().map_err(|e| e.to_string())?;
+            *limit = max_jobs;
+        }
+        println!("Maximum tracked jobs set to {}", max_jobs);
+
+        self.evict_finished_jobs().map_err(ScannerError::Internal)?;
+        self.persist();
+        Ok(())
+    }
+
+    /// Drop the least recently updated finished jobs until the job map fits within
+    /// the cap. Active jobs are never evicted, so the map can stay over the cap
+    /// while they run.
+    fn evict_finished_jobs(&self) -> Result<(), String> {
+        let max_jobs = *self.max_jobs.lock().map_err(|e| e.to_string())?;
+
+        let evicted: Vec<String> = {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            if jobs.len() <= max_jobs {
+                return Ok(());
             }
+
+            let mut finished: Vec<(chrono::DateTime<chrono::Utc>, String)> = jobs
+                .values()
+                .filter(|job| job.is_finished())
+                .map(|job| (job.updated_at, job.id.clone()))
+                .collect();
+            finished.sort();
+
+            let excess = jobs.len() - max_jobs;
+            finished
+                .into_iter()
+                .take(excess)
+                .map(|(_, job_id)| {
+                    jobs.remove(&job_id);
+                    job_id
+                })
+                .collect()
+        };
+
+        let mut cache = self.result_cache.lock().map_err(|e| e.to_string())?;
+        for job_id in evicted {
+            cache.remove(&job_id);
+            println!("Evicted finished job: {}", job_id);
+            self.emit_event("job-evicted", job_id);
         }
+
+        Ok(())
+    }
+
+    fn default_generation_concurrency() -> usize {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
     }
 
-    pub fn get_scan_job(&self, job_id: &str) -> Result<ScanJob, String> {
+    pub fn get_scan_job(&self, job_id: &str) -> Result<ScanJob, ScannerError> {
         let jobs = self.jobs.lock().map_err(|e| e.to_string())?;
         jobs.get(job_id)
             .cloned()
-            .ok_or_else(|| format!("Job with ID {} not found", job_id))
+            .ok_or_else(|| ScannerError::job_not_found(job_id))
     }
 
-    pub fn get_all_jobs(&self) -> Result<Vec<ScanJob>, String> {
+    /// Scanner a job was created for. The job may outlive it once the scanner is
+    /// removed, which is reported as a missing scanner rather than a missing job.
+    pub fn get_scanner_for_job(&self, job_id: &str) -> Result<Scanner, ScannerError> {
+        let scanner_id = self.get_scan_job(job_id)?.scanner_id;
+        let scanners = self.scanners.lock().map_err(|e| e.to_string())?;
+        scanners.get(&scanner_id).cloned().ok_or_else(|| {
+            ScannerError::ScannerNotFound(format!(
+                "Scanner with ID {} used by job {} no longer exists",
+                scanner_id, job_id
+            ))
+        })
+    }
+
+    /// Statuses the job has gone through with when each was entered, oldest first.
+    /// Jobs saved before transitions were recorded have an empty history.
+    pub fn get_job_history(&self, job_id: &str) -> Result<Vec<StatusTransition>, ScannerError> {
+        Ok(self.get_scan_job(job_id)?.status_history)
+    }
+
+    pub fn get_all_jobs(&self) -> Result<Vec<ScanJob>, ScannerError> {
         let jobs = self.jobs.lock().map_err(|e| e.to_string())?;
         Ok(jobs.values().cloned().collect())
     }
 
-    pub fn cancel_scan_job(&self, job_id: &str) -> Result<(), String> {
-        let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
-        let job = jobs
-            .get_mut(job_id)
-            .ok_or_else(|| format!("Job with ID {} not found", job_id))?;
+    /// Write every job, with its scan result metadata, to a JSON file oldest first
+    pub fn export_history(&self, path: &Path) -> Result<(), ScannerError> {
+        let mut history = self.get_all_jobs()?;
+        history.retain(|job| !job.is_preview);
+        history.sort_by_key(|job| job.created_at);
 
-        match job.status {
-            JobStatus::Pending | JobStatus::Scanning | JobStatus::Processing => {
-                job.status = JobStatus::Cancelled;
-                job.completed_at = Some(chrono::Utc::now());
+        let contents = serde_json::to_string_pretty(&history).map_err(|e| {
+            ScannerError::Internal(format!("Failed to serialize job history: {}", e))
+        })?;
+        std::fs::write(path, contents).map_err(|e| {
+            ScannerError::IoError(format!("Failed to write job history {:?}: {}", path, e))
+        })?;
 
-                // Set scanner back to available
-                let mut scanners = self.scanners.lock().map_err(|e| e.to_string())?;
-                if let Some(scanner) = scanners.get_mut(&job.scanner_id) {
-                    scanner.status = ScannerStatus::Available;
+        println!("Exported {} jobs to {:?}", history.len(), path);
+        Ok(())
+    }
+
+    /// Load jobs exported by export_history, skipping IDs that are already tracked.
+    /// Returns how many jobs were added.
+    pub fn import_history(&self, path: &Path) -> Result<usize, ScannerError> {
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            ScannerError::IoError(format!("Failed to read job history {:?}: {}", path, e))
+        })?;
+        let history: Vec<ScanJob> = serde_json::from_str(&contents).map_err(|e| {
+            ScannerError::InvalidSettings(format!("Failed to parse job history: {}", e))
+        })?;
+
+        let imported = {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            let mut imported = 0;
+            for mut job in history {
+                if jobs.contains_key(&job.id) {
+                    continue;
+                }
+                // No scan task exists for an imported job, so it cannot still be running
+                if matches!(
+                    job.status,
+                    JobStatus::Scanning | JobStatus::Paused | JobStatus::Processing
+                ) {
+                    job.fail("Interrupted before the history was exported".to_string());
+                }
+                jobs.insert(job.id.clone(), job);
+                imported += 1;
+            }
+            imported
+        };
+        println!("Imported {} jobs from {:?}", imported, path);
+
+        self.evict_finished_jobs().map_err(ScannerError::Internal)?;
+        self.persist();
+        Ok(imported)
+    }
+
+    pub fn cancel_scan_job(&self, job_id: &str) -> Result<(), ScannerError> {
+        let (scanner_id, was_pending) = {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            let job = jobs
+                .get_mut(job_id)
+                .ok_or_else(|| ScannerError::job_not_found(job_id))?;
+
+            let (scanner_id, was_pending) = match job.status {
+                JobStatus::Pending
+                | JobStatus::Scanning
+                | JobStatus::Paused
+                | JobStatus::Processing => {
+                    let was_pending = matches!(job.status, JobStatus::Pending);
+                    job.cancel();
+                    job.scheduled_at = None;
+                    (job.scanner_id.clone(), was_pending)
+                }
+                _ => {
+                    return Err(ScannerError::InvalidJobState(
+                        "Job cannot be cancelled in its current state".to_string(),
+                    ))
                 }
+            };
 
-                Ok(())
+            // A queued job never held the scanner
+            if !was_pending {
+                Self::release_idle_scanner(&self.scanners, &jobs, &scanner_id);
+            }
+
+            (scanner_id, was_pending)
+        };
+
+        if was_pending {
+            self.abort_schedule(job_id)?;
+        }
+
+        self.persist();
+        if was_pending {
+            self.notify_queue_changed(&scanner_id);
+        }
+        Ok(())
+    }
+
+    /// Emergency stop: cancel every pending, scanning, paused and processing job.
+    /// Busy scanners are freed once their jobs stop; offline and jammed ones keep
+    /// their status. Returns the IDs of the cancelled jobs, empty when nothing was
+    /// active.
+    pub fn cancel_all_jobs(&self) -> Result<Vec<String>, ScannerError> {
+        let mut active: Vec<(bool, String)> = {
+            let jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            jobs.values()
+                .filter(|job| {
+                    matches!(
+                        job.status,
+                        JobStatus::Pending
+                            | JobStatus::Scanning
+                            | JobStatus::Paused
+                            | JobStatus::Processing
+                    )
+                })
+                .map(|job| (!matches!(job.status, JobStatus::Pending), job.id.clone()))
+                .collect()
+        };
+        // Pending jobs go first so stopping a scan does not start the next queued one
+        active.sort();
+
+        let mut cancelled = Vec::new();
+        for (_, job_id) in active {
+            // A job can finish between the snapshot and its cancellation
+            match self.cancel_scan_job(&job_id) {
+                Ok(()) => cancelled.push(job_id),
+                Err(e) => println!("Could not cancel job {}: {}", job_id, e),
+            }
+        }
+
+        println!("Cancelled {} active job(s)", cancelled.len());
+        Ok(cancelled)
+    }
+
+    /// Prepare for the app to exit: stop the event loop and schedule timers, cancel
+    /// every running job, wait up to SHUTDOWN_GRACE_PERIOD for the scans to stop
+    /// and remove their files, then save the final state. Queued and scheduled
+    /// jobs stay pending for the next run. Returns the IDs of the cancelled jobs.
+    pub async fn shutdown(&self) -> Result<Vec<String>, ScannerError> {
+        self.shutting_down.store(true, Ordering::SeqCst);
+        self.stop_event_loop()?;
+        {
+            let mut schedules = self.schedules.lock().map_err(|e| e.to_string())?;
+            for (_, handle) in schedules.drain() {
+                handle.abort();
+            }
+        }
+
+        let running: Vec<String> = {
+            let jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            jobs.values()
+                .filter(|job| {
+                    matches!(
+                        job.status,
+                        JobStatus::Scanning | JobStatus::Paused | JobStatus::Processing
+                    )
+                })
+                .map(|job| job.id.clone())
+                .collect()
+        };
+        let mut cancelled = Vec::new();
+        for job_id in running {
+            // A job can finish between the snapshot and its cancellation
+            match self.cancel_scan_job(&job_id) {
+                Ok(()) => cancelled.push(job_id),
+                Err(e) => println!("Could not cancel job {} on shutdown: {}", job_id, e),
+            }
+        }
+
+        let started = std::time::Instant::now();
+        while self.running_scans.load(Ordering::SeqCst) > 0 {
+            if started.elapsed() >= Self::SHUTDOWN_GRACE_PERIOD {
+                println!(
+                    "{} scan(s) still stopping after {:?}, shutting down anyway",
+                    self.running_scans.load(Ordering::SeqCst),
+                    Self::SHUTDOWN_GRACE_PERIOD
+                );
+                break;
+            }
+            sleep(Self::PAUSE_POLL_INTERVAL).await;
+        }
+
+        self.save_state().map_err(ScannerError::IoError)?;
+        println!("Shut down, cancelled {} running job(s)", cancelled.len());
+        Ok(cancelled)
+    }
+
+    /// Pause a job while it is scanning, keeping its progress and its scanner
+    pub fn pause_scan_job(&self, job_id: &str) -> Result<(), ScannerError> {
+        {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            let job = jobs
+                .get_mut(job_id)
+                .ok_or_else(|| ScannerError::job_not_found(job_id))?;
+
+            if !matches!(job.status, JobStatus::Scanning) {
+                return Err(ScannerError::InvalidJobState(format!(
+                    "Job {} is {:?}; only scanning jobs can be paused",
+                    job_id, job.status
+                )));
+            }
+            job.pause();
+        }
+
+        self.persist();
+        Ok(())
+    }
+
+    /// Continue a paused job from the progress it was paused at
+    pub fn resume_scan_job(&self, job_id: &str) -> Result<(), ScannerError> {
+        {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            let job = jobs
+                .get_mut(job_id)
+                .ok_or_else(|| ScannerError::job_not_found(job_id))?;
+
+            if !matches!(job.status, JobStatus::Paused) {
+                return Err(ScannerError::InvalidJobState(format!(
+                    "Job {} is {:?}, not paused",
+                    job_id, job.status
+                )));
+            }
+            if let Some(reason) = &job.pause_reason {
+                return Err(ScannerError::InvalidJobState(format!(
+                    "Job {} was paused by its scanner ({}); clear the jam to resume it",
+                    job_id, reason
+                )));
+            }
+            job.resume();
+        }
+
+        self.persist();
+        Ok(())
+    }
+
+    /// Remove a finished job and delete its scan file and preview from disk
+    pub fn delete_job(&self, job_id: &str) -> Result<(), ScannerError> {
+        let job = self.get_scan_job(job_id)?;
+        if !job.is_finished() {
+            return Err(ScannerError::InvalidJobState(format!(
+                "Job {} is {:?} and cannot be deleted until it finishes",
+                job_id, job.status
+            )));
+        }
+
+        // Files go first so a failed delete never leaves them orphaned
+        Self::remove_job_files(&job).map_err(ScannerError::IoError)?;
+        {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            jobs.remove(job_id);
+        }
+        self.invalidate_result_cache(job_id)?;
+
+        self.persist();
+        Ok(())
+    }
+
+    /// Delete every completed, failed or cancelled job along with its files,
+    /// returning the IDs of the jobs removed
+    pub fn clear_completed_jobs(&self) -> Result<Vec<String>, ScannerError> {
+        let finished: Vec<ScanJob> = {
+            let jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            jobs.values()
+                .filter(|job| job.is_finished())
+                .cloned()
+                .collect()
+        };
+
+        let mut removed = Vec::new();
+        for job in finished {
+            // Jobs whose files cannot be deleted stay listed so they can be retried
+            if let Err(e) = Self::remove_job_files(&job) {
+                println!("Keeping job {}: {}", job.id, e);
+                continue;
+            }
+            removed.push(job.id);
+        }
+
+        {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            for job_id in &removed {
+                jobs.remove(job_id);
+            }
+        }
+        for job_id in &removed {
+            self.invalidate_result_cache(job_id)?;
+        }
+
+        self.persist();
+        Ok(removed)
+    }
+
+    fn remove_job_files(job: &ScanJob) -> Result<(), String> {
+        let results = job.scan_result.iter().chain(&job.partial_result);
+        for result in results {
+            for path in std::iter::once(&result.file_path).chain(&result.preview_path) {
+                match std::fs::remove_file(path) {
+                    Ok(()) => {}
+                    // Already gone, e.g. removed by hand
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => return Err(format!("Failed to delete {:?}: {}", path, e)),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn get_queue_status(&self) -> Result<Vec<QueueStatus>, ScannerError> {
+        let scanner_ids: Vec<String> = {
+            let scanners = self.scanners.lock().map_err(|e| e.to_string())?;
+            scanners.keys().cloned().collect()
+        };
+
+        let jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+        Ok(scanner_ids
+            .iter()
+            .map(|scanner_id| Self::build_queue_status(&jobs, scanner_id))
+            .collect())
+    }
+
+    fn build_queue_status(jobs: &HashMap<String, ScanJob>, scanner_id: &str) -> QueueStatus {
+        let mut pending: Vec<&ScanJob> = jobs
+            .values()
+            .filter(|job| job.scanner_id == scanner_id && matches!(job.status, JobStatus::Pending))
+            .collect();
+        pending.sort_by_key(|job| Self::queue_order(job));
+
+        QueueStatus {
+            scanner_id: scanner_id.to_string(),
+            pending_count: pending.len(),
+            next_job_id: pending.first().map(|job| job.id.clone()),
+        }
+    }
+
+    /// Usage counters of a scanner; all zero until one of its jobs finishes
+    pub fn get_scanner_metrics(&self, scanner_id: &str) -> Result<ScannerMetrics, ScannerError> {
+        self.get_scanner(scanner_id)?;
+        let usage = self.usage.lock().map_err(|e| e.to_string())?;
+        Ok(usage.get(scanner_id).cloned().unwrap_or_default())
+    }
+
+    /// Job counts by status, the average duration of completed jobs and the
+    /// total size of their output files. Preview scans are not counted.
+    pub fn get_job_statistics(&self) -> Result<JobStatistics, ScannerError> {
+        let jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+        let mut statistics = JobStatistics::default();
+        let mut total_duration_secs = 0.0;
+
+        for job in jobs.values().filter(|job| !job.is_preview) {
+            statistics.total += 1;
+            match job.status {
+                JobStatus::Pending => statistics.pending += 1,
+                JobStatus::Scanning | JobStatus::Paused | JobStatus::Processing => {
+                    statistics.in_progress += 1
+                }
+                JobStatus::Completed => {
+                    statistics.completed += 1;
+                    if let Some(completed_at) = job.completed_at {
+                        total_duration_secs +=
+                            (completed_at - job.created_at).num_milliseconds().max(0) as f64
+                                / 1000.0;
+                    }
+                }
+                JobStatus::Failed(_) => statistics.failed += 1,
+                JobStatus::Cancelled => statistics.cancelled += 1,
             }
-            _ => Err("Job cannot be cancelled in its current state".to_string()),
+            if let Some(result) = &job.scan_result {
+                statistics.total_bytes_generated += result.file_size;
+            }
+        }
+
+        if statistics.completed > 0 {
+            statistics.average_duration_secs =
+                Some(total_duration_secs / statistics.completed as f64);
         }
+        Ok(statistics)
+    }
+
+    fn notify_queue_changed(&self, scanner_id: &str) {
+        let status = match self.jobs.lock() {
+            Ok(jobs) => Self::build_queue_status(&jobs, scanner_id),
+            Err(_) => return,
+        };
+        self.emit_event("queue-changed", status);
     }
 
-    pub async fn add_scanner(&self, mut scanner: Scanner) -> Result<String, String> {
+    pub async fn add_scanner(&self, mut scanner: Scanner) -> Result<String, ScannerError> {
         // Validate scanner is for current system
-        let current_system = self.detect_platform();
+        let current_system = Self::detect_platform();
         if scanner.system_type != current_system {
-            return Err(format!(
+            return Err(ScannerError::InvalidSettings(format!(
                 "Scanner system type {:?} does not match current system {:?}",
                 scanner.system_type, current_system
-            ));
+            )));
         }
 
         // Generate new ID if empty
         if scanner.id.is_empty() {
             scanner.id = uuid::Uuid::new_v4().to_string();
         }
+        // Kept across discovery runs, which only replace the devices they found
+        scanner.source = ScannerSource::Manual;
 
         // Simulate device detection delay
         sleep(Duration::from_millis(300)).await;
 
-        let mut scanners = self.scanners.lock().map_err(|e| e.to_string())?;
         let scanner_id = scanner.id.clone();
-        scanners.insert(scanner_id.clone(), scanner);
+        {
+            let mut scanners = self.scanners.lock().map_err(|e| e.to_string())?;
+            scanners.insert(scanner_id.clone(), scanner);
 
-        println!(
-            "Added scanner: {} (ID: {})",
-            scanners.get(&scanner_id).unwrap().name,
-            scanner_id
-        );
+            println!(
+                "Added scanner: {} (ID: {})",
+                scanners.get(&scanner_id).unwrap().name,
+                scanner_id
+            );
+        }
+
+        self.persist();
         Ok(scanner_id)
     }
 
-    pub fn remove_scanner(&self, scanner_id: &str) -> Result<(), String> {
+    /// Register a network scanner by IP address or host name, for devices that do
+    /// not announce themselves over mDNS
+    pub async fn add_network_scanner(
+        &self,
+        name: String,
+        address: &str,
+        scanner_type: ScannerType,
+    ) -> Result<String, ScannerError> {
+        let address = address.trim();
+        validate_network_address(address).map_err(ScannerError::InvalidSettings)?;
+        {
+            let scanners = self.scanners.lock().map_err(|e| e.to_string())?;
+            if let Some(existing) = scanners
+                .values()
+                .find(|scanner| scanner.network_address.as_deref() == Some(address))
+            {
+                return Err(ScannerError::InvalidSettings(format!(
+                    "Scanner {} is already registered at {}",
+                    existing.name, address
+                )));
+            }
+        }
+
+        let mut scanner = Scanner::new(name, scanner_type, Self::detect_platform());
+        scanner.network_address = Some(address.to_string());
+        self.add_scanner(scanner).await
+    }
+
+    /// Add a manual scanner with the type, capabilities and system of an existing
+    /// one under a new name. Returns the new scanner's ID.
+    pub async fn clone_scanner(
+        &self,
+        scanner_id: &str,
+        new_name: &str,
+    ) -> Result<String, ScannerError> {
+        let new_name = new_name.trim();
+        if new_name.is_empty() {
+            return Err(ScannerError::InvalidSettings(
+                "Scanner name cannot be empty".to_string(),
+            ));
+        }
+
+        let source = self.get_scanner(scanner_id)?;
+        let mut scanner = Scanner::new(
+            new_name.to_string(),
+            source.scanner_type,
+            source.system_type,
+        );
+        scanner.capabilities = source.capabilities;
+        println!("Cloning scanner {} as {}", source.name, new_name);
+
+        self.add_scanner(scanner).await
+    }
+
+    pub fn remove_scanner(&self, scanner_id: &str) -> Result<(), ScannerError> {
         let mut scanners = self.scanners.lock().map_err(|e| e.to_string())?;
 
         // Check if scanner has active jobs
-        let active_jobs = self.get_active_jobs_for_scanner(scanner_id)?;
+        let active_jobs = self
+            .get_active_jobs_for_scanner(scanner_id)
+            .map_err(ScannerError::Internal)?;
         if !active_jobs.is_empty() {
-            return Err(format!(
+            return Err(ScannerError::ScannerUnavailable(format!(
                 "Cannot remove scanner with {} active jobs. Cancel jobs first.",
                 active_jobs.len()
-            ));
+            )));
         }
 
         match scanners.remove(scanner_id) {
             Some(scanner) => {
                 println!("Removed scanner: {} (ID: {})", scanner.name, scanner_id);
+                drop(scanners);
+                self.persist();
                 Ok(())
             }
-            None => Err(format!("Scanner with ID {} not found", scanner_id)),
+            None => Err(ScannerError::scanner_not_found(scanner_id)),
         }
     }
 
@@ -512,82 +3899,368 @@ impl ScannerService {
                 job.scanner_id == scanner_id
                     && matches!(
                         job.status,
-                        JobStatus::Pending | JobStatus::Scanning | JobStatus::Processing
+                        JobStatus::Pending
+                            | JobStatus::Scanning
+                            | JobStatus::Paused
+                            | JobStatus::Processing
                     )
             })
             .map(|job| job.id.clone())
             .collect())
     }
 
-    pub async fn simulate_scanner_events(&self) -> Result<(), String> {
+    pub async fn simulate_scanner_events(&self) -> Result<(), ScannerError> {
         // Simulate random scanner events (disconnect/reconnect)
-        let mut rng = rand::thread_rng();
+        let mut simulation = Self::lock_simulation(&self.simulation);
+        let rng = &mut simulation.rng;
 
         if rng.gen::<f32>() < 0.1 {
             // 10% chance of scanner event
+            // Sorted so a seeded simulation picks the same scanner every run
             let scanners = {
                 let scanners_lock = self.scanners.lock().map_err(|e| e.to_string())?;
-                scanners_lock.values().cloned().collect::<Vec<_>>()
+                let mut scanners = scanners_lock.values().cloned().collect::<Vec<_>>();
+                scanners.sort_by(|a, b| a.id.cmp(&b.id));
+                scanners
             };
 
             if !scanners.is_empty() {
                 let random_scanner = &scanners[rng.gen_range(0..scanners.len())];
                 let event_type = rng.gen_range(0..3);
 
-                match event_type {
-                    0 => {
-                        // Simulate scanner going offline
-                        let mut scanners_lock = self.scanners.lock().map_err(|e| e.to_string())?;
-                        if let Some(scanner) = scanners_lock.get_mut(&random_scanner.id) {
-                            scanner.status = ScannerStatus::Offline;
-                            println!("Scanner {} went offline", scanner.name);
-                        }
-                    }
-                    1 => {
-                        // Simulate scanner coming back online
-                        let mut scanners_lock = self.scanners.lock().map_err(|e| e.to_string())?;
-                        if let Some(scanner) = scanners_lock.get_mut(&random_scanner.id) {
-                            if matches!(scanner.status, ScannerStatus::Offline) {
-                                scanner.status = ScannerStatus::Available;
-                                println!("Scanner {} came back online", scanner.name);
+                let mut reconnected = false;
+                let change = {
+                    let mut scanners_lock = self.scanners.lock().map_err(|e| e.to_string())?;
+                    scanners_lock
+                        .get_mut(&random_scanner.id)
+                        .and_then(|scanner| {
+                            let previous = scanner.status.clone();
+                            match event_type {
+                                0 => {
+                                    // Simulate scanner going offline
+                                    scanner.status = ScannerStatus::Offline;
+                                    println!("Scanner {} went offline", scanner.name);
+                                }
+                                1 => {
+                                    // Simulate scanner coming back online
+                                    if matches!(scanner.status, ScannerStatus::Offline) {
+                                        scanner.status = ScannerStatus::WarmingUp;
+                                        reconnected = true;
+                                        println!("Scanner {} came back online", scanner.name);
+                                    }
+                                }
+                                _ => {
+                                    // Simulate scanner error
+                                    scanner.status =
+                                        ScannerStatus::Error(Self::PAPER_JAM.to_string());
+                                    println!("Scanner {} reported an error", scanner.name);
+                                }
                             }
-                        }
-                    }
-                    _ => {
-                        // Simulate scanner error
-                        let mut scanners_lock = self.scanners.lock().map_err(|e| e.to_string())?;
-                        if let Some(scanner) = scanners_lock.get_mut(&random_scanner.id) {
-                            scanner.status = ScannerStatus::Error("Paper jam detected".to_string());
-                            println!("Scanner {} reported an error", scanner.name);
-                        }
+                            Self::status_change(scanner, previous)
+                        })
+                };
+
+                if let Some(change) = change {
+                    self.emit_event("scanner-status-changed", change);
+                }
+                if reconnected {
+                    self.schedule_warm_up(random_scanner.id.clone());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replace the failure rates and seed of the simulation. Setting a seed, even the
+    /// one already in use, restarts its random sequence.
+    pub fn set_simulation_params(&self, params: SimulationParams) -> Result<(), ScannerError> {
+        let rates = [
+            Some(params.scan_failure_rate),
+            params.connection_failure_rate,
+            Some(params.adf_empty_rate),
+            Some(params.adf_misfeed_rate),
+        ];
+        if rates
+            .iter()
+            .flatten()
+            .any(|rate| !(0.0..=1.0).contains(rate))
+        {
+            return Err(ScannerError::InvalidSettings(
+                "Failure rates must be between 0 and 1".to_string(),
+            ));
+        }
+
+        println!("Simulation parameters set to {:?}", params);
+        *Self::lock_simulation(&self.simulation) = Simulation::new(params);
+        Ok(())
+    }
+
+    // The RNG stays usable even if a panic elsewhere poisoned its lock
+    fn lock_simulation(simulation: &Mutex<Simulation>) -> MutexGuard<'_, Simulation> {
+        simulation.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Run simulate_scanner_events every `interval` in the background, replacing any
+    /// loop that is already running
+    pub fn start_event_loop(&self, interval: Duration) -> Result<(), ScannerError> {
+        if interval.is_zero() {
+            return Err(ScannerError::InvalidSettings(
+                "Event interval must be greater than zero".to_string(),
+            ));
+        }
+
+        let service = self.clone();
+        // Tauri's runtime handle also works from app setup, outside any tokio context
+        let handle = tauri::async_runtime::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // The first tick completes immediately
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                if let Err(e) = service.simulate_scanner_events().await {
+                    println!("Failed to simulate scanner events: {}", e);
+                }
+            }
+        });
+
+        let mut event_loop = self.event_loop.lock().map_err(|e| e.to_string())?;
+        if let Some(previous) = event_loop.replace(handle) {
+            previous.abort();
+        }
+        println!("Simulating scanner events every {:?}", interval);
+        Ok(())
+    }
+
+    pub fn stop_event_loop(&self) -> Result<(), ScannerError> {
+        let mut event_loop = self.event_loop.lock().map_err(|e| e.to_string())?;
+        if let Some(handle) = event_loop.take() {
+            handle.abort();
+            println!("Stopped simulating scanner events");
+        }
+        Ok(())
+    }
+
+    // Event payload for a scanner whose status differs from `previous`
+    fn status_change(
+        scanner: &Scanner,
+        previous: ScannerStatus,
+    ) -> Option<ScannerStatusChangedEvent> {
+        (scanner.status != previous).then(|| ScannerStatusChangedEvent {
+            scanner_id: scanner.id.clone(),
+            previous,
+            status: scanner.status.clone(),
+        })
+    }
+
+    /// Re-test the connection to an offline or errored scanner and, only if the test
+    /// succeeds, let it warm up before it becomes available, returning the resulting
+    /// status. Scanners in any other status, and jammed ones, which clear_jam
+    /// recovers, are left alone.
+    pub async fn reconnect_scanner(&self, scanner_id: &str) -> Result<ScannerStatus, ScannerError> {
+        let scanner = self.get_scanner(scanner_id)?;
+        if !Self::needs_reconnect(&scanner.status) {
+            return Ok(scanner.status);
+        }
+
+        let connected = self.test_scanner_connection(scanner_id).await?;
+
+        let (status, change) = {
+            let mut scanners = self.scanners.lock().map_err(|e| e.to_string())?;
+            let scanner = scanners
+                .get_mut(scanner_id)
+                .ok_or_else(|| ScannerError::scanner_not_found(scanner_id))?;
+
+            // The scanner may have come back or been claimed while the test ran
+            if !connected || !Self::needs_reconnect(&scanner.status) {
+                println!(
+                    "Scanner {} did not reconnect, still {:?}",
+                    scanner.name, scanner.status
+                );
+                return Ok(scanner.status.clone());
+            }
+
+            let previous = std::mem::replace(&mut scanner.status, ScannerStatus::WarmingUp);
+            println!("Scanner {} reconnected, warming up", scanner.name);
+            (
+                scanner.status.clone(),
+                Self::status_change(scanner, previous),
+            )
+        };
+
+        if let Some(change) = change {
+            self.emit_event("scanner-status-changed", change);
+        }
+        self.schedule_warm_up(scanner_id.to_string());
+        Ok(status)
+    }
+
+    fn needs_reconnect(status: &ScannerStatus) -> bool {
+        match status {
+            ScannerStatus::Offline => true,
+            ScannerStatus::Error(_) => !Self::is_paper_jam(status),
+            _ => false,
+        }
+    }
+
+    /// Make a scanner that is warming up available once WARM_UP_DURATION has passed,
+    /// unless its status changed in the meantime
+    fn schedule_warm_up(&self, scanner_id: String) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            sleep(Self::WARM_UP_DURATION).await;
+
+            let change = match service.scanners.lock() {
+                Ok(mut scanners) => scanners.get_mut(&scanner_id).and_then(|scanner| {
+                    if scanner.status != ScannerStatus::WarmingUp {
+                        return None;
                     }
+                    let previous = std::mem::replace(&mut scanner.status, ScannerStatus::Available);
+                    println!("Scanner {} finished warming up", scanner.name);
+                    Self::status_change(scanner, previous)
+                }),
+                Err(e) => {
+                    println!("Failed to finish warm-up of scanner {}: {}", scanner_id, e);
+                    None
                 }
+            };
+
+            if let Some(change) = change {
+                service.emit_event("scanner-status-changed", change);
             }
+        });
+    }
+
+    // Reject a job for a scanner that cannot take it, saying why
+    fn check_accepts_jobs(scanner: &Scanner) -> Result<(), ScannerError> {
+        if scanner.status == ScannerStatus::WarmingUp {
+            return Err(ScannerError::ScannerUnavailable(format!(
+                "Scanner {} is still warming up, try again in a few seconds",
+                scanner.name
+            )));
+        }
+        if !scanner.accepts_jobs() {
+            return Err(ScannerError::ScannerUnavailable(
+                "Scanner is not available".to_string(),
+            ));
         }
+        Ok(())
+    }
+
+    pub fn reset_scanner_status(&self, scanner_id: &str) -> Result<(), ScannerError> {
+        let change = {
+            let mut scanners = self.scanners.lock().map_err(|e| e.to_string())?;
+            let scanner = scanners
+                .get_mut(scanner_id)
+                .ok_or_else(|| ScannerError::scanner_not_found(scanner_id))?;
+            let previous = std::mem::replace(&mut scanner.status, ScannerStatus::Available);
+            println!("Reset scanner {} status to Available", scanner.name);
+            Self::status_change(scanner, previous)
+        };
 
+        if let Some(change) = change {
+            self.emit_event("scanner-status-changed", change);
+        }
         Ok(())
     }
 
-    pub fn reset_scanner_status(&self, scanner_id: &str) -> Result<(), String> {
-        let mut scanners = self.scanners.lock().map_err(|e| e.to_string())?;
-        match scanners.get_mut(scanner_id) {
-            Some(scanner) => {
-                scanner.status = ScannerStatus::Available;
-                println!("Reset scanner {} status to Available", scanner.name);
-                Ok(())
+    /// Clear a paper jam, returning the scanner to Available and resuming the jobs
+    /// the jam paused from where they stopped. Returns the IDs of the resumed jobs.
+    pub fn clear_jam(&self, scanner_id: &str) -> Result<Vec<String>, ScannerError> {
+        let change = {
+            let mut scanners = self.scanners.lock().map_err(|e| e.to_string())?;
+            let scanner = scanners
+                .get_mut(scanner_id)
+                .ok_or_else(|| ScannerError::scanner_not_found(scanner_id))?;
+            if !Self::is_paper_jam(&scanner.status) {
+                return Err(ScannerError::ScannerUnavailable(format!(
+                    "Scanner {} has no paper jam to clear",
+                    scanner.name
+                )));
             }
-            None => Err(format!("Scanner with ID {} not found", scanner_id)),
+            let previous = std::mem::replace(&mut scanner.status, ScannerStatus::Available);
+            println!("Cleared paper jam on scanner {}", scanner.name);
+            Self::status_change(scanner, previous)
+        };
+        if let Some(change) = change {
+            self.emit_event("scanner-status-changed", change);
+        }
+
+        let resumed: Vec<String> = {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            jobs.values_mut()
+                .filter(|job| {
+                    job.scanner_id == scanner_id
+                        && matches!(job.status, JobStatus::Paused)
+                        && job.pause_reason.is_some()
+                })
+                .map(|job| {
+                    job.resume();
+                    job.id.clone()
+                })
+                .collect()
+        };
+        for job_id in &resumed {
+            println!("Resuming job {} after the paper jam was cleared", job_id);
         }
+
+        self.persist();
+        Ok(resumed)
     }
 
     pub fn get_system_info(&self) -> SystemInfo {
-        let current_platform = self.detect_platform();
+        let current_platform = Self::detect_platform();
         SystemInfo {
             platform: current_platform,
-            available_scanners: self.get_scanners().unwrap_or_default().len(),
+            available_scanners: self
+                .get_scanners()
+                .unwrap_or_default()
+                .iter()
+                .filter(|scanner| scanner.status != ScannerStatus::WarmingUp)
+                .count(),
             total_scanners: self.get_all_scanners().unwrap_or_default().len(),
             active_jobs: self.get_active_jobs_count(),
             scanner_api: self.get_scanner_api_info(current_platform),
+            discovery_ran: self.discovery_ran(),
+        }
+    }
+
+    /// What the simulated scanner API of the current platform supports
+    pub fn get_platform_capabilities(&self) -> PlatformCapabilities {
+        let platform = Self::detect_platform();
+        let scanner_api = self.get_scanner_api_info(platform);
+        match platform {
+            SystemType::Windows => PlatformCapabilities {
+                platform,
+                scanner_api,
+                network_discovery: false,
+                duplex: true,
+                document_feeder: true,
+                status_events: true,
+                preview_scan: true,
+                max_resolution: 4800,
+            },
+            SystemType::MacOS => PlatformCapabilities {
+                platform,
+                scanner_api,
+                network_discovery: true,
+                duplex: true,
+                document_feeder: true,
+                status_events: true,
+                preview_scan: true,
+                max_resolution: 6400,
+            },
+            // SANE has no device callbacks, so status changes are only seen by polling
+            SystemType::Linux => PlatformCapabilities {
+                platform,
+                scanner_api,
+                network_discovery: true,
+                duplex: false,
+                document_feeder: true,
+                status_events: false,
+                preview_scan: true,
+                max_resolution: 1200,
+            },
         }
     }
 
@@ -599,7 +4272,7 @@ impl ScannerService {
         }
     }
 
-    fn detect_platform(&self) -> SystemType {
+    fn detect_platform() -> SystemType {
         #[cfg(target_os = "windows")]
         return SystemType::Windows;
 
@@ -608,6 +4281,11 @@ impl ScannerService {
 
         #[cfg(target_os = "linux")]
         return SystemType::Linux;
+
+        // Other Unix-likes (FreeBSD, OpenBSD, ...) typically use SANE as well,
+        // so they are treated as Linux for discovery purposes
+        #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+        return SystemType::Linux;
     }
 
     fn get_active_jobs_count(&self) -> usize {
@@ -616,7 +4294,10 @@ impl ScannerService {
                 .filter(|job| {
                     matches!(
                         job.status,
-                        JobStatus::Pending | JobStatus::Scanning | JobStatus::Processing
+                        JobStatus::Pending
+                            | JobStatus::Scanning
+                            | JobStatus::Paused
+                            | JobStatus::Processing
                     )
                 })
                 .count()
@@ -633,6 +4314,310 @@ pub struct SystemInfo {
     pub total_scanners: usize,
     pub active_jobs: usize,
     pub scanner_api: String,
+    pub discovery_ran: bool, // False means an empty scanner list is not yet meaningful
+}
+
+/// Features the simulated scanner API of a platform offers, so the UI can hide
+/// what the platform cannot do
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PlatformCapabilities {
+    pub platform: SystemType,
+    pub scanner_api: String,
+    pub network_discovery: bool, // Finds scanners on the network, not only attached ones
+    pub duplex: bool,            // Scans both sides of a sheet in one pass
+    pub document_feeder: bool,   // Drives automatic document feeders
+    pub status_events: bool,     // Reports jams and disconnects as they happen
+    pub preview_scan: bool,      // Runs a quick low-resolution pass before the scan
+    pub max_resolution: u32,     // Highest resolution of any device on the API, in DPI
+}
+
+/// How the simulated scanning phase of a job ended
+enum ScanOutcome {
+    Scanned,
+    Cancelled,
+    Failed(String),
+}
+
+/// On-disk format of the saved scanner and job state
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct PersistedState {
+    scanners: Vec<Scanner>,
+    jobs: Vec<ScanJob>,
+    #[serde(default)]
+    usage: HashMap<String, ScannerMetrics>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ScanProgressEvent {
+    pub job_id: String,
+    pub progress: f32,
+    pub eta_secs: Option<u64>,
+    pub status: JobStatus,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ScannerStatusChangedEvent {
+    pub scanner_id: String,
+    pub previous: ScannerStatus,
+    pub status: ScannerStatus,
+}
+
+/// Failure rates and RNG seed behind the simulated scans, connections and events
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SimulationParams {
+    pub scan_failure_rate: f32, // Chance a scan fails partway with a hardware error
+    pub connection_failure_rate: Option<f32>, // None keeps the per-scanner-type rates
+    pub seed: Option<u64>,      // Makes the simulated outcomes reproducible
+    #[serde(default = "default_adf_empty_rate")]
+    pub adf_empty_rate: f32, // Chance a document feeder job finds the feeder empty
+    #[serde(default = "default_adf_misfeed_rate")]
+    pub adf_misfeed_rate: f32, // Chance the feeder pulls several sheets at once
+}
+
+fn default_adf_empty_rate() -> f32 {
+    0.03
+}
+
+fn default_adf_misfeed_rate() -> f32 {
+    0.03
+}
+
+impl Default for SimulationParams {
+    fn default() -> Self {
+        Self {
+            scan_failure_rate: 0.05,
+            connection_failure_rate: None,
+            seed: None,
+            adf_empty_rate: default_adf_empty_rate(),
+            adf_misfeed_rate: default_adf_misfeed_rate(),
+        }
+    }
+}
+
+/// Simulation parameters with the RNG they seeded
+struct Simulation {
+    params: SimulationParams,
+    rng: StdRng,
+}
+
+impl Simulation {
+    fn new(params: SimulationParams) -> Self {
+        let rng = match params.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Self { params, rng }
+    }
+}
+
+/// Semaphore limiting how many scan files are generated at once. The limit can be
+/// changed while files are generating: lowering it retires permits as the
+/// generations holding them finish, so jobs already waiting start under the new
+/// limit.
+struct GenerationLimiter {
+    semaphore: Arc<Semaphore>,
+    limits: Mutex<GenerationLimits>,
+}
+
+struct GenerationLimits {
+    limit: usize,
+    // Permits still in use that are dropped rather than returned, after a lowered limit
+    excess: usize,
+}
+
+impl GenerationLimiter {
+    fn new(limit: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(limit)),
+            limits: Mutex::new(GenerationLimits { limit, excess: 0 }),
+        }
+    }
+
+    async fn acquire(self: &Arc<Self>) -> Result<GenerationPermit, AcquireError> {
+        let permit = Arc::clone(&self.semaphore).acquire_owned().await?;
+        Ok(GenerationPermit {
+            limiter: Arc::clone(self),
+            permit: Some(permit),
+        })
+    }
+
+    fn set_limit(&self, limit: usize) {
+        let mut limits = self.lock_limits();
+        if limit > limits.limit {
+            // Permits waiting to be retired are kept before any new ones are added
+            let raise = limit - limits.limit;
+            let kept = raise.min(limits.excess);
+            limits.excess -= kept;
+            self.semaphore.add_permits(raise - kept);
+        } else {
+            // Free permits go at once, the ones in use when they are released
+            let lower = limits.limit - limit;
+            limits.excess += lower - self.semaphore.forget_permits(lower);
+        }
+        limits.limit = limit;
+    }
+
+    fn lock_limits(&self) -> MutexGuard<'_, GenerationLimits> {
+        self.limits.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A generation slot, given back to its limiter when dropped
+struct GenerationPermit {
+    limiter: Arc<GenerationLimiter>,
+    permit: Option<OwnedSemaphorePermit>,
+}
+
+impl Drop for GenerationPermit {
+    fn drop(&mut self) {
+        let Some(permit) = self.permit.take() else {
+            return;
+        };
+        // Decided under the lock so a concurrent set_limit sees a consistent count
+        let mut limits = self.limiter.lock_limits();
+        if limits.excess > 0 {
+            limits.excess -= 1;
+            permit.forget();
+        } else {
+            drop(permit);
+        }
+    }
+}
+
+/// An IPv4 network given in CIDR notation
+#[derive(Debug, Clone, Copy)]
+struct Ipv4Subnet {
+    network: u32,
+    prefix: u32,
+}
+
+impl Ipv4Subnet {
+    // Anything wider than a /16 would take far too long to browse
+    const MIN_PREFIX: u32 = 16;
+    const MAX_PREFIX: u32 = 30;
+
+    fn parse(subnet: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid subnet '{}', expected e.g. 192.168.1.0/24", subnet);
+        let (address, prefix) = subnet.trim().split_once('/').ok_or_else(invalid)?;
+        let address: Ipv4Addr = address.parse().map_err(|_| invalid())?;
+        let prefix: u32 = prefix.parse().map_err(|_| invalid())?;
+        if !(Self::MIN_PREFIX..=Self::MAX_PREFIX).contains(&prefix) {
+            return Err(format!(
+                "Subnet prefix must be between /{} and /{}",
+                Self::MIN_PREFIX,
+                Self::MAX_PREFIX
+            ));
+        }
+        Ok(Self {
+            network: u32::from(address) & Self::mask(prefix),
+            prefix,
+        })
+    }
+
+    fn mask(prefix: u32) -> u32 {
+        u32::MAX << (32 - prefix)
+    }
+
+    fn contains(&self, address: Ipv4Addr) -> bool {
+        u32::from(address) & Self::mask(self.prefix) == self.network
+    }
+
+    // The nth host address, leaving out the network and broadcast addresses
+    fn host(&self, number: u32) -> Option<Ipv4Addr> {
+        let hosts = (1u32 << (32 - self.prefix)) - 2;
+        (1..=hosts)
+            .contains(&number)
+            .then(|| Ipv4Addr::from(self.network + number))
+    }
+}
+
+impl std::fmt::Display for Ipv4Subnet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", Ipv4Addr::from(self.network), self.prefix)
+    }
+}
+
+// An IP address or a DNS host name
+fn validate_network_address(address: &str) -> Result<(), String> {
+    if address.parse::<IpAddr>().is_ok() {
+        return Ok(());
+    }
+    let valid_host = !address.is_empty()
+        && address.len() <= 253
+        && address.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    if valid_host {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid network address '{}', expected an IP address or host name",
+            address
+        ))
+    }
+}
+
+/// A scan result as get_scan_result last read it, valid until the job or its file changes
+struct CachedScanResult {
+    result: ScanResult,
+    modified: std::time::SystemTime,
+    job_updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ConnectionTestResult {
+    pub success: bool,
+    pub latency_ms: u64,
+    pub message: String, // Round trip on success, the reason on failure
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct QueueStatus {
+    pub scanner_id: String,
+    pub pending_count: usize,
+    pub next_job_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct JobStatistics {
+    pub total: usize,
+    pub pending: usize,
+    pub in_progress: usize, // Scanning, paused or processing
+    pub completed: usize,
+    pub failed: usize,
+    pub cancelled: usize,
+    // From creation to completion, over completed jobs only; None until one completes
+    pub average_duration_secs: Option<f64>,
+    pub total_bytes_generated: u64,
+}
+
+/// How much a scanner has been used, over the completed and failed jobs it ran.
+/// Cancelled jobs are not counted.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct ScannerMetrics {
+    pub total_jobs: u64,
+    pub total_pages: u64, // Including pages salvaged from failed jobs
+    pub total_bytes: u64,
+    pub failure_count: u64,
+    pub last_used: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl ScannerMetrics {
+    fn record(&mut self, job: &ScanJob) {
+        self.total_jobs += 1;
+        if matches!(job.status, JobStatus::Failed(_)) {
+            self.failure_count += 1;
+        }
+        for result in job.scan_result.iter().chain(&job.partial_result) {
+            self.total_pages += result.pages as u64;
+            self.total_bytes += result.file_size;
+        }
+        self.last_used = Some(job.updated_at);
+    }
 }
 
 impl Default for ScannerService {
@@ -640,3 +4625,530 @@ impl Default for ScannerService {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_platform_returns_host_platform() {
+        let expected = if cfg!(target_os = "windows") {
+            SystemType::Windows
+        } else if cfg!(target_os = "macos") {
+            SystemType::MacOS
+        } else {
+            // Linux, and the Unix-likes discovered through SANE like it
+            SystemType::Linux
+        };
+
+        assert_eq!(ScannerService::detect_platform(), expected);
+    }
+
+    #[tokio::test]
+    async fn lowered_generation_limit_applies_to_permits_in_use() {
+        let limiter = Arc::new(GenerationLimiter::new(2));
+        let first = limiter.acquire().await.unwrap();
+        let second = limiter.acquire().await.unwrap();
+
+        limiter.set_limit(1);
+        drop(first);
+        assert_eq!(limiter.semaphore.available_permits(), 0);
+        drop(second);
+        assert_eq!(limiter.semaphore.available_permits(), 1);
+
+        let held = limiter.acquire().await.unwrap();
+        limiter.set_limit(3);
+        assert_eq!(limiter.semaphore.available_permits(), 2);
+        drop(held);
+        assert_eq!(limiter.semaphore.available_permits(), 3);
+    }
+
+    // A scanner in the given status, shared the way the service holds its scanners
+    fn shared_scanner(status: ScannerStatus) -> (String, Arc<Mutex<HashMap<String, Scanner>>>) {
+        let mut scanner = Scanner::new(
+            "Test Scanner".to_string(),
+            ScannerType::Flatbed,
+            SystemType::Linux,
+        );
+        scanner.status = status;
+        let scanner_id = scanner.id.clone();
+        let scanners = HashMap::from([(scanner_id.clone(), scanner)]);
+        (scanner_id, Arc::new(Mutex::new(scanners)))
+    }
+
+    fn running_job(scanner_id: &str) -> ScanJob {
+        let mut job = ScanJob::new(
+            scanner_id.to_string(),
+            DocumentType::Text,
+            ScanSettings::default(),
+        );
+        job.start_scanning();
+        job
+    }
+
+    fn scanner_status(
+        scanners: &Arc<Mutex<HashMap<String, Scanner>>>,
+        scanner_id: &str,
+    ) -> ScannerStatus {
+        scanners.lock().unwrap()[scanner_id].status.clone()
+    }
+
+    #[test]
+    fn scanner_stays_busy_while_another_job_runs_on_it() {
+        let (scanner_id, scanners) = shared_scanner(ScannerStatus::Busy);
+        let mut first = running_job(&scanner_id);
+        let second = running_job(&scanner_id);
+        let second_id = second.id.clone();
+        first.complete();
+        let jobs = Arc::new(Mutex::new(HashMap::from([
+            (first.id.clone(), first),
+            (second_id.clone(), second),
+        ])));
+
+        ScannerService::release_scanner(&scanners, &jobs, &scanner_id);
+        assert_eq!(scanner_status(&scanners, &scanner_id), ScannerStatus::Busy);
+
+        jobs.lock().unwrap().get_mut(&second_id).unwrap().complete();
+        ScannerService::release_scanner(&scanners, &jobs, &scanner_id);
+        assert_eq!(
+            scanner_status(&scanners, &scanner_id),
+            ScannerStatus::Available
+        );
+    }
+
+    #[test]
+    fn finishing_a_job_leaves_a_jammed_or_offline_scanner_alone() {
+        for status in [
+            ScannerStatus::Error(ScannerService::PAPER_JAM.to_string()),
+            ScannerStatus::Offline,
+        ] {
+            let (scanner_id, scanners) = shared_scanner(status.clone());
+            let mut job = running_job(&scanner_id);
+            job.complete();
+            let jobs = Arc::new(Mutex::new(HashMap::from([(job.id.clone(), job)])));
+
+            ScannerService::release_scanner(&scanners, &jobs, &scanner_id);
+            assert_eq!(scanner_status(&scanners, &scanner_id), status);
+        }
+    }
+
+    #[test]
+    fn settings_that_lost_their_pdf_password_are_refused() {
+        let scanner = Scanner::new(
+            "Test Scanner".to_string(),
+            ScannerType::Flatbed,
+            SystemType::Linux,
+        );
+        let job = ScanJob::new(
+            scanner.id.clone(),
+            DocumentType::Text,
+            ScanSettings {
+                pdf_password: Some("secret".to_string()),
+                ..ScanSettings::default()
+            },
+        );
+        assert!(ScannerService::validate_job_request(
+            &scanner,
+            &job.document_type,
+            &job.scan_settings
+        )
+        .is_ok());
+
+        // Saved state and profiles keep everything but the password
+        let saved: ScanSettings =
+            serde_json::from_str(&serde_json::to_string(&job.scan_settings).unwrap()).unwrap();
+        assert_eq!(saved.pdf_password, None);
+        let error = ScannerService::validate_job_request(&scanner, &job.document_type, &saved)
+            .expect_err("settings without their password should be refused");
+        assert_eq!(error, ScannerService::PDF_PASSWORD_LOST);
+    }
+
+    // A service without the state earlier tests saved, whose scans never fail at random
+    fn test_service() -> ScannerService {
+        let service = ScannerService::new();
+        service.scanners.lock().unwrap().clear();
+        service.jobs.lock().unwrap().clear();
+        service.usage.lock().unwrap().clear();
+        service
+            .set_simulation_params(SimulationParams {
+                scan_failure_rate: 0.0,
+                adf_empty_rate: 0.0,
+                adf_misfeed_rate: 0.0,
+                ..SimulationParams::default()
+            })
+            .unwrap();
+        service
+    }
+
+    // A service with one available scanner registered
+    fn service_with_scanner() -> (ScannerService, String) {
+        let service = test_service();
+        let scanner = Scanner::new(
+            "Test Scanner".to_string(),
+            ScannerType::Flatbed,
+            ScannerService::detect_platform(),
+        );
+        let scanner_id = scanner.id.clone();
+        service
+            .scanners
+            .lock()
+            .unwrap()
+            .insert(scanner_id.clone(), scanner);
+        (service, scanner_id)
+    }
+
+    #[tokio::test]
+    async fn document_type_change_is_validated_like_a_new_job() {
+        let (service, scanner_id) = service_with_scanner();
+        let settings = ScanSettings {
+            barcode_payload: Some("INV-42".to_string()),
+            ..ScanSettings::default()
+        };
+        let job_id = service
+            .create_scan_job(scanner_id, DocumentType::Barcode, settings, None)
+            .await
+            .unwrap();
+
+        let error = service
+            .set_job_document_type(&job_id, DocumentType::Text)
+            .expect_err("a barcode payload needs the Barcode document type");
+        assert!(matches!(error, ScannerError::InvalidSettings(_)));
+        assert_eq!(
+            service.get_scan_job(&job_id).unwrap().document_type,
+            DocumentType::Barcode
+        );
+    }
+
+    #[tokio::test]
+    async fn auto_paper_follows_a_changed_document_type() {
+        let (service, scanner_id) = service_with_scanner();
+        let settings = ScanSettings {
+            paper_size: PaperSize::Auto,
+            ..ScanSettings::default()
+        };
+        let job_id = service
+            .create_scan_job(scanner_id, DocumentType::Text, settings, None)
+            .await
+            .unwrap();
+
+        service
+            .set_job_document_type(&job_id, DocumentType::BusinessCard)
+            .unwrap();
+        let mut job = service.get_scan_job(&job_id).unwrap();
+        assert_eq!(job.scan_settings.paper_size, PaperSize::Auto);
+
+        job.start_scanning();
+        assert_eq!(
+            job.scan_settings.paper_size,
+            PaperSize::for_document_type(DocumentType::BusinessCard)
+        );
+    }
+
+    #[tokio::test]
+    async fn discovery_keeps_units_of_the_same_model_apart() {
+        let service = test_service();
+        let model = format!("Test Model {}", uuid::Uuid::new_v4());
+        let discover = |serial_number| {
+            service.merge_discovered(Scanner::discovered(
+                model.clone(),
+                ScannerType::Flatbed,
+                ScannerService::detect_platform(),
+                serial_number,
+            ))
+        };
+
+        let first = discover("SN-1").unwrap();
+        let second = discover("SN-2").unwrap();
+        assert_ne!(first.id, second.id);
+        assert_eq!(discover("SN-1").unwrap().id, first.id);
+
+        let scanners = service.scanners.lock().unwrap();
+        let units = scanners
+            .values()
+            .filter(|scanner| scanner.name == model)
+            .count();
+        assert_eq!(units, 2);
+    }
+
+    #[tokio::test]
+    async fn reconnect_leaves_available_and_jammed_scanners_alone() {
+        let (service, scanner_id) = service_with_scanner();
+        for status in [
+            ScannerStatus::Available,
+            ScannerStatus::Error(ScannerService::PAPER_JAM.to_string()),
+        ] {
+            service
+                .scanners
+                .lock()
+                .unwrap()
+                .get_mut(&scanner_id)
+                .unwrap()
+                .status = status.clone();
+
+            assert_eq!(
+                service.reconnect_scanner(&scanner_id).await.unwrap(),
+                status
+            );
+            assert_eq!(service.get_scanner(&scanner_id).unwrap().status, status);
+        }
+    }
+
+    // Settings that save scans under the test directory
+    fn test_settings() -> ScanSettings {
+        ScanSettings {
+            output_dir: Some(
+                std::env::temp_dir()
+                    .join("scanner-tool-tests")
+                    .join("scans"),
+            ),
+            ..ScanSettings::default()
+        }
+    }
+
+    async fn start_job(
+        service: &ScannerService,
+        scanner_id: &str,
+        settings: ScanSettings,
+    ) -> String {
+        let job_id = service
+            .create_scan_job(scanner_id.to_string(), DocumentType::Text, settings, None)
+            .await
+            .unwrap();
+        service.start_scan_job(&job_id).await.unwrap();
+        job_id
+    }
+
+    // Wait for the job to reach a state the check accepts, failing the test if it
+    // takes longer than any simulated scan could
+    async fn wait_for_job(
+        service: &ScannerService,
+        job_id: &str,
+        check: impl Fn(&ScanJob) -> bool,
+    ) -> ScanJob {
+        let started = std::time::Instant::now();
+        loop {
+            let job = service.get_scan_job(job_id).unwrap();
+            if check(&job) {
+                return job;
+            }
+            assert!(
+                started.elapsed() < Duration::from_secs(30),
+                "job {} stuck at {:?}",
+                job_id,
+                job.status
+            );
+            sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    fn set_scanner_status(service: &ScannerService, scanner_id: &str, status: ScannerStatus) {
+        let mut scanners = service.scanners.lock().unwrap();
+        scanners.get_mut(scanner_id).unwrap().status = status;
+    }
+
+    #[tokio::test]
+    async fn queued_job_starts_once_the_scanner_is_free() {
+        let (service, scanner_id) = service_with_scanner();
+        let first = start_job(&service, &scanner_id, test_settings()).await;
+        let second = start_job(&service, &scanner_id, test_settings()).await;
+
+        // One job at a time by default, so the second waits its turn
+        wait_for_job(&service, &first, |job| job.progress > 0.0).await;
+        assert!(matches!(
+            service.get_scan_job(&second).unwrap().status,
+            JobStatus::Pending
+        ));
+        assert_eq!(
+            service.get_scanner(&scanner_id).unwrap().status,
+            ScannerStatus::Busy
+        );
+
+        wait_for_job(&service, &first, |job| {
+            matches!(job.status, JobStatus::Completed)
+        })
+        .await;
+        wait_for_job(&service, &second, |job| {
+            matches!(job.status, JobStatus::Completed)
+        })
+        .await;
+        assert_eq!(
+            service.get_scanner(&scanner_id).unwrap().status,
+            ScannerStatus::Available
+        );
+
+        service.delete_job(&first).unwrap();
+        service.delete_job(&second).unwrap();
+    }
+
+    #[tokio::test]
+    async fn scanner_stays_busy_while_a_paused_job_holds_it() {
+        let (service, scanner_id) = service_with_scanner();
+        service.set_max_concurrent(&scanner_id, 2).unwrap();
+        let first = start_job(&service, &scanner_id, test_settings()).await;
+        let second = start_job(&service, &scanner_id, test_settings()).await;
+
+        wait_for_job(&service, &second, |job| {
+            matches!(job.status, JobStatus::Scanning)
+        })
+        .await;
+        service.pause_scan_job(&second).unwrap();
+        wait_for_job(&service, &first, |job| {
+            matches!(job.status, JobStatus::Completed)
+        })
+        .await;
+        assert_eq!(
+            service.get_scanner(&scanner_id).unwrap().status,
+            ScannerStatus::Busy
+        );
+
+        service.resume_scan_job(&second).unwrap();
+        wait_for_job(&service, &second, |job| {
+            matches!(job.status, JobStatus::Completed)
+        })
+        .await;
+        assert_eq!(
+            service.get_scanner(&scanner_id).unwrap().status,
+            ScannerStatus::Available
+        );
+
+        service.delete_job(&first).unwrap();
+        service.delete_job(&second).unwrap();
+    }
+
+    #[tokio::test]
+    async fn completion_does_not_clear_a_jam() {
+        let (service, scanner_id) = service_with_scanner();
+        // Post-processing keeps the job in Processing long enough to jam it there
+        let settings = ScanSettings {
+            post_processing: PostProcessing {
+                deskew: true,
+                auto_crop: true,
+                contrast: true,
+            },
+            ..test_settings()
+        };
+        let job_id = start_job(&service, &scanner_id, settings).await;
+
+        wait_for_job(&service, &job_id, |job| {
+            matches!(job.status, JobStatus::Processing)
+        })
+        .await;
+        let jam = ScannerStatus::Error(ScannerService::PAPER_JAM.to_string());
+        set_scanner_status(&service, &scanner_id, jam.clone());
+        wait_for_job(&service, &job_id, |job| {
+            matches!(job.status, JobStatus::Completed)
+        })
+        .await;
+        assert_eq!(service.get_scanner(&scanner_id).unwrap().status, jam);
+
+        service.clear_jam(&scanner_id).unwrap();
+        assert_eq!(
+            service.get_scanner(&scanner_id).unwrap().status,
+            ScannerStatus::Available
+        );
+        service.delete_job(&job_id).unwrap();
+    }
+
+    #[tokio::test]
+    async fn jammed_job_waits_for_the_jam_to_be_cleared() {
+        let (service, scanner_id) = service_with_scanner();
+        let job_id = start_job(&service, &scanner_id, test_settings()).await;
+
+        wait_for_job(&service, &job_id, |job| {
+            matches!(job.status, JobStatus::Scanning)
+        })
+        .await;
+        set_scanner_status(
+            &service,
+            &scanner_id,
+            ScannerStatus::Error(ScannerService::PAPER_JAM.to_string()),
+        );
+        let job = wait_for_job(&service, &job_id, |job| {
+            matches!(job.status, JobStatus::Paused)
+        })
+        .await;
+        assert_eq!(job.pause_reason.as_deref(), Some(ScannerService::PAPER_JAM));
+        assert!(service.resume_scan_job(&job_id).is_err());
+
+        assert_eq!(
+            service.clear_jam(&scanner_id).unwrap(),
+            vec![job_id.clone()]
+        );
+        wait_for_job(&service, &job_id, |job| {
+            matches!(job.status, JobStatus::Completed)
+        })
+        .await;
+        assert_eq!(
+            service.get_scanner(&scanner_id).unwrap().status,
+            ScannerStatus::Available
+        );
+        service.delete_job(&job_id).unwrap();
+    }
+
+    #[tokio::test]
+    async fn cancelling_a_scan_frees_its_scanner() {
+        let (service, scanner_id) = service_with_scanner();
+        let job_id = start_job(&service, &scanner_id, test_settings()).await;
+
+        wait_for_job(&service, &job_id, |job| {
+            matches!(job.status, JobStatus::Scanning)
+        })
+        .await;
+        service.cancel_scan_job(&job_id).unwrap();
+        assert!(matches!(
+            service.get_scan_job(&job_id).unwrap().status,
+            JobStatus::Cancelled
+        ));
+        assert_eq!(
+            service.get_scanner(&scanner_id).unwrap().status,
+            ScannerStatus::Available
+        );
+    }
+
+    #[tokio::test]
+    async fn scan_longer_than_its_timeout_fails() {
+        let (service, scanner_id) = service_with_scanner();
+        // Simulated scans take at least three seconds
+        let settings = ScanSettings {
+            timeout_secs: 1,
+            ..test_settings()
+        };
+        let job_id = start_job(&service, &scanner_id, settings).await;
+
+        let job = wait_for_job(&service, &job_id, |job| {
+            matches!(job.status, JobStatus::Failed(_))
+        })
+        .await;
+        assert!(matches!(job.status, JobStatus::Failed(error) if error == "Scan timed out"));
+        assert_eq!(
+            service.get_scanner(&scanner_id).unwrap().status,
+            ScannerStatus::Available
+        );
+    }
+
+    #[test]
+    fn validate_settings_enforces_quality_range() {
+        let scanner = Scanner::new(
+            "Test Scanner".to_string(),
+            ScannerType::Flatbed,
+            SystemType::Linux,
+        );
+        let with_quality = |quality| ScanSettings {
+            quality,
+            ..ScanSettings::default()
+        };
+
+        for quality in [1, 100] {
+            assert!(
+                ScannerService::validate_settings(&scanner, &with_quality(quality)).is_ok(),
+                "quality {} should be accepted",
+                quality
+            );
+        }
+        for quality in [0, 101] {
+            let error = ScannerService::validate_settings(&scanner, &with_quality(quality))
+                .expect_err("quality outside 1..=100 should be rejected");
+            assert!(error.contains("Quality"), "unexpected error: {}", error);
+        }
+    }
+}
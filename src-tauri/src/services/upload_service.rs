@@ -0,0 +1,78 @@
+use crate::domain::*;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, LOCATION};
+use std::path::Path;
+use std::time::Duration;
+
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Check an upload target before a job is queued so bad URLs fail fast
+pub fn validate_upload_target(target: &UploadTarget) -> Result<(), String> {
+    let url = reqwest::Url::parse(&target.url)
+        .map_err(|e| format!("Invalid upload URL {}: {}", target.url, e))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!(
+            "Upload URL must use http or https, got {}",
+            url.scheme()
+        ));
+    }
+
+    build_headers(target)?;
+    Ok(())
+}
+
+/// Send a generated scan file to a remote destination as the raw request body
+pub async fn upload_scan_file(path: &Path, target: &UploadTarget) -> Result<UploadResult, String> {
+    let body = tokio::fs::read(path)
+        .await
+        .map_err(|e| format!("Failed to read scan file for upload: {}", e))?;
+    let headers = build_headers(target)?;
+
+    let client = reqwest::Client::builder()
+        .timeout(UPLOAD_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let request = match target.method {
+        UploadMethod::Post => client.post(&target.url),
+        UploadMethod::Put => client.put(&target.url),
+    };
+
+    let response = request
+        .headers(headers)
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| format!("Network error uploading to {}: {}", target.url, e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!(
+            "Upload to {} was rejected with HTTP {}",
+            target.url, status
+        ));
+    }
+
+    let location = response
+        .headers()
+        .get(LOCATION)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
+
+    Ok(UploadResult {
+        url: target.url.clone(),
+        status: status.as_u16(),
+        location,
+    })
+}
+
+fn build_headers(target: &UploadTarget) -> Result<HeaderMap, String> {
+    let mut headers = HeaderMap::new();
+    for (name, value) in &target.headers {
+        let header_name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| format!("Invalid upload header name {}: {}", name, e))?;
+        let header_value = HeaderValue::from_str(value)
+            .map_err(|e| format!("Invalid value for upload header {}: {}", name, e))?;
+        headers.insert(header_name, header_value);
+    }
+    Ok(headers)
+}